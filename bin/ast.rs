@@ -0,0 +1,77 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{env, fs, process};
+
+use toy_lang::{diagnostic, dump, lexer, parser, source_map::SourceMap};
+
+// 退出码跟 toy 相同，参考 BSD sysexits.h
+const EXIT_USAGE: i32 = 64;
+const EXIT_SYNTAX_ERROR: i32 = 65;
+const EXIT_NO_INPUT: i32 = 66;
+
+// 输出脚本的 AST，默认为 JSON 格式，`--sexp` 输出 S 表达式，
+// 存在语法错误时仍然输出（包含错误节点的）AST，并在标准错误输出各个错误
+fn main() {
+    let mut is_s_expression = false;
+    let mut file_path: Option<String> = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => is_s_expression = false,
+            "--sexp" => is_s_expression = true,
+            _ if file_path.is_none() => file_path = Some(arg),
+            _ => {
+                eprintln!("unexpected argument: {}", arg);
+                process::exit(EXIT_USAGE);
+            }
+        }
+    }
+
+    let file_path = match file_path {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: ast [--json | --sexp] path_to_script_file");
+            process::exit(EXIT_USAGE);
+        }
+    };
+
+    let text = match fs::read_to_string(&file_path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("failed to read file {}: {}", file_path, e);
+            process::exit(EXIT_NO_INPUT);
+        }
+    };
+
+    let mut source_map = SourceMap::new();
+    let file_id = source_map.add_file(&file_path, &text);
+
+    let tokens = match lexer::tokenize_file(&text, file_id) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("{}", diagnostic::render(&e, &source_map, file_id));
+            process::exit(EXIT_SYNTAX_ERROR);
+        }
+    };
+
+    let (ast, errors) = parser::parse_with_recovery(&tokens);
+    if is_s_expression {
+        println!("{}", dump::to_s_expression(&ast));
+    } else {
+        println!("{}", dump::to_json(&ast));
+    }
+
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors
+            .iter()
+            .map(|error| diagnostic::render(error, &source_map, file_id))
+            .collect();
+        eprintln!("{}", messages.join("\n\n"));
+        process::exit(EXIT_SYNTAX_ERROR);
+    }
+}
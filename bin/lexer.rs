@@ -5,9 +5,6 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
-
-use toy_lang::lexer;
-
 fn main() {
     println!("Hello, world!");
 }
@@ -0,0 +1,77 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    io::{self, Write},
+    thread,
+};
+
+use toy_lang::repl::{self, Repl};
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+
+// 跟 toy 一样在栈空间较大的线程里执行
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+fn main() {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("failed to spawn the interpreter thread")
+        .join()
+        .ok();
+}
+
+// 读取一行或多行输入并执行，输入未完成时（比如存在未闭合的括号）提示继续输入，
+// 继续输入时输入空行则强制执行已输入的内容，Ctrl+D 退出
+fn run() {
+    let mut repl = Repl::new();
+    let mut buffer = String::new();
+
+    loop {
+        print!(
+            "{}",
+            if buffer.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            }
+        );
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => {
+                println!();
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("failed to read input: {}", e);
+                break;
+            }
+        }
+
+        let is_blank = line.trim().is_empty();
+        if buffer.is_empty() && is_blank {
+            continue;
+        }
+
+        buffer.push_str(&line);
+        if !is_blank && repl::is_incomplete(&buffer) {
+            continue;
+        }
+
+        match repl.eval(&buffer) {
+            Ok(text) if text.is_empty() => {}
+            Ok(text) => println!("{}", text),
+            Err(message) => eprintln!("{}", message),
+        }
+        buffer.clear();
+    }
+}
@@ -0,0 +1,117 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{env, fs, process, rc::Rc, thread};
+
+use toy_lang::{
+    diagnostic,
+    interpreter::{Interpreter, Limits},
+    lexer, parser,
+    source_map::SourceMap,
+    value::Value,
+};
+
+const USAGE: &str = "\
+usage: toy [options] path_to_script_file [args...]
+
+options:
+  --max-call-depth <n>  limit the depth of nested function calls
+  --max-steps <n>       limit the number of evaluation steps
+  --max-value-size <n>  limit the number of elements (or chars) of a new value";
+
+// 解释器递归求值，默认的调用深度限制在默认大小的线程里也能生效，
+// 这里使用较大的栈空间，使得通过 --max-call-depth 提高限制时也不会耗尽调用栈
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+// 退出码参考 BSD sysexits.h
+const EXIT_USAGE: i32 = 64;
+const EXIT_SYNTAX_ERROR: i32 = 65;
+const EXIT_NO_INPUT: i32 = 66;
+const EXIT_RUNTIME_ERROR: i32 = 70;
+
+fn main() {
+    let handle = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("failed to spawn the interpreter thread");
+    if handle.join().is_err() {
+        process::exit(EXIT_RUNTIME_ERROR);
+    }
+}
+
+// 执行脚本，脚本的值不是 `()` 时输出该值，
+// 脚本文件之后的命令行参数以字符串列表的形式保存在全局变量 args
+fn run() {
+    let mut args = env::args().skip(1).peekable();
+    let mut limits = Limits::default();
+    while let Some(option) = args.next_if(|arg| arg.starts_with("--")) {
+        let value = args.next().and_then(|value| value.parse().ok());
+        match (option.as_str(), value) {
+            ("--max-call-depth", Some(value)) => limits.max_call_depth = Some(value),
+            ("--max-steps", Some(value)) => limits.max_steps = Some(value as u64),
+            ("--max-value-size", Some(value)) => limits.max_value_size = Some(value),
+            _ => {
+                eprintln!("invalid option {}\n\n{}", option, USAGE);
+                process::exit(EXIT_USAGE);
+            }
+        }
+    }
+
+    let file_path = match args.next() {
+        Some(path) => path,
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
+    let script_args: Vec<Value> = args.map(|arg| Value::String(Rc::new(arg))).collect();
+
+    let text = match fs::read_to_string(&file_path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("failed to read file {}: {}", file_path, e);
+            process::exit(EXIT_NO_INPUT);
+        }
+    };
+
+    let mut source_map = SourceMap::new();
+    let file_id = source_map.add_file(&file_path, &text);
+
+    let tokens = match lexer::tokenize_file(&text, file_id) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("{}", diagnostic::render(&e, &source_map, file_id));
+            process::exit(EXIT_SYNTAX_ERROR);
+        }
+    };
+
+    let (program, errors) = parser::parse_with_recovery(&tokens);
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors
+            .iter()
+            .map(|error| diagnostic::render(error, &source_map, file_id))
+            .collect();
+        eprintln!("{}", messages.join("\n\n"));
+        process::exit(EXIT_SYNTAX_ERROR);
+    }
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_limits(limits);
+    interpreter.define("args", Value::List(Rc::new(script_args)));
+
+    match interpreter.eval_program(&program) {
+        Ok(value) => {
+            if !value.is_unit() {
+                println!("{}", value);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", diagnostic::render(&e, &source_map, file_id));
+            process::exit(EXIT_RUNTIME_ERROR);
+        }
+    }
+}
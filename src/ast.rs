@@ -0,0 +1,1451 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use core::fmt;
+
+use crate::error::Error;
+use crate::token::Location;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Expression(Expression),
+    Function(FunctionDeclaration),
+    Struct(StructDeclaration),
+    Union(UnionDeclaration),
+    Enum(EnumDeclaration),
+    Const(ConstDeclaration),
+    Trait(TraitDeclaration),
+    Impl(ImplDeclaration),
+    Namespace(NamespaceDeclaration),
+    Use(UseDeclaration),
+    Alias(AliasDeclaration),
+    Error(ErrorNode),
+}
+
+// 语法错误恢复时，出错的语句或者表达式被替换为错误节点，
+// location 为出错记号的位置，错误发生在文件末尾时为最后一个记号的位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorNode {
+    pub message: &'static str,
+    pub location: Location,
+}
+
+impl ErrorNode {
+    pub fn to_error(&self) -> Error {
+        Error::ParserError(self.message, Some(self.location.clone()))
+    }
+}
+
+// `function name (Type a, Type b = default) type Type which {...} {...}`
+// 或者 `function name (...) type Type = expression`，
+// 模式匹配函数（is_pattern）的参数可以是任意模式，
+// 特性里的空函数 `empty function name (...) type Type` 没有函数体，body 为 None，
+// 泛型函数 `function name<T, U> (...)` 的泛型参数记录在 generic_parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDeclaration {
+    pub name: Identifier,
+    pub is_pattern: bool,
+    pub generic_parameters: Vec<Identifier>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<DataType>,
+    pub which: Vec<WhichEntry>,
+    pub body: Option<Expression>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub data_type: Option<DataType>,
+    pub pattern: Pattern,
+    pub default_value: Option<Expression>,
+    pub location: Location,
+}
+
+// which 块里的一项类型说明，比如 `F: sign (Int x) type Int`、`T: limit Display, Ordered`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhichEntry {
+    pub name: Identifier,
+    pub constraint: TypeConstraint,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeConstraint {
+    DataType(DataType),
+    Limit(Vec<DataType>),
+}
+
+// `trait Name type (ItemType, Other = String) { empty function ... function ... }`，
+// 关联类型的 data_type 为默认类型
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitDeclaration {
+    pub name: Identifier,
+    pub associated_types: Vec<AssociatedType>,
+    pub functions: Vec<FunctionDeclaration>,
+    pub location: Location,
+}
+
+// `impl DataType trait Name type (ItemType = Type) { function ... }`，
+// 关联类型的 data_type 为具体类型
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImplDeclaration {
+    pub data_type: DataType,
+    pub trait_name: Option<DataType>,
+    pub associated_types: Vec<AssociatedType>,
+    pub functions: Vec<FunctionDeclaration>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociatedType {
+    pub name: Identifier,
+    pub data_type: Option<DataType>,
+    pub location: Location,
+}
+
+// `namespace name { statement ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceDeclaration {
+    pub name: Identifier,
+    pub statements: Vec<Statement>,
+    pub location: Location,
+}
+
+// `use foo::bar`、`use foo::*`、`use foo::{bar, baz::*}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseDeclaration {
+    pub tree: UseTree,
+    pub location: Location,
+}
+
+// 导入树，path 是各层的路径前缀，
+// 对于 Single，path 的最后一项即被导入的名称；
+// 对于 Glob 和 Group，path 在分组里面可以为空，比如 `foo::{*, bar}` 里的 `*`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseTree {
+    pub path: Vec<Identifier>,
+    pub kind: UseKind,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UseKind {
+    Single,
+    Glob,
+    Group(Vec<UseTree>),
+}
+
+// `alias Name = DataType`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasDeclaration {
+    pub name: Identifier,
+    pub data_type: DataType,
+    pub location: Location,
+}
+
+// `struct User { Int id, String name }`，成员之间使用逗号或者换行分隔
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDeclaration {
+    pub name: Identifier,
+    pub fields: Vec<FieldDeclaration>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDeclaration {
+    pub data_type: DataType,
+    pub name: Identifier,
+    pub location: Location,
+}
+
+// `union Name { A {Int x}, B }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionDeclaration {
+    pub name: Identifier,
+    pub members: Vec<UnionMember>,
+    pub location: Location,
+}
+
+// 不带数据的成员 fields 为空
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionMember {
+    pub name: Identifier,
+    pub fields: Vec<FieldDeclaration>,
+    pub location: Location,
+}
+
+// `enum Color { Red Green Blue }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDeclaration {
+    pub name: Identifier,
+    pub members: Vec<Identifier>,
+    pub location: Location,
+}
+
+// `const Int Code = 123` 或者 `const Int Code { Ok = 123, Moved = 301 }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDeclaration {
+    pub data_type: DataType,
+    pub name: Identifier,
+    pub value: ConstValue,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Single(Expression),
+    Group(Vec<ConstMember>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstMember {
+    pub name: Identifier,
+    pub value: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Literal(Literal),
+    Identifier(Identifier),
+    Path(Path),
+    Tuple(TupleExpression),
+    List(ListExpression),
+    Array(ListExpression),
+    Matrix(MatrixExpression),
+    Map(MapExpression),
+    Range(Box<RangeExpression>),
+    Sequence(SequenceExpression),
+    Spread(Box<SpreadExpression>),
+    Block(BlockExpression),
+    Let(Box<LetExpression>),
+    If(Box<IfExpression>),
+    Branch(Box<BranchExpression>),
+    Match(Box<MatchExpression>),
+    ForLet(Box<ForLetExpression>),
+    ForIn(Box<ForInExpression>),
+    Each(Box<EachExpression>),
+    Next(Box<NextExpression>),
+    AnonymousFunction(Box<AnonymousFunction>),
+    Struct(StructExpression),
+    Prefix(Box<PrefixExpression>),
+    Postfix(Box<PostfixExpression>),
+    Binary(Box<BinaryExpression>),
+    Call(Box<CallExpression>),
+    Member(Box<MemberExpression>),
+    TupleIndex(Box<TupleIndexExpression>),
+    Index(Box<IndexExpression>),
+    Slice(Box<SliceExpression>),
+    Error(ErrorNode),
+}
+
+// 字面量
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    pub value: LiteralValue,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    Integer(i64),
+    Boolean(bool),
+    Char(char),
+    String(String),
+    TemplateString(String),
+    HashString(String),
+    Regexp(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    pub name: String,
+    pub location: Location,
+}
+
+// 命名空间路径 `foo::bar::baz`，表达式里的路径至少包含两段，
+// 结构体模式的名称也使用路径，比如 `Point {...}` 以及联合体成员 `Option::Some {...}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub segments: Vec<Identifier>,
+    pub location: Location,
+}
+
+// 数据类型，比如 `Int`、`String`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataType {
+    Named(NamedDataType),
+    Sign(Box<SignDataType>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedDataType {
+    pub name: Identifier,
+    pub generic_arguments: Vec<DataType>,
+    pub location: Location,
+}
+
+// 函数签名 `sign (Type name, Type) type Type`，参数名称可以省略
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignDataType {
+    pub parameters: Vec<SignParameter>,
+    pub return_type: Option<DataType>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignParameter {
+    pub data_type: DataType,
+    pub name: Option<Identifier>,
+}
+
+// 元组 `(a, b)`、`(a,)`、`()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleExpression {
+    pub elements: Vec<Expression>,
+    pub location: Location,
+}
+
+// 列表 `[a, b, c]` 和数组 `#[a, b, c]`，元素可以是重组表达式 `...a`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListExpression {
+    pub elements: Vec<Expression>,
+    pub location: Location,
+}
+
+// 矩阵 `![1, 2, 3\n4, 5, 6]`，行之间使用换行分隔，每行的元素数量相同
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixExpression {
+    pub rows: Vec<Vec<Expression>>,
+    pub location: Location,
+}
+
+// 映射 `{name: value, ...other}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapExpression {
+    pub entries: Vec<MapEntry>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapEntry {
+    Pair(MapPair),
+    Spread(SpreadExpression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapPair {
+    pub name: Identifier,
+    pub value: Expression,
+    pub location: Location,
+}
+
+// 范围 `[start..end]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeExpression {
+    pub start: Expression,
+    pub end: Expression,
+    pub location: Location,
+}
+
+// 数列 `[one, two, ..end]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceExpression {
+    pub elements: Vec<Expression>,
+    pub end: Box<Expression>,
+    pub location: Location,
+}
+
+// 重组 `...value`，只能位于列表、数组和映射里面
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadExpression {
+    pub value: Expression,
+    pub location: Location,
+}
+
+// 结构体实例化 `User {1, "foo"}`、`User {id: 1, name: "foo"}`，
+// 或者省略结构体名称的 `{1, "foo"}`，按位置实例化时成员的 name 为 None
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructExpression {
+    pub name: Option<Identifier>,
+    pub fields: Vec<FieldValue>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValue {
+    pub name: Option<Identifier>,
+    pub value: Expression,
+}
+
+// 表达式块 `{...}`，最后一个表达式的值作为块的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockExpression {
+    pub expressions: Vec<Expression>,
+    pub location: Location,
+}
+
+// `let pattern = value` 或者 `let pattern match value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetExpression {
+    pub pattern: Pattern,
+    pub value: Expression,
+    pub is_match: bool,
+    pub location: Location,
+}
+
+// `if condition where ... then consequent else alternative`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfExpression {
+    pub condition: Expression,
+    pub where_clause: Option<Expression>,
+    pub consequent: Expression,
+    pub alternative: Option<Expression>,
+    pub location: Location,
+}
+
+// `branch where ... { case condition where ...: body, default: body }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchExpression {
+    pub where_clause: Option<Expression>,
+    pub cases: Vec<BranchCase>,
+    pub default: Option<Expression>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchCase {
+    pub condition: Expression,
+    pub where_clause: Option<Expression>,
+    pub body: Expression,
+    pub location: Location,
+}
+
+// `match subject where ... { case pattern, pattern to ... only ... where ...: body, default: body }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchExpression {
+    pub subject: Expression,
+    pub where_clause: Option<Expression>,
+    pub cases: Vec<MatchCase>,
+    pub default: Option<Expression>,
+    pub location: Location,
+}
+
+// 多值匹配时 patterns 有多个元素，其中任意一个匹配即可
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCase {
+    pub patterns: Vec<Pattern>,
+    pub clauses: PatternClauses,
+    pub body: Expression,
+    pub location: Location,
+}
+
+// 模式后面的 `to`、`only` 和 `where` 子表达式
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PatternClauses {
+    pub to: Option<Identifier>,
+    pub only: Option<Expression>,
+    pub where_clause: Option<Expression>,
+}
+
+// `for let pattern = initial body`，body 里面可以使用 `next value` 更新变量的值并再次执行 body
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForLetExpression {
+    pub pattern: Pattern,
+    pub initial: Expression,
+    pub body: Expression,
+    pub location: Location,
+}
+
+// `for let pattern in iterable mix let pattern in iterable body`，返回最后一次执行 body 的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForInExpression {
+    pub bindings: Vec<InBinding>,
+    pub body: Expression,
+    pub location: Location,
+}
+
+// `each let pattern in iterable mix let pattern in iterable body`，返回由每次执行 body 的值组成的列表
+#[derive(Debug, Clone, PartialEq)]
+pub struct EachExpression {
+    pub bindings: Vec<InBinding>,
+    pub body: Expression,
+    pub location: Location,
+}
+
+// `let pattern in iterable`，多个 binding 之间使用 mix 连接
+#[derive(Debug, Clone, PartialEq)]
+pub struct InBinding {
+    pub pattern: Pattern,
+    pub iterable: Expression,
+    pub location: Location,
+}
+
+// `next value`，只能位于 for let 的 body 里面
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextExpression {
+    pub value: Expression,
+    pub location: Location,
+}
+
+// 匿名函数 `(Type name) type Type => expression`、`(name) => expression` 或者 `name => expression`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnonymousFunction {
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<DataType>,
+    pub body: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Wildcard(Location),
+    Identifier(Identifier),
+    Literal(Literal),
+    Tuple(TuplePattern),
+    List(ListPattern),
+    Array(ListPattern),
+    Struct(StructPattern),
+    Rest(RestPattern),
+    Into(IntoPattern),
+    Regular(RegularPattern),
+    Template(TemplatePattern),
+    Within(WithinPattern),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuplePattern {
+    pub elements: Vec<Pattern>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListPattern {
+    pub elements: Vec<Pattern>,
+    pub location: Location,
+}
+
+// `User{id, name: user_name, ...rest}`，省略结构体名称时 name 为 None
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructPattern {
+    pub name: Option<Path>,
+    pub fields: Vec<FieldPattern>,
+    pub rest: Option<RestPattern>,
+    pub location: Location,
+}
+
+// 不带冒号的成员（is_shorthand）会把成员的值绑定到同名标识符，比如
+// `name`、`name only ...`、`city within [...]`，
+// 带冒号的成员则使用冒号后面的模式匹配成员的值，比如 `addr: Addr {...}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPattern {
+    pub name: Identifier,
+    pub pattern: Option<Pattern>,
+    pub is_shorthand: bool,
+    pub clauses: PatternClauses,
+    pub location: Location,
+}
+
+// `...rest` 或者 `...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestPattern {
+    pub name: Option<Identifier>,
+    pub location: Location,
+}
+
+// `into Type name`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntoPattern {
+    pub data_type: DataType,
+    pub name: Identifier,
+    pub location: Location,
+}
+
+// `regular /.../ [name, name]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularPattern {
+    pub regexp: Expression,
+    pub names: Vec<Identifier>,
+    pub location: Location,
+}
+
+// template `...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplatePattern {
+    pub template: String,
+    pub location: Location,
+}
+
+// `within [...]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithinPattern {
+    pub range: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixExpression {
+    pub operator: PrefixOperator,
+    pub operand: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixOperator {
+    Negative, // -
+}
+
+// 后置一元运算 `value^`、`value?`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostfixExpression {
+    pub operator: PostfixOperator,
+    pub operand: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostfixOperator {
+    Cast,   // ^
+    Unwrap, // ?
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryExpression {
+    pub operator: BinaryOperator,
+    pub left: Expression,
+    pub right: Expression,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Forward,            // >>
+    Pipe,               // |
+    LogicOr,            // ||
+    LogicAnd,           // &&
+    Equal,              // ==
+    NotEqual,           // !=
+    GreaterThan,        // >
+    GreaterThanOrEqual, // >=
+    LessThan,           // <
+    LessThanOrEqual,    // <=
+    Concat,             // ++
+    Add,                // +
+    Subtract,           // -
+    Multiply,           // *
+    Divide,             // /
+    UnwrapOr,           // ??
+    Combine,            // &
+}
+
+// 函数调用 `name(a, b)`，或者泛型参数具体化的调用 `name<Type>(a, b)`，
+// 中置调用 `a :name: b` 和前置调用 `!name (a, b)` 也都转换为 `name(a, b)`，
+// 关键字参数 `name(a, b=1)` 必须位于位置参数之后
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallExpression {
+    pub callee: Expression,
+    pub generic_arguments: Vec<DataType>,
+    pub arguments: Vec<Expression>,
+    pub keyword_arguments: Vec<KeywordArgument>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordArgument {
+    pub name: Identifier,
+    pub value: Expression,
+    pub location: Location,
+}
+
+// 对象成员 `object.name`，方法调用 `object.name(...)` 是以成员为被调用者的函数调用
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberExpression {
+    pub object: Expression,
+    pub property: Identifier,
+    pub location: Location,
+}
+
+// 元组元素 `tuple.0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleIndexExpression {
+    pub object: Expression,
+    pub index: usize,
+    pub location: Location,
+}
+
+// 索引 `object[index]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexExpression {
+    pub object: Expression,
+    pub index: Expression,
+    pub location: Location,
+}
+
+// 框选 `object[start:end]`，开始和结束位置都可以省略
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceExpression {
+    pub object: Expression,
+    pub start: Option<Expression>,
+    pub end: Option<Expression>,
+    pub location: Location,
+}
+
+impl Statement {
+    pub fn location(&self) -> &Location {
+        match self {
+            Statement::Expression(e) => e.location(),
+            Statement::Function(s) => &s.location,
+            Statement::Struct(s) => &s.location,
+            Statement::Union(s) => &s.location,
+            Statement::Enum(s) => &s.location,
+            Statement::Const(s) => &s.location,
+            Statement::Trait(s) => &s.location,
+            Statement::Impl(s) => &s.location,
+            Statement::Namespace(s) => &s.location,
+            Statement::Use(s) => &s.location,
+            Statement::Alias(s) => &s.location,
+            Statement::Error(s) => &s.location,
+        }
+    }
+}
+
+impl Expression {
+    pub fn location(&self) -> &Location {
+        match self {
+            Expression::Literal(e) => &e.location,
+            Expression::Identifier(e) => &e.location,
+            Expression::Path(e) => &e.location,
+            Expression::Tuple(e) => &e.location,
+            Expression::List(e) => &e.location,
+            Expression::Array(e) => &e.location,
+            Expression::Matrix(e) => &e.location,
+            Expression::Map(e) => &e.location,
+            Expression::Range(e) => &e.location,
+            Expression::Sequence(e) => &e.location,
+            Expression::Spread(e) => &e.location,
+            Expression::Block(e) => &e.location,
+            Expression::Let(e) => &e.location,
+            Expression::If(e) => &e.location,
+            Expression::Branch(e) => &e.location,
+            Expression::Match(e) => &e.location,
+            Expression::ForLet(e) => &e.location,
+            Expression::ForIn(e) => &e.location,
+            Expression::Each(e) => &e.location,
+            Expression::Next(e) => &e.location,
+            Expression::AnonymousFunction(e) => &e.location,
+            Expression::Struct(e) => &e.location,
+            Expression::Prefix(e) => &e.location,
+            Expression::Postfix(e) => &e.location,
+            Expression::Binary(e) => &e.location,
+            Expression::Call(e) => &e.location,
+            Expression::Member(e) => &e.location,
+            Expression::TupleIndex(e) => &e.location,
+            Expression::Index(e) => &e.location,
+            Expression::Slice(e) => &e.location,
+            Expression::Error(e) => &e.location,
+        }
+    }
+}
+
+impl DataType {
+    pub fn location(&self) -> &Location {
+        match self {
+            DataType::Named(t) => &t.location,
+            DataType::Sign(t) => &t.location,
+        }
+    }
+}
+
+impl Pattern {
+    pub fn location(&self) -> &Location {
+        match self {
+            Pattern::Wildcard(location) => location,
+            Pattern::Identifier(p) => &p.location,
+            Pattern::Literal(p) => &p.location,
+            Pattern::Tuple(p) => &p.location,
+            Pattern::List(p) => &p.location,
+            Pattern::Array(p) => &p.location,
+            Pattern::Struct(p) => &p.location,
+            Pattern::Rest(p) => &p.location,
+            Pattern::Into(p) => &p.location,
+            Pattern::Regular(p) => &p.location,
+            Pattern::Template(p) => &p.location,
+            Pattern::Within(p) => &p.location,
+        }
+    }
+}
+
+// 以下 Display 实现输出单行的 toy 源代码，二元和一元运算均会添加括号，
+// 主要用于调试和单元测试
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.statements.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Expression(e) => write!(f, "{}", e),
+            Statement::Function(s) => write!(f, "{}", s),
+            Statement::Struct(s) => write!(f, "{}", s),
+            Statement::Union(s) => write!(f, "{}", s),
+            Statement::Enum(s) => write!(f, "{}", s),
+            Statement::Const(s) => write!(f, "{}", s),
+            Statement::Trait(s) => write!(f, "{}", s),
+            Statement::Impl(s) => write!(f, "{}", s),
+            Statement::Namespace(s) => write!(f, "{}", s),
+            Statement::Use(s) => write!(f, "use {}", s.tree),
+            Statement::Alias(s) => write!(f, "alias {} = {}", s.name, s.data_type),
+            Statement::Error(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for ErrorNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<error: {}>", self.message)
+    }
+}
+
+impl fmt::Display for NamespaceDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "namespace {} {{{}}}",
+            self.name,
+            join(&self.statements, "; ")
+        )
+    }
+}
+
+impl fmt::Display for UseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut segments: Vec<String> = self.path.iter().map(|s| s.to_string()).collect();
+        match &self.kind {
+            UseKind::Single => {}
+            UseKind::Glob => segments.push("*".to_string()),
+            UseKind::Group(items) => segments.push(format!("{{{}}}", join(items, ", "))),
+        }
+        write!(f, "{}", segments.join("::"))
+    }
+}
+
+fn format_associated_types(associated_types: &[AssociatedType]) -> String {
+    match associated_types {
+        [] => "".to_string(),
+        [single] => format!(" type {}", single),
+        _ => format!(" type ({})", join(associated_types, ", ")),
+    }
+}
+
+impl fmt::Display for TraitDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "trait {}{} {{{}}}",
+            self.name,
+            format_associated_types(&self.associated_types),
+            join(&self.functions, "; ")
+        )
+    }
+}
+
+impl fmt::Display for ImplDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "impl {}", self.data_type)?;
+        if let Some(trait_name) = &self.trait_name {
+            write!(f, " trait {}", trait_name)?;
+        }
+        write!(
+            f,
+            "{} {{{}}}",
+            format_associated_types(&self.associated_types),
+            join(&self.functions, "; ")
+        )
+    }
+}
+
+impl fmt::Display for AssociatedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data_type {
+            Some(data_type) => write!(f, "{} = {}", self.name, data_type),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl fmt::Display for StructDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "struct {} {{{}}}", self.name, join(&self.fields, ", "))
+    }
+}
+
+impl fmt::Display for FieldDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.data_type, self.name)
+    }
+}
+
+impl fmt::Display for UnionDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "union {} {{{}}}", self.name, join(&self.members, ", "))
+    }
+}
+
+impl fmt::Display for UnionMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fields.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} {{{}}}", self.name, join(&self.fields, ", "))
+        }
+    }
+}
+
+impl fmt::Display for EnumDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "enum {} {{{}}}", self.name, join(&self.members, ", "))
+    }
+}
+
+impl fmt::Display for ConstDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "const {} {}", self.data_type, self.name)?;
+        match &self.value {
+            ConstValue::Single(value) => write!(f, " = {}", value),
+            ConstValue::Group(members) => write!(f, " {{{}}}", join(members, ", ")),
+        }
+    }
+}
+
+impl fmt::Display for ConstMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.name, self.value)
+    }
+}
+
+impl fmt::Display for FunctionDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_pattern {
+            write!(f, "pattern ")?;
+        }
+        if self.body.is_none() {
+            write!(f, "empty ")?;
+        }
+        write!(f, "function {}", self.name)?;
+        if !self.generic_parameters.is_empty() {
+            write!(f, "<{}>", join(&self.generic_parameters, ", "))?;
+        }
+        write!(f, " ({})", join(&self.parameters, ", "))?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, " type {}", return_type)?;
+        }
+        if !self.which.is_empty() {
+            write!(f, " which {{{}}}", join(&self.which, ", "))?;
+        }
+        match &self.body {
+            Some(Expression::Block(body)) => write!(f, " {}", body),
+            Some(body) => write!(f, " = {}", body),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(data_type) = &self.data_type {
+            write!(f, "{} ", data_type)?;
+        }
+        write!(f, "{}", self.pattern)?;
+        if let Some(default_value) = &self.default_value {
+            write!(f, " = {}", default_value)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WhichEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.constraint {
+            TypeConstraint::DataType(data_type) => write!(f, "{}: {}", self.name, data_type),
+            TypeConstraint::Limit(traits) => {
+                write!(f, "{}: limit {}", self.name, join(traits, ", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Literal(e) => write!(f, "{}", e),
+            Expression::Identifier(e) => write!(f, "{}", e),
+            Expression::Path(e) => write!(f, "{}", e),
+            Expression::Tuple(e) => write!(f, "{}", e),
+            Expression::List(e) => write!(f, "{}", e),
+            Expression::Array(e) => write!(f, "#{}", e),
+            Expression::Matrix(e) => write!(f, "{}", e),
+            Expression::Map(e) => write!(f, "{{{}}}", join(&e.entries, ", ")),
+            Expression::Range(e) => write!(f, "[{}..{}]", e.start, e.end),
+            Expression::Sequence(e) => {
+                write!(f, "[{}, ..{}]", join(&e.elements, ", "), e.end)
+            }
+            Expression::Spread(e) => write!(f, "{}", e),
+            Expression::Block(e) => write!(f, "{}", e),
+            Expression::Let(e) => write!(f, "{}", e),
+            Expression::If(e) => write!(f, "{}", e),
+            Expression::Branch(e) => write!(f, "{}", e),
+            Expression::Match(e) => write!(f, "{}", e),
+            Expression::ForLet(e) => write!(f, "{}", e),
+            Expression::ForIn(e) => write!(f, "{}", e),
+            Expression::Each(e) => write!(f, "{}", e),
+            Expression::Next(e) => write!(f, "{}", e),
+            Expression::AnonymousFunction(e) => write!(f, "{}", e),
+            Expression::Struct(e) => write!(f, "{}", e),
+            Expression::Prefix(e) => write!(f, "{}", e),
+            Expression::Postfix(e) => write!(f, "{}", e),
+            Expression::Binary(e) => write!(f, "{}", e),
+            Expression::Call(e) => write!(f, "{}", e),
+            Expression::Member(e) => write!(f, "{}.{}", e.object, e.property),
+            Expression::TupleIndex(e) => write!(f, "{}.{}", e.object, e.index),
+            Expression::Index(e) => write!(f, "{}[{}]", e.object, e.index),
+            Expression::Error(e) => write!(f, "{}", e),
+            Expression::Slice(e) => {
+                write!(f, "{}[", e.object)?;
+                if let Some(start) = &e.start {
+                    write!(f, "{}", start)?;
+                }
+                write!(f, ":")?;
+                if let Some(end) = &e.end {
+                    write!(f, "{}", end)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            LiteralValue::Integer(value) => write!(f, "{}", value),
+            LiteralValue::Boolean(value) => write!(f, "{}", value),
+            LiteralValue::Char(value) => write!(f, "'{}'", escape(&value.to_string(), '\'')),
+            LiteralValue::String(value) => write!(f, "\"{}\"", escape(value, '"')),
+            LiteralValue::TemplateString(value) => write!(f, "`{}`", value),
+            LiteralValue::HashString(value) => write!(f, "#{}", value),
+            LiteralValue::Regexp(value) => write!(f, "/{}/", value.replace('/', "\\/")),
+        }
+    }
+}
+
+// 转义字符串或者字符字面量里的特殊字符，quote 为包围字面量的引号
+pub(crate) fn escape(value: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            '\\' => escaped.push_str("\\\\"),
+            _ if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", join(&self.segments, "::"))
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Named(t) if t.generic_arguments.is_empty() => write!(f, "{}", t.name),
+            DataType::Named(t) => write!(f, "{}<{}>", t.name, join(&t.generic_arguments, ", ")),
+            DataType::Sign(t) => {
+                write!(f, "sign ({})", join(&t.parameters, ", "))?;
+                if let Some(return_type) = &t.return_type {
+                    write!(f, " type {}", return_type)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for SignParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} {}", self.data_type, name),
+            None => write!(f, "{}", self.data_type),
+        }
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T], separator: &str) -> String {
+    let strings: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+    strings.join(separator)
+}
+
+impl fmt::Display for TupleExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.elements.len() == 1 {
+            write!(f, "({},)", self.elements[0])
+        } else {
+            write!(f, "({})", join(&self.elements, ", "))
+        }
+    }
+}
+
+impl fmt::Display for ListExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", join(&self.elements, ", "))
+    }
+}
+
+// 矩阵的行之间使用分号（等同于换行）分隔
+impl fmt::Display for MatrixExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self.rows.iter().map(|row| join(row, ", ")).collect();
+        write!(f, "![{}]", rows.join("; "))
+    }
+}
+
+impl fmt::Display for MapEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapEntry::Pair(p) => write!(f, "{}: {}", p.name, p.value),
+            MapEntry::Spread(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for SpreadExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "...{}", self.value)
+    }
+}
+
+impl fmt::Display for StructExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{} ", name)?;
+        }
+
+        // 省略名称且只有一个成员时添加逗号，以避免跟表达式块混淆
+        if self.name.is_none() && self.fields.len() == 1 && self.fields[0].name.is_none() {
+            write!(f, "{{{},}}", self.fields[0])
+        } else {
+            write!(f, "{{{}}}", join(&self.fields, ", "))
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}: {}", name, self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+impl fmt::Display for BlockExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}}", join(&self.expressions, "; "))
+    }
+}
+
+impl fmt::Display for LetExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = if self.is_match { "match" } else { "=" };
+        write!(f, "let {} {} {}", self.pattern, operator, self.value)
+    }
+}
+
+impl fmt::Display for IfExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if {}", self.condition)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+        write!(f, " then {}", self.consequent)?;
+        if let Some(alternative) = &self.alternative {
+            write!(f, " else {}", alternative)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BranchExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "branch")?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+
+        let mut cases: Vec<String> = self.cases.iter().map(|c| c.to_string()).collect();
+        if let Some(default) = &self.default {
+            cases.push(format!("default: {}", default));
+        }
+        write!(f, " {{{}}}", cases.join("; "))
+    }
+}
+
+impl fmt::Display for BranchCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "case {}", self.condition)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+        write!(f, ": {}", self.body)
+    }
+}
+
+impl fmt::Display for MatchExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "match {}", self.subject)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+
+        let mut cases: Vec<String> = self.cases.iter().map(|c| c.to_string()).collect();
+        if let Some(default) = &self.default {
+            cases.push(format!("default: {}", default));
+        }
+        write!(f, " {{{}}}", cases.join("; "))
+    }
+}
+
+impl fmt::Display for MatchCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "case {}{}: {}",
+            join(&self.patterns, ", "),
+            self.clauses,
+            self.body
+        )
+    }
+}
+
+impl fmt::Display for ForLetExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "for let {} = {} {}",
+            self.pattern, self.initial, self.body
+        )
+    }
+}
+
+impl fmt::Display for ForInExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "for {} {}", join(&self.bindings, " mix "), self.body)
+    }
+}
+
+impl fmt::Display for EachExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "each {} {}", join(&self.bindings, " mix "), self.body)
+    }
+}
+
+impl fmt::Display for InBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "let {} in {}", self.pattern, self.iterable)
+    }
+}
+
+impl fmt::Display for NextExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "next {}", self.value)
+    }
+}
+
+impl fmt::Display for AnonymousFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", join(&self.parameters, ", "))?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, " type {}", return_type)?;
+        }
+        write!(f, " => {}", self.body)
+    }
+}
+
+impl fmt::Display for PatternClauses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(to) = &self.to {
+            write!(f, " to {}", to)?;
+        }
+        if let Some(only) = &self.only {
+            write!(f, " only {}", only)?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " where {}", where_clause)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Wildcard(_) => write!(f, "_"),
+            Pattern::Identifier(p) => write!(f, "{}", p),
+            Pattern::Literal(p) => write!(f, "{}", p),
+            Pattern::Tuple(p) => {
+                if p.elements.len() == 1 {
+                    write!(f, "({},)", p.elements[0])
+                } else {
+                    write!(f, "({})", join(&p.elements, ", "))
+                }
+            }
+            Pattern::List(p) => write!(f, "[{}]", join(&p.elements, ", ")),
+            Pattern::Array(p) => write!(f, "#[{}]", join(&p.elements, ", ")),
+            Pattern::Struct(p) => write!(f, "{}", p),
+            Pattern::Rest(p) => write!(f, "{}", p),
+            Pattern::Into(p) => write!(f, "into {} {}", p.data_type, p.name),
+            Pattern::Regular(p) => write!(f, "regular {} [{}]", p.regexp, join(&p.names, ", ")),
+            Pattern::Template(p) => write!(f, "template `{}`", p.template),
+            Pattern::Within(p) => write!(f, "within {}", p.range),
+        }
+    }
+}
+
+impl fmt::Display for StructPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields: Vec<String> = self.fields.iter().map(|p| p.to_string()).collect();
+        if let Some(rest) = &self.rest {
+            fields.push(rest.to_string());
+        }
+
+        match &self.name {
+            Some(name) => write!(f, "{}{{{}}}", name, fields.join(", ")),
+            None => write!(f, "{{{}}}", fields.join(", ")),
+        }
+    }
+}
+
+impl fmt::Display for FieldPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        match (&self.pattern, self.is_shorthand) {
+            (Some(pattern), true) => write!(f, " {}", pattern)?,
+            (Some(pattern), false) => write!(f, ": {}", pattern)?,
+            (None, _) => {}
+        }
+        write!(f, "{}", self.clauses)
+    }
+}
+
+impl fmt::Display for RestPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "...{}", name),
+            None => write!(f, "..."),
+        }
+    }
+}
+
+impl fmt::Display for PrefixExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}{})", self.operator, self.operand)
+    }
+}
+
+impl fmt::Display for PrefixOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefixOperator::Negative => write!(f, "-"),
+        }
+    }
+}
+
+impl fmt::Display for PostfixExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}{})", self.operand, self.operator)
+    }
+}
+
+impl fmt::Display for PostfixOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostfixOperator::Cast => write!(f, "^"),
+            PostfixOperator::Unwrap => write!(f, "?"),
+        }
+    }
+}
+
+impl fmt::Display for BinaryExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} {} {})", self.left, self.operator, self.right)
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryOperator::Forward => write!(f, ">>"),
+            BinaryOperator::Pipe => write!(f, "|"),
+            BinaryOperator::LogicOr => write!(f, "||"),
+            BinaryOperator::LogicAnd => write!(f, "&&"),
+            BinaryOperator::Equal => write!(f, "=="),
+            BinaryOperator::NotEqual => write!(f, "!="),
+            BinaryOperator::GreaterThan => write!(f, ">"),
+            BinaryOperator::GreaterThanOrEqual => write!(f, ">="),
+            BinaryOperator::LessThan => write!(f, "<"),
+            BinaryOperator::LessThanOrEqual => write!(f, "<="),
+            BinaryOperator::Concat => write!(f, "++"),
+            BinaryOperator::Add => write!(f, "+"),
+            BinaryOperator::Subtract => write!(f, "-"),
+            BinaryOperator::Multiply => write!(f, "*"),
+            BinaryOperator::Divide => write!(f, "/"),
+            BinaryOperator::UnwrapOr => write!(f, "??"),
+            BinaryOperator::Combine => write!(f, "&"),
+        }
+    }
+}
+
+impl fmt::Display for CallExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.callee)?;
+        if !self.generic_arguments.is_empty() {
+            write!(f, "<{}>", join(&self.generic_arguments, ", "))?;
+        }
+        let mut arguments: Vec<String> = self.arguments.iter().map(|a| a.to_string()).collect();
+        arguments.extend(self.keyword_arguments.iter().map(|a| a.to_string()));
+        write!(f, "({})", arguments.join(", "))
+    }
+}
+
+impl fmt::Display for KeywordArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.name, self.value)
+    }
+}
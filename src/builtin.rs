@@ -0,0 +1,442 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{collections::BTreeMap, rc::Rc};
+
+use crate::error::Error;
+use crate::interpreter::Interpreter;
+use crate::token::Location;
+use crate::value::Value;
+
+// 内置函数，参数的数量固定为 arity，参数的类型由各个函数自己检查
+pub struct Builtin {
+    pub name: &'static str,
+    pub arity: usize,
+    function: fn(&mut Interpreter, &[Value], &Location) -> Result<Value, Error>,
+}
+
+// 按名称排序
+pub const BUILTINS: [Builtin; 14] = [
+    builtin("filter", 2, filter),
+    builtin("first", 1, first),
+    builtin("fold", 3, fold),
+    builtin("keys", 1, keys),
+    builtin("last", 1, last),
+    builtin("len", 1, len),
+    builtin("map", 2, map),
+    builtin("parse_int", 1, parse_int),
+    builtin("push", 2, push),
+    builtin("puts", 1, puts),
+    builtin("range", 2, range),
+    builtin("rest", 1, rest),
+    builtin("to_string", 1, to_string),
+    builtin("values", 1, values),
+];
+
+const fn builtin(
+    name: &'static str,
+    arity: usize,
+    function: fn(&mut Interpreter, &[Value], &Location) -> Result<Value, Error>,
+) -> Builtin {
+    Builtin {
+        name,
+        arity,
+        function,
+    }
+}
+
+pub fn find(name: &str) -> Option<&'static Builtin> {
+    BUILTINS
+        .binary_search_by(|builtin| builtin.name.cmp(name))
+        .ok()
+        .map(|index| &BUILTINS[index])
+}
+
+// 检查参数数量之后调用内置函数，location 为调用表达式的位置
+pub fn call(
+    interpreter: &mut Interpreter,
+    name: &str,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    let builtin = match find(name) {
+        Some(builtin) => builtin,
+        None => {
+            return Err(runtime_error(
+                format!("undefined builtin function \"{}\"", name),
+                location,
+            ))
+        }
+    };
+
+    if arguments.len() != builtin.arity {
+        return Err(runtime_error(
+            format!(
+                "expected {} argument{}, found {}",
+                builtin.arity,
+                if builtin.arity == 1 { "" } else { "s" },
+                arguments.len()
+            ),
+            location,
+        ));
+    }
+
+    (builtin.function)(interpreter, arguments, location)
+}
+
+fn len(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let length = match &arguments[0] {
+        Value::String(text) => text.chars().count(),
+        Value::Tuple(elements) | Value::List(elements) | Value::Array(elements) => elements.len(),
+        Value::Map(entries) => entries.len(),
+        value => {
+            return Err(type_error(
+                "len",
+                1,
+                "String, Tuple, List, Array or Map",
+                value,
+                location,
+            ))
+        }
+    };
+    Ok(Value::Integer(length as i64))
+}
+
+// 列表为空时 first、last 和 rest 返回 Option::None，否则返回 Option::Some
+fn first(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let elements = list_elements("first", &arguments[0], location)?;
+    match elements.first() {
+        Some(value) => Ok(Value::some(value.clone())),
+        None => Ok(Value::none()),
+    }
+}
+
+fn last(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let elements = list_elements("last", &arguments[0], location)?;
+    match elements.last() {
+        Some(value) => Ok(Value::some(value.clone())),
+        None => Ok(Value::none()),
+    }
+}
+
+// 返回的是新的列表，即需要复制剩余的元素，时间复杂度为 O(n)
+fn rest(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let elements = list_elements("rest", &arguments[0], location)?;
+    match elements.split_first() {
+        Some((_, others)) => Ok(Value::some(same_kind(&arguments[0], others.to_vec()))),
+        None => Ok(Value::none()),
+    }
+}
+
+// 字符串原样输出，其他值按字面量的格式输出
+fn puts(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    match &arguments[0] {
+        Value::String(text) => interpreter.write_line(text, location),
+        value => interpreter.write_line(&value.to_string(), location),
+    }
+}
+
+// 返回添加了元素的新列表（或数组），原来的值不变
+fn push(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    match &arguments[0] {
+        Value::List(elements) | Value::Array(elements) => {
+            interpreter.check_size(elements.len() + 1, location)?;
+            let mut elements = elements.as_ref().clone();
+            elements.push(arguments[1].clone());
+            Ok(same_kind(&arguments[0], elements))
+        }
+        value => Err(type_error("push", 1, "List or Array", value, location)),
+    }
+}
+
+fn map(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    let elements = elements(interpreter, "map", &arguments[0], location)?;
+    let function = callable("map", 2, &arguments[1], location)?;
+    let mut results = vec![];
+    for element in elements {
+        results.push(interpreter.call_value(function, vec![element], location)?);
+    }
+    Ok(same_kind(&arguments[0], results))
+}
+
+fn filter(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    let elements = elements(interpreter, "filter", &arguments[0], location)?;
+    let function = callable("filter", 2, &arguments[1], location)?;
+    let mut results = vec![];
+    for element in elements {
+        match interpreter.call_value(function, vec![element.clone()], location)? {
+            Value::Boolean(true) => results.push(element),
+            Value::Boolean(false) => {}
+            value => {
+                return Err(runtime_error(
+                    format!(
+                        "filter: expected the function to return Boolean, found {}",
+                        value.type_name()
+                    ),
+                    location,
+                ))
+            }
+        }
+    }
+    Ok(same_kind(&arguments[0], results))
+}
+
+// 从左往右折叠，函数的签名为 (accumulator, element) -> result
+fn fold(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    let elements = elements(interpreter, "fold", &arguments[0], location)?;
+    let function = callable("fold", 3, &arguments[2], location)?;
+    let mut accumulator = arguments[1].clone();
+    for element in elements {
+        accumulator = interpreter.call_value(function, vec![accumulator, element], location)?;
+    }
+    Ok(accumulator)
+}
+
+// 从 start 到 end 的整数列表，不包括 end
+fn range(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    match (&arguments[0], &arguments[1]) {
+        (Value::Integer(start), Value::Integer(end)) => {
+            interpreter.check_size(range_size(*start, *end), location)?;
+            let elements = (*start..*end).map(Value::Integer).collect();
+            Ok(Value::List(Rc::new(elements)))
+        }
+        (Value::Integer(_), value) => Err(type_error("range", 2, "Int", value, location)),
+        (value, _) => Err(type_error("range", 1, "Int", value, location)),
+    }
+}
+
+fn keys(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let entries = map_entries("keys", &arguments[0], location)?;
+    let keys = entries.keys().map(|key| Value::string(key)).collect();
+    Ok(Value::List(Rc::new(keys)))
+}
+
+fn values(_: &mut Interpreter, arguments: &[Value], location: &Location) -> Result<Value, Error> {
+    let entries = map_entries("values", &arguments[0], location)?;
+    let values = entries.values().cloned().collect();
+    Ok(Value::List(Rc::new(values)))
+}
+
+// 字符串原样返回，其他值转换为字面量的格式
+fn to_string(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    match &arguments[0] {
+        Value::String(_) => Ok(arguments[0].clone()),
+        value => {
+            let text = value.to_string();
+            interpreter.check_size(text.chars().count(), location)?;
+            Ok(Value::String(Rc::new(text)))
+        }
+    }
+}
+
+// 返回 Result，字符串不是合法的整数时为 Result::Error
+fn parse_int(
+    _: &mut Interpreter,
+    arguments: &[Value],
+    location: &Location,
+) -> Result<Value, Error> {
+    match &arguments[0] {
+        Value::String(text) => match text.trim().parse::<i64>() {
+            Ok(value) => Ok(Value::ok(Value::Integer(value))),
+            Err(_) => Ok(Value::error(&format!("invalid integer {}", arguments[0]))),
+        },
+        value => Err(type_error("parse_int", 1, "String", value, location)),
+    }
+}
+
+// 可以遍历的值：列表、数组以及范围（包括 end）
+fn elements(
+    interpreter: &Interpreter,
+    name: &str,
+    value: &Value,
+    location: &Location,
+) -> Result<Vec<Value>, Error> {
+    match value {
+        Value::List(elements) | Value::Array(elements) => Ok(elements.as_ref().clone()),
+        Value::Range(start, end) => {
+            interpreter.check_size(range_size(*start, *end).saturating_add(1), location)?;
+            Ok((*start..=*end).map(Value::Integer).collect())
+        }
+        _ => Err(type_error(name, 1, "List, Array or Range", value, location)),
+    }
+}
+
+fn list_elements<'a>(
+    name: &str,
+    value: &'a Value,
+    location: &Location,
+) -> Result<&'a [Value], Error> {
+    match value {
+        Value::List(elements) | Value::Array(elements) => Ok(elements),
+        _ => Err(type_error(name, 1, "List or Array", value, location)),
+    }
+}
+
+fn map_entries<'a>(
+    name: &str,
+    value: &'a Value,
+    location: &Location,
+) -> Result<&'a BTreeMap<String, Value>, Error> {
+    match value {
+        Value::Map(entries) => Ok(entries),
+        _ => Err(type_error(name, 1, "Map", value, location)),
+    }
+}
+
+fn callable<'a>(
+    name: &str,
+    position: usize,
+    value: &'a Value,
+    location: &Location,
+) -> Result<&'a Value, Error> {
+    match value {
+        Value::Function(_) | Value::Builtin(_) => Ok(value),
+        _ => Err(type_error(name, position, "Function", value, location)),
+    }
+}
+
+// 从 start 到 end（不包括）的整数个数
+fn range_size(start: i64, end: i64) -> usize {
+    usize::try_from(end.saturating_sub(start)).unwrap_or(0)
+}
+
+// 数组的结果仍然是数组，其他（列表和范围）的结果为列表
+fn same_kind(original: &Value, elements: Vec<Value>) -> Value {
+    match original {
+        Value::Array(_) => Value::Array(Rc::new(elements)),
+        _ => Value::List(Rc::new(elements)),
+    }
+}
+
+fn type_error(
+    name: &str,
+    position: usize,
+    expected: &str,
+    value: &Value,
+    location: &Location,
+) -> Error {
+    runtime_error(
+        format!(
+            "{}: expected {} for argument {}, found {}",
+            name,
+            expected,
+            position,
+            value.type_name()
+        ),
+        location,
+    )
+}
+
+fn runtime_error(message: impl Into<String>, location: &Location) -> Error {
+    Error::RuntimeError(message.into(), location.clone(), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::interpreter::eval_from_str;
+
+    use super::{find, BUILTINS};
+
+    fn eval(text: &str) -> String {
+        eval_from_str(text).unwrap().to_string()
+    }
+
+    fn eval_error(text: &str) -> String {
+        match eval_from_str(text) {
+            Err(Error::RuntimeError(message, ..)) => message,
+            other => panic!("expected runtime error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        assert!(BUILTINS.windows(2).all(|w| w[0].name < w[1].name));
+        assert_eq!(find("fold").map(|b| b.arity), Some(3));
+        assert!(find("foo").is_none());
+    }
+
+    #[test]
+    fn test_builtin() {
+        assert_eq!(eval("len({a: 1, b: 2})"), "2");
+        assert_eq!(eval("first([1, 2])"), "Option::Some {value: 1}");
+        assert_eq!(eval("last(#[1, 2])"), "Option::Some {value: 2}");
+        assert_eq!(eval("rest(#[1, 2, 3])"), "Option::Some {value: #[2, 3]}");
+        assert_eq!(
+            eval("(first([]), last([]), rest(#[]))"),
+            "(Option::None, Option::None, Option::None)"
+        );
+        assert_eq!(eval("push([1], 2)"), "[1, 2]");
+        assert_eq!(eval("map([1, 2], x => x * 10)"), "[10, 20]");
+        assert_eq!(eval("map([1..3], to_string)"), "[\"1\", \"2\", \"3\"]");
+        assert_eq!(eval("filter(#[1, 2, 3, 4], x => x > 2)"), "#[3, 4]");
+        assert_eq!(eval("fold([1, 2, 3], 0, (a, b) => a + b)"), "6");
+        assert_eq!(eval("range(0, 3)"), "[0, 1, 2]");
+        assert_eq!(eval("range(3, 0)"), "[]");
+        assert_eq!(eval("keys({b: 1, a: 2})"), "[\"a\", \"b\"]");
+        assert_eq!(eval("values({b: 1, a: 2})"), "[2, 1]");
+        assert_eq!(eval("to_string([1, 'a'])"), "\"[1, 'a']\"");
+        assert_eq!(eval("parse_int(\" -12 \")"), "Result::Ok {value: -12}");
+        assert_eq!(
+            eval("parse_int(\"1a\")"),
+            "Result::Error {error: \"invalid integer \\\"1a\\\"\"}"
+        );
+    }
+
+    #[test]
+    fn test_builtin_error() {
+        assert_eq!(eval_error("push([1])"), "expected 2 arguments, found 1");
+        assert_eq!(
+            eval_error("len(1)"),
+            "len: expected String, Tuple, List, Array or Map for argument 1, found Int"
+        );
+        assert_eq!(
+            eval_error("first(1)"),
+            "first: expected List or Array for argument 1, found Int"
+        );
+        assert_eq!(
+            eval_error("map([1], 2)"),
+            "map: expected Function for argument 2, found Int"
+        );
+        assert_eq!(
+            eval_error("filter([1], x => x)"),
+            "filter: expected the function to return Boolean, found Int"
+        );
+        assert_eq!(
+            eval_error("parse_int(1)"),
+            "parse_int: expected String for argument 1, found Int"
+        );
+    }
+}
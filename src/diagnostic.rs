@@ -0,0 +1,216 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use crate::error::Error;
+use crate::source_map::SourceMap;
+use crate::token::Location;
+
+// 把错误渲染为多行文本，包括错误信息、位置以及标注了出错范围的源代码行，比如：
+//
+// ```
+// runtime error: division by zero
+//  --> main.toy:2:1
+//   |
+// 2 | a / (a - 1)
+//   | ^^^^^^^^^^^
+// ```
+//
+// 没有位置的错误（位于文件末尾的语法错误）只显示文件名，
+// 运行时错误在最后列出调用栈（从内到外）以及每一层的调用位置，比如：
+//
+// ```
+// stack trace:
+//   at div (main.toy:5:1)
+//   at <anonymous> (main.toy:8:9)
+// ```
+//
+// 参数 file_id 为正在处理的源文件
+pub fn render(error: &Error, source_map: &SourceMap, file_id: usize) -> String {
+    let (kind, message, location, frames) = match error {
+        Error::LexerError(message, location) => {
+            ("syntax error", message.to_string(), Some(location), &[][..])
+        }
+        Error::ParserError(message, location) => (
+            "syntax error",
+            message.to_string(),
+            location.as_ref(),
+            &[][..],
+        ),
+        Error::RuntimeError(message, location, frames) => (
+            "runtime error",
+            message.clone(),
+            Some(location),
+            frames.as_slice(),
+        ),
+        Error::LimitError(limit, location, frames) => (
+            "runtime error",
+            limit.to_string(),
+            Some(location),
+            frames.as_slice(),
+        ),
+    };
+
+    let mut lines = vec![format!("{}: {}", kind, message)];
+    match location {
+        Some(location) => lines.extend(render_location(location, source_map)),
+        None => {
+            if let Some(file) = source_map.get(file_id) {
+                lines.push(format!(" --> {}", file.name));
+            }
+        }
+    }
+
+    if !frames.is_empty() {
+        lines.push("stack trace:".to_owned());
+        for frame in frames {
+            lines.push(format!(
+                "  at {} ({})",
+                frame.name.as_deref().unwrap_or("<anonymous>"),
+                format_location(&frame.location, source_map)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+// `file:line:column`，找不到源文件时只显示偏移量
+fn format_location(location: &Location, source_map: &SourceMap) -> String {
+    match source_map.get(location.file_id) {
+        Some(file) => {
+            let (line_number, column) = file.line_column(location.start);
+            format!("{}:{}:{}", file.name, line_number, column)
+        }
+        None => format!("{}..{}", location.start, location.end),
+    }
+}
+
+fn render_location(location: &Location, source_map: &SourceMap) -> Vec<String> {
+    let file = match source_map.get(location.file_id) {
+        Some(file) => file,
+        None => return vec![],
+    };
+
+    let (line_number, column) = file.line_column(location.start);
+    let line = file.line(line_number);
+
+    // 跨越多行的范围只标注到第一行的末尾
+    let line_length = line.chars().count();
+    let width = (location.end - location.start)
+        .min(line_length.saturating_sub(column - 1))
+        .max(1);
+
+    let gutter = " ".repeat(line_number.to_string().len());
+    vec![
+        format!("{}--> {}", gutter, format_location(location, source_map)),
+        format!("{} |", gutter),
+        format!("{} | {}", line_number, line),
+        format!(
+            "{} | {}{}",
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(width)
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::error::StackFrame;
+    use crate::source_map::SourceMap;
+    use crate::token::Location;
+
+    use super::render;
+
+    fn location(start: usize, end: usize) -> Location {
+        Location {
+            file_id: 0,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut source_map = SourceMap::new();
+        source_map.add_file("main.toy", "let a = 1\na / (a - 1)\nlet b = {\n 1}");
+
+        assert_eq!(
+            render(
+                &Error::RuntimeError("division by zero".to_owned(), location(10, 21), vec![]),
+                &source_map,
+                0
+            ),
+            "runtime error: division by zero\n \
+            --> main.toy:2:1\n  \
+            |\n\
+            2 | a / (a - 1)\n  \
+            | ^^^^^^^^^^^"
+        );
+
+        assert_eq!(
+            render(
+                &Error::ParserError("expected identifier", Some(location(26, 36))),
+                &source_map,
+                0
+            ),
+            "syntax error: expected identifier\n \
+            --> main.toy:3:5\n  \
+            |\n\
+            3 | let b = {\n  \
+            |     ^^^^^"
+        );
+
+        let frames = vec![
+            StackFrame {
+                name: Some("div".to_owned()),
+                location: location(0, 9),
+            },
+            StackFrame {
+                name: None,
+                location: location(33, 34),
+            },
+        ];
+        assert_eq!(
+            render(
+                &Error::RuntimeError("division by zero".to_owned(), location(14, 21), frames),
+                &source_map,
+                0
+            ),
+            "runtime error: division by zero\n \
+            --> main.toy:2:5\n  \
+            |\n\
+            2 | a / (a - 1)\n  \
+            |     ^^^^^^^\n\
+            stack trace:\n  \
+            at div (main.toy:1:1)\n  \
+            at <anonymous> (main.toy:4:2)"
+        );
+
+        assert_eq!(
+            render(
+                &Error::LexerError("unterminated string", location(8, 9)),
+                &source_map,
+                0
+            ),
+            "syntax error: unterminated string\n \
+            --> main.toy:1:9\n  \
+            |\n\
+            1 | let a = 1\n  \
+            |         ^"
+        );
+
+        assert_eq!(
+            render(
+                &Error::ParserError("unexpected end of file", None),
+                &source_map,
+                0
+            ),
+            "syntax error: unexpected end of file\n --> main.toy"
+        );
+    }
+}
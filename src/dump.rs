@@ -0,0 +1,961 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use crate::ast::AliasDeclaration;
+use crate::ast::AnonymousFunction;
+use crate::ast::AssociatedType;
+use crate::ast::BlockExpression;
+use crate::ast::BranchCase;
+use crate::ast::CallExpression;
+use crate::ast::ConstDeclaration;
+use crate::ast::ConstMember;
+use crate::ast::ConstValue;
+use crate::ast::DataType;
+use crate::ast::EnumDeclaration;
+use crate::ast::ErrorNode;
+use crate::ast::Expression;
+use crate::ast::FieldDeclaration;
+use crate::ast::FieldPattern;
+use crate::ast::FieldValue;
+use crate::ast::FunctionDeclaration;
+use crate::ast::Identifier;
+use crate::ast::ImplDeclaration;
+use crate::ast::InBinding;
+use crate::ast::KeywordArgument;
+use crate::ast::ListPattern;
+use crate::ast::Literal;
+use crate::ast::LiteralValue;
+use crate::ast::MapEntry;
+use crate::ast::MatchCase;
+use crate::ast::NamespaceDeclaration;
+use crate::ast::Parameter;
+use crate::ast::Path;
+use crate::ast::Pattern;
+use crate::ast::Program;
+use crate::ast::RestPattern;
+use crate::ast::SignParameter;
+use crate::ast::Statement;
+use crate::ast::StructDeclaration;
+use crate::ast::TraitDeclaration;
+use crate::ast::TypeConstraint;
+use crate::ast::UnionDeclaration;
+use crate::ast::UnionMember;
+use crate::ast::UseKind;
+use crate::ast::UseTree;
+use crate::ast::WhichEntry;
+use crate::token::Location;
+
+// 把 AST 输出为 JSON 或者 S 表达式，用于调试以及供其他语言编写的工具使用。
+//
+// JSON 格式里每个节点都是一个对象，`kind` 为节点的种类，`location` 为节点的位置
+// （没有位置的节点省略该项），其余各项为节点的成员，比如：
+// `{"kind": "Binary", "location": {"file_id": 0, "start": 0, "end": 5}, "operator": "+", ...}`
+//
+// S 表达式格式省略位置信息以及成员名称，节点的成员按照固定的顺序排列，
+// 空值输出为 `nil`，标识符跟其他节点一样输出，以免跟 `nil` 等符号混淆，
+// 比如 `(Binary "+" (Identifier "a") (Integer 1))`
+pub fn to_json(program: &Program) -> String {
+    let mut output = String::new();
+    write_json(&program.dump(), &mut output);
+    output
+}
+
+pub fn to_s_expression(program: &Program) -> String {
+    let mut output = String::new();
+    write_s_expression(&program.dump(), &mut output);
+    output
+}
+
+// AST 的通用树形表示
+enum Value {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    String(String),
+    List(Vec<Value>),
+    Node(Node),
+}
+
+struct Node {
+    kind: &'static str,
+    location: Option<Location>,
+    fields: Vec<(&'static str, Value)>,
+}
+
+fn node(kind: &'static str, location: &Location, fields: Vec<(&'static str, Value)>) -> Value {
+    Value::Node(Node {
+        kind,
+        location: Some(location.clone()),
+        fields,
+    })
+}
+
+// 没有位置信息的节点
+fn plain_node(kind: &'static str, fields: Vec<(&'static str, Value)>) -> Value {
+    Value::Node(Node {
+        kind,
+        location: None,
+        fields,
+    })
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+fn write_json(value: &Value, output: &mut String) {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Boolean(value) => output.push_str(&value.to_string()),
+        Value::Integer(value) => output.push_str(&value.to_string()),
+        Value::String(value) => write_string(value, output),
+        Value::List(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(", ");
+                }
+                write_json(item, output);
+            }
+            output.push(']');
+        }
+        Value::Node(node) => {
+            output.push_str("{\"kind\": ");
+            write_string(node.kind, output);
+            if let Some(location) = &node.location {
+                output.push_str(&format!(
+                    ", \"location\": {{\"file_id\": {}, \"start\": {}, \"end\": {}}}",
+                    location.file_id, location.start, location.end
+                ));
+            }
+            for (name, value) in &node.fields {
+                output.push_str(", ");
+                write_string(name, output);
+                output.push_str(": ");
+                write_json(value, output);
+            }
+            output.push('}');
+        }
+    }
+}
+
+fn write_s_expression(value: &Value, output: &mut String) {
+    match value {
+        Value::Null => output.push_str("nil"),
+        Value::Boolean(value) => output.push_str(&value.to_string()),
+        Value::Integer(value) => output.push_str(&value.to_string()),
+        Value::String(value) => write_string(value, output),
+        Value::List(items) => {
+            output.push('(');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(' ');
+                }
+                write_s_expression(item, output);
+            }
+            output.push(')');
+        }
+        Value::Node(node) => {
+            output.push('(');
+            output.push_str(node.kind);
+            for (_, value) in &node.fields {
+                output.push(' ');
+                write_s_expression(value, output);
+            }
+            output.push(')');
+        }
+    }
+}
+
+// JSON 和 S 表达式的字符串使用相同的转义规则
+fn write_string(value: &str, output: &mut String) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            _ if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+trait Dump {
+    fn dump(&self) -> Value;
+}
+
+impl<T: Dump> Dump for Vec<T> {
+    fn dump(&self) -> Value {
+        Value::List(self.iter().map(|item| item.dump()).collect())
+    }
+}
+
+impl<T: Dump> Dump for Option<T> {
+    fn dump(&self) -> Value {
+        match self {
+            Some(item) => item.dump(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: Dump> Dump for Box<T> {
+    fn dump(&self) -> Value {
+        self.as_ref().dump()
+    }
+}
+
+impl Dump for Program {
+    fn dump(&self) -> Value {
+        plain_node("Program", vec![("statements", self.statements.dump())])
+    }
+}
+
+impl Dump for Statement {
+    fn dump(&self) -> Value {
+        match self {
+            Statement::Expression(e) => e.dump(),
+            Statement::Function(s) => s.dump(),
+            Statement::Struct(s) => s.dump(),
+            Statement::Union(s) => s.dump(),
+            Statement::Enum(s) => s.dump(),
+            Statement::Const(s) => s.dump(),
+            Statement::Trait(s) => s.dump(),
+            Statement::Impl(s) => s.dump(),
+            Statement::Namespace(s) => s.dump(),
+            Statement::Use(s) => node("UseDeclaration", &s.location, vec![("tree", s.tree.dump())]),
+            Statement::Alias(s) => s.dump(),
+            Statement::Error(s) => s.dump(),
+        }
+    }
+}
+
+impl Dump for ErrorNode {
+    fn dump(&self) -> Value {
+        node(
+            "Error",
+            &self.location,
+            vec![("message", text(self.message))],
+        )
+    }
+}
+
+impl Dump for Identifier {
+    fn dump(&self) -> Value {
+        node(
+            "Identifier",
+            &self.location,
+            vec![("name", text(&self.name))],
+        )
+    }
+}
+
+impl Dump for FunctionDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "FunctionDeclaration",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("is_pattern", Value::Boolean(self.is_pattern)),
+                ("generic_parameters", self.generic_parameters.dump()),
+                ("parameters", self.parameters.dump()),
+                ("return_type", self.return_type.dump()),
+                ("which", self.which.dump()),
+                ("body", self.body.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for Parameter {
+    fn dump(&self) -> Value {
+        node(
+            "Parameter",
+            &self.location,
+            vec![
+                ("data_type", self.data_type.dump()),
+                ("pattern", self.pattern.dump()),
+                ("default_value", self.default_value.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for WhichEntry {
+    fn dump(&self) -> Value {
+        let constraint = match &self.constraint {
+            TypeConstraint::DataType(data_type) => data_type.dump(),
+            TypeConstraint::Limit(traits) => plain_node("Limit", vec![("traits", traits.dump())]),
+        };
+        node(
+            "WhichEntry",
+            &self.location,
+            vec![("name", self.name.dump()), ("constraint", constraint)],
+        )
+    }
+}
+
+impl Dump for TraitDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "TraitDeclaration",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("associated_types", self.associated_types.dump()),
+                ("functions", self.functions.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for ImplDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "ImplDeclaration",
+            &self.location,
+            vec![
+                ("data_type", self.data_type.dump()),
+                ("trait_name", self.trait_name.dump()),
+                ("associated_types", self.associated_types.dump()),
+                ("functions", self.functions.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for AssociatedType {
+    fn dump(&self) -> Value {
+        node(
+            "AssociatedType",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("data_type", self.data_type.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for NamespaceDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "NamespaceDeclaration",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("statements", self.statements.dump()),
+            ],
+        )
+    }
+}
+
+// 导入方式 import 为 "single"、"glob" 或者 "group"，只有 "group" 的 items 不为空
+impl Dump for UseTree {
+    fn dump(&self) -> Value {
+        let (import, items) = match &self.kind {
+            UseKind::Single => ("single", Value::List(vec![])),
+            UseKind::Glob => ("glob", Value::List(vec![])),
+            UseKind::Group(items) => ("group", items.dump()),
+        };
+        node(
+            "UseTree",
+            &self.location,
+            vec![
+                ("path", self.path.dump()),
+                ("import", text(import)),
+                ("items", items),
+            ],
+        )
+    }
+}
+
+impl Dump for AliasDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "AliasDeclaration",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("data_type", self.data_type.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for StructDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "StructDeclaration",
+            &self.location,
+            vec![("name", self.name.dump()), ("fields", self.fields.dump())],
+        )
+    }
+}
+
+impl Dump for FieldDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "FieldDeclaration",
+            &self.location,
+            vec![
+                ("data_type", self.data_type.dump()),
+                ("name", self.name.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for UnionDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "UnionDeclaration",
+            &self.location,
+            vec![("name", self.name.dump()), ("members", self.members.dump())],
+        )
+    }
+}
+
+impl Dump for UnionMember {
+    fn dump(&self) -> Value {
+        node(
+            "UnionMember",
+            &self.location,
+            vec![("name", self.name.dump()), ("fields", self.fields.dump())],
+        )
+    }
+}
+
+impl Dump for EnumDeclaration {
+    fn dump(&self) -> Value {
+        node(
+            "EnumDeclaration",
+            &self.location,
+            vec![("name", self.name.dump()), ("members", self.members.dump())],
+        )
+    }
+}
+
+// 单个常量的 value 为表达式，常量组的 value 为成员列表
+impl Dump for ConstDeclaration {
+    fn dump(&self) -> Value {
+        let value = match &self.value {
+            ConstValue::Single(value) => value.dump(),
+            ConstValue::Group(members) => members.dump(),
+        };
+        node(
+            "ConstDeclaration",
+            &self.location,
+            vec![
+                ("data_type", self.data_type.dump()),
+                ("name", self.name.dump()),
+                ("value", value),
+            ],
+        )
+    }
+}
+
+impl Dump for ConstMember {
+    fn dump(&self) -> Value {
+        node(
+            "ConstMember",
+            &self.location,
+            vec![("name", self.name.dump()), ("value", self.value.dump())],
+        )
+    }
+}
+
+impl Dump for DataType {
+    fn dump(&self) -> Value {
+        match self {
+            DataType::Named(t) => node(
+                "NamedType",
+                &t.location,
+                vec![
+                    ("name", t.name.dump()),
+                    ("generic_arguments", t.generic_arguments.dump()),
+                ],
+            ),
+            DataType::Sign(t) => node(
+                "SignType",
+                &t.location,
+                vec![
+                    ("parameters", t.parameters.dump()),
+                    ("return_type", t.return_type.dump()),
+                ],
+            ),
+        }
+    }
+}
+
+impl Dump for SignParameter {
+    fn dump(&self) -> Value {
+        plain_node(
+            "SignParameter",
+            vec![
+                ("data_type", self.data_type.dump()),
+                ("name", self.name.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for Literal {
+    fn dump(&self) -> Value {
+        let (kind, value) = match &self.value {
+            LiteralValue::Integer(value) => ("Integer", Value::Integer(*value)),
+            LiteralValue::Boolean(value) => ("Boolean", Value::Boolean(*value)),
+            LiteralValue::Char(value) => ("Char", text(&value.to_string())),
+            LiteralValue::String(value) => ("String", text(value)),
+            LiteralValue::TemplateString(value) => ("TemplateString", text(value)),
+            LiteralValue::HashString(value) => ("HashString", text(value)),
+            LiteralValue::Regexp(value) => ("Regexp", text(value)),
+        };
+        node(kind, &self.location, vec![("value", value)])
+    }
+}
+
+impl Dump for Expression {
+    fn dump(&self) -> Value {
+        match self {
+            Expression::Literal(e) => e.dump(),
+            Expression::Identifier(e) => e.dump(),
+            Expression::Path(e) => e.dump(),
+            Expression::Tuple(e) => {
+                node("Tuple", &e.location, vec![("elements", e.elements.dump())])
+            }
+            Expression::List(e) => node("List", &e.location, vec![("elements", e.elements.dump())]),
+            Expression::Array(e) => {
+                node("Array", &e.location, vec![("elements", e.elements.dump())])
+            }
+            Expression::Matrix(e) => node("Matrix", &e.location, vec![("rows", e.rows.dump())]),
+            Expression::Map(e) => node("Map", &e.location, vec![("entries", e.entries.dump())]),
+            Expression::Range(e) => node(
+                "Range",
+                &e.location,
+                vec![("start", e.start.dump()), ("end", e.end.dump())],
+            ),
+            Expression::Sequence(e) => node(
+                "Sequence",
+                &e.location,
+                vec![("elements", e.elements.dump()), ("end", e.end.dump())],
+            ),
+            Expression::Spread(e) => node("Spread", &e.location, vec![("value", e.value.dump())]),
+            Expression::Block(e) => e.dump(),
+            Expression::Let(e) => node(
+                "Let",
+                &e.location,
+                vec![
+                    ("pattern", e.pattern.dump()),
+                    ("value", e.value.dump()),
+                    ("is_match", Value::Boolean(e.is_match)),
+                ],
+            ),
+            Expression::If(e) => node(
+                "If",
+                &e.location,
+                vec![
+                    ("condition", e.condition.dump()),
+                    ("where", e.where_clause.dump()),
+                    ("consequent", e.consequent.dump()),
+                    ("alternative", e.alternative.dump()),
+                ],
+            ),
+            Expression::Branch(e) => node(
+                "Branch",
+                &e.location,
+                vec![
+                    ("where", e.where_clause.dump()),
+                    ("cases", e.cases.dump()),
+                    ("default", e.default.dump()),
+                ],
+            ),
+            Expression::Match(e) => node(
+                "Match",
+                &e.location,
+                vec![
+                    ("subject", e.subject.dump()),
+                    ("where", e.where_clause.dump()),
+                    ("cases", e.cases.dump()),
+                    ("default", e.default.dump()),
+                ],
+            ),
+            Expression::ForLet(e) => node(
+                "ForLet",
+                &e.location,
+                vec![
+                    ("pattern", e.pattern.dump()),
+                    ("initial", e.initial.dump()),
+                    ("body", e.body.dump()),
+                ],
+            ),
+            Expression::ForIn(e) => node(
+                "ForIn",
+                &e.location,
+                vec![("bindings", e.bindings.dump()), ("body", e.body.dump())],
+            ),
+            Expression::Each(e) => node(
+                "Each",
+                &e.location,
+                vec![("bindings", e.bindings.dump()), ("body", e.body.dump())],
+            ),
+            Expression::Next(e) => node("Next", &e.location, vec![("value", e.value.dump())]),
+            Expression::AnonymousFunction(e) => e.dump(),
+            Expression::Struct(e) => node(
+                "Struct",
+                &e.location,
+                vec![("name", e.name.dump()), ("fields", e.fields.dump())],
+            ),
+            Expression::Prefix(e) => node(
+                "Prefix",
+                &e.location,
+                vec![
+                    ("operator", text(&e.operator.to_string())),
+                    ("operand", e.operand.dump()),
+                ],
+            ),
+            Expression::Postfix(e) => node(
+                "Postfix",
+                &e.location,
+                vec![
+                    ("operator", text(&e.operator.to_string())),
+                    ("operand", e.operand.dump()),
+                ],
+            ),
+            Expression::Binary(e) => node(
+                "Binary",
+                &e.location,
+                vec![
+                    ("operator", text(&e.operator.to_string())),
+                    ("left", e.left.dump()),
+                    ("right", e.right.dump()),
+                ],
+            ),
+            Expression::Call(e) => e.dump(),
+            Expression::Member(e) => node(
+                "Member",
+                &e.location,
+                vec![("object", e.object.dump()), ("property", e.property.dump())],
+            ),
+            Expression::TupleIndex(e) => node(
+                "TupleIndex",
+                &e.location,
+                vec![
+                    ("object", e.object.dump()),
+                    ("index", Value::Integer(e.index as i64)),
+                ],
+            ),
+            Expression::Index(e) => node(
+                "Index",
+                &e.location,
+                vec![("object", e.object.dump()), ("index", e.index.dump())],
+            ),
+            Expression::Slice(e) => node(
+                "Slice",
+                &e.location,
+                vec![
+                    ("object", e.object.dump()),
+                    ("start", e.start.dump()),
+                    ("end", e.end.dump()),
+                ],
+            ),
+            Expression::Error(e) => e.dump(),
+        }
+    }
+}
+
+impl Dump for MapEntry {
+    fn dump(&self) -> Value {
+        match self {
+            MapEntry::Pair(p) => node(
+                "MapPair",
+                &p.location,
+                vec![("name", p.name.dump()), ("value", p.value.dump())],
+            ),
+            MapEntry::Spread(s) => node("Spread", &s.location, vec![("value", s.value.dump())]),
+        }
+    }
+}
+
+impl Dump for Path {
+    fn dump(&self) -> Value {
+        node(
+            "Path",
+            &self.location,
+            vec![("segments", self.segments.dump())],
+        )
+    }
+}
+
+impl Dump for FieldValue {
+    fn dump(&self) -> Value {
+        plain_node(
+            "FieldValue",
+            vec![("name", self.name.dump()), ("value", self.value.dump())],
+        )
+    }
+}
+
+impl Dump for BlockExpression {
+    fn dump(&self) -> Value {
+        node(
+            "Block",
+            &self.location,
+            vec![("expressions", self.expressions.dump())],
+        )
+    }
+}
+
+impl Dump for BranchCase {
+    fn dump(&self) -> Value {
+        node(
+            "BranchCase",
+            &self.location,
+            vec![
+                ("condition", self.condition.dump()),
+                ("where", self.where_clause.dump()),
+                ("body", self.body.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for MatchCase {
+    fn dump(&self) -> Value {
+        node(
+            "MatchCase",
+            &self.location,
+            vec![
+                ("patterns", self.patterns.dump()),
+                ("to", self.clauses.to.dump()),
+                ("only", self.clauses.only.dump()),
+                ("where", self.clauses.where_clause.dump()),
+                ("body", self.body.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for InBinding {
+    fn dump(&self) -> Value {
+        node(
+            "InBinding",
+            &self.location,
+            vec![
+                ("pattern", self.pattern.dump()),
+                ("iterable", self.iterable.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for AnonymousFunction {
+    fn dump(&self) -> Value {
+        node(
+            "AnonymousFunction",
+            &self.location,
+            vec![
+                ("parameters", self.parameters.dump()),
+                ("return_type", self.return_type.dump()),
+                ("body", self.body.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for CallExpression {
+    fn dump(&self) -> Value {
+        node(
+            "Call",
+            &self.location,
+            vec![
+                ("callee", self.callee.dump()),
+                ("generic_arguments", self.generic_arguments.dump()),
+                ("arguments", self.arguments.dump()),
+                ("keyword_arguments", self.keyword_arguments.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for KeywordArgument {
+    fn dump(&self) -> Value {
+        node(
+            "KeywordArgument",
+            &self.location,
+            vec![("name", self.name.dump()), ("value", self.value.dump())],
+        )
+    }
+}
+
+impl Dump for Pattern {
+    fn dump(&self) -> Value {
+        match self {
+            Pattern::Wildcard(location) => node("WildcardPattern", location, vec![]),
+            Pattern::Identifier(p) => {
+                node("IdentifierPattern", &p.location, vec![("name", p.dump())])
+            }
+            Pattern::Literal(p) => node("LiteralPattern", &p.location, vec![("value", p.dump())]),
+            Pattern::Tuple(p) => node(
+                "TuplePattern",
+                &p.location,
+                vec![("elements", p.elements.dump())],
+            ),
+            Pattern::List(p) => list_pattern("ListPattern", p),
+            Pattern::Array(p) => list_pattern("ArrayPattern", p),
+            Pattern::Struct(p) => node(
+                "StructPattern",
+                &p.location,
+                vec![
+                    ("name", p.name.dump()),
+                    ("fields", p.fields.dump()),
+                    ("rest", p.rest.dump()),
+                ],
+            ),
+            Pattern::Rest(p) => p.dump(),
+            Pattern::Into(p) => node(
+                "IntoPattern",
+                &p.location,
+                vec![("data_type", p.data_type.dump()), ("name", p.name.dump())],
+            ),
+            Pattern::Regular(p) => node(
+                "RegularPattern",
+                &p.location,
+                vec![("regexp", p.regexp.dump()), ("names", p.names.dump())],
+            ),
+            Pattern::Template(p) => node(
+                "TemplatePattern",
+                &p.location,
+                vec![("template", text(&p.template))],
+            ),
+            Pattern::Within(p) => node(
+                "WithinPattern",
+                &p.location,
+                vec![("range", p.range.dump())],
+            ),
+        }
+    }
+}
+
+fn list_pattern(kind: &'static str, pattern: &ListPattern) -> Value {
+    node(
+        kind,
+        &pattern.location,
+        vec![("elements", pattern.elements.dump())],
+    )
+}
+
+impl Dump for FieldPattern {
+    fn dump(&self) -> Value {
+        node(
+            "FieldPattern",
+            &self.location,
+            vec![
+                ("name", self.name.dump()),
+                ("pattern", self.pattern.dump()),
+                ("is_shorthand", Value::Boolean(self.is_shorthand)),
+                ("to", self.clauses.to.dump()),
+                ("only", self.clauses.only.dump()),
+                ("where", self.clauses.where_clause.dump()),
+            ],
+        )
+    }
+}
+
+impl Dump for RestPattern {
+    fn dump(&self) -> Value {
+        node(
+            "RestPattern",
+            &self.location,
+            vec![("name", self.name.dump())],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::tokenize;
+    use crate::parser::{parse, parse_with_recovery};
+
+    use super::{to_json, to_s_expression};
+
+    fn json_from_str(text: &str) -> String {
+        let tokens = tokenize(text).unwrap();
+        to_json(&parse(&tokens).unwrap())
+    }
+
+    fn s_expression_from_str(text: &str) -> String {
+        let tokens = tokenize(text).unwrap();
+        to_s_expression(&parse(&tokens).unwrap())
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            json_from_str("a+1"),
+            "{\"kind\": \"Program\", \"statements\": [\
+            {\"kind\": \"Binary\", \"location\": {\"file_id\": 0, \"start\": 0, \"end\": 3}, \
+            \"operator\": \"+\", \
+            \"left\": {\"kind\": \"Identifier\", \"location\": {\"file_id\": 0, \"start\": 0, \"end\": 1}, \"name\": \"a\"}, \
+            \"right\": {\"kind\": \"Integer\", \"location\": {\"file_id\": 0, \"start\": 2, \"end\": 3}, \"value\": 1}}]}"
+        );
+
+        // 字符串转义，空值和没有位置的节点
+        assert_eq!(
+            json_from_str("User {\"a\\\"b\\n\"}"),
+            "{\"kind\": \"Program\", \"statements\": [\
+            {\"kind\": \"Struct\", \"location\": {\"file_id\": 0, \"start\": 0, \"end\": 15}, \
+            \"name\": {\"kind\": \"Identifier\", \"location\": {\"file_id\": 0, \"start\": 0, \"end\": 4}, \"name\": \"User\"}, \
+            \"fields\": [{\"kind\": \"FieldValue\", \"name\": null, \
+            \"value\": {\"kind\": \"String\", \"location\": {\"file_id\": 0, \"start\": 6, \"end\": 14}, \"value\": \"a\\\"b\\n\"}}]}]}"
+        );
+    }
+
+    #[test]
+    fn test_s_expression() {
+        assert_eq!(
+            s_expression_from_str("let (a, _) = f(1, b = true)"),
+            "(Program ((Let (TuplePattern ((IdentifierPattern (Identifier \"a\")) (WildcardPattern))) \
+            (Call (Identifier \"f\") () ((Integer 1)) ((KeywordArgument (Identifier \"b\") (Boolean true)))) \
+            false)))"
+        );
+        assert_eq!(
+            s_expression_from_str("function f(Int x) type Int = x\nuse a::{b, c::*}"),
+            "(Program ((FunctionDeclaration (Identifier \"f\") false () \
+            ((Parameter (NamedType (Identifier \"Int\") ()) (IdentifierPattern (Identifier \"x\")) nil)) \
+            (NamedType (Identifier \"Int\") ()) () (Identifier \"x\")) \
+            (UseDeclaration (UseTree ((Identifier \"a\")) \"group\" \
+            ((UseTree ((Identifier \"b\")) \"single\" ()) (UseTree ((Identifier \"c\")) \"glob\" ()))))))"
+        );
+        assert_eq!(
+            s_expression_from_str("match x {case [a, ...] only a > 1: a\ndefault: 0}"),
+            "(Program ((Match (Identifier \"x\") nil ((MatchCase ((ListPattern \
+            ((IdentifierPattern (Identifier \"a\")) (RestPattern nil)))) \
+            nil (Binary \">\" (Identifier \"a\") (Integer 1)) nil (Identifier \"a\"))) (Integer 0))))"
+        );
+
+        // 名为 nil 的标识符跟空值不会混淆
+        assert_eq!(
+            s_expression_from_str("(nil) => nil"),
+            "(Program ((AnonymousFunction ((Parameter nil (IdentifierPattern (Identifier \"nil\")) nil)) \
+            nil (Identifier \"nil\"))))"
+        );
+
+        // 错误节点
+        let tokens = tokenize("1 2").unwrap();
+        let (program, _) = parse_with_recovery(&tokens);
+        assert_eq!(
+            to_s_expression(&program),
+            "(Program ((Integer 1) (Error \"expected new line\")))"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::value::Value;
+
+// 变量的作用域，每个函数调用和表达式块都创建一个新的环境，
+// 查找变量时从当前环境开始逐层往外（parent）查找。
+// 环境使用 Rc<RefCell<..>> 共享，闭包持有定义时所在的环境，
+// 所以闭包可以访问在它之后才定义的变量，比如递归调用自身
+#[derive(Debug, Default)]
+pub struct Environment {
+    bindings: HashMap<String, Value>,
+    parent: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Rc<RefCell<Environment>> {
+        Rc::new(RefCell::new(Environment::default()))
+    }
+
+    pub fn new_child(parent: &Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        Rc::new(RefCell::new(Environment {
+            bindings: HashMap::new(),
+            parent: Some(Rc::clone(parent)),
+        }))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self.bindings.get(name) {
+            Some(value) => Some(value.clone()),
+            None => match &self.parent {
+                Some(parent) => parent.borrow().get(name),
+                None => None,
+            },
+        }
+    }
+
+    // 总是在当前环境定义变量，同名的变量会被覆盖（遮蔽外层的同名变量）
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.bindings.insert(name.to_owned(), value);
+    }
+
+    // 当前环境（不包括外层）的所有变量，按名称排序
+    pub fn bindings(&self) -> Vec<(String, Value)> {
+        let mut bindings: Vec<(String, Value)> = self
+            .bindings
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        bindings.sort_by(|left, right| left.0.cmp(&right.0));
+        bindings
+    }
+}
@@ -5,9 +5,42 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
+use std::fmt;
 
-#[derive(Debug)]
+use crate::token::Location;
+
+// 语法错误附带出错位置，语法分析错误的位置为 None 表示错误发生在文件末尾，
+// 运行时错误的位置为出错的表达式的位置，并附带出错时的调用栈
+#[derive(Debug, Clone, PartialEq)]
 pub enum Error {
-    LexerError(&'static str),
-    ParserError(&'static str),
+    LexerError(&'static str, Location),
+    ParserError(&'static str, Option<Location>),
+    RuntimeError(String, Location, Vec<StackFrame>),
+    // 超出了解释器的资源限制（参见 interpreter::Limits），位置和调用栈跟运行时错误相同
+    LimitError(Limit, Location, Vec<StackFrame>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    CallDepth,
+    Steps,
+    ValueSize,
+}
+
+// 调用栈的一层，location 为调用该函数的位置，
+// 匿名函数的 name 为 None
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    pub name: Option<String>,
+    pub location: Location,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::CallDepth => write!(f, "call depth limit exceeded"),
+            Limit::Steps => write!(f, "evaluation step limit exceeded"),
+            Limit::ValueSize => write!(f, "value size limit exceeded"),
+        }
+    }
 }
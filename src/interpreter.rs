@@ -0,0 +1,2175 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    rc::Rc,
+};
+
+use crate::ast::AnonymousFunction;
+use crate::ast::BinaryExpression;
+use crate::ast::BinaryOperator;
+use crate::ast::BlockExpression;
+use crate::ast::BranchExpression;
+use crate::ast::CallExpression;
+use crate::ast::ConstValue;
+use crate::ast::Expression;
+use crate::ast::FunctionDeclaration;
+use crate::ast::Identifier;
+use crate::ast::IfExpression;
+use crate::ast::IndexExpression;
+use crate::ast::LetExpression;
+use crate::ast::ListExpression;
+use crate::ast::Literal;
+use crate::ast::LiteralValue;
+use crate::ast::MapEntry;
+use crate::ast::MapExpression;
+use crate::ast::MatchExpression;
+use crate::ast::MatrixExpression;
+use crate::ast::MemberExpression;
+use crate::ast::Parameter;
+use crate::ast::Path;
+use crate::ast::Pattern;
+use crate::ast::PostfixExpression;
+use crate::ast::PostfixOperator;
+use crate::ast::PrefixExpression;
+use crate::ast::PrefixOperator;
+use crate::ast::Program;
+use crate::ast::RangeExpression;
+use crate::ast::RestPattern;
+use crate::ast::SequenceExpression;
+use crate::ast::Statement;
+use crate::ast::StructExpression;
+use crate::ast::StructPattern;
+use crate::ast::TupleExpression;
+use crate::ast::TupleIndexExpression;
+use crate::builtin;
+use crate::environment::Environment;
+use crate::error::Error;
+use crate::error::Limit;
+use crate::error::StackFrame;
+use crate::lexer;
+use crate::parser;
+use crate::token::Location;
+use crate::value::EnumValue;
+use crate::value::Function;
+use crate::value::Matrix;
+use crate::value::StructValue;
+use crate::value::UnionValue;
+use crate::value::Value;
+
+// 调试构建中每层非尾调用的递归大约占用 5 到 8 KB 的 Rust 调用栈，
+// 默认的限制使得递归在默认大小（2 MiB）的线程里也不会耗尽调用栈
+const DEFAULT_MAX_CALL_DEPTH: usize = 200;
+
+// 树遍历解释器，顶层环境（globals）在多次调用 eval_program 之间保留，
+// 内置函数 puts 的输出写到 output
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    types: HashMap<String, TypeDefinition>,
+    output: Box<dyn Write>,
+    limits: Limits,
+    // 本次 eval_program 已经执行的步数，以及当前（非尾调用的）函数调用的深度
+    steps: u64,
+    depth: usize,
+}
+
+// 每次执行程序（eval_program）的资源限制，None 表示不限制，超出限制时产生 Error::LimitError。
+// - max_call_depth：函数调用的嵌套层数，尾调用不增加层数，
+//   默认的限制用于避免递归耗尽 Rust 的调用栈；
+// - max_steps：求值表达式的次数；
+// - max_value_size：新创建的列表、数组、映射、矩阵的元素数量，或者字符串的字符数量。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub max_call_depth: Option<usize>,
+    pub max_steps: Option<u64>,
+    pub max_value_size: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+            max_steps: None,
+            max_value_size: None,
+        }
+    }
+}
+
+// 运行时只需要用户自定义类型的成员名称，用于构造结构体、联合体和枚举的值
+enum TypeDefinition {
+    Struct(Vec<String>),
+    Union(Vec<(String, Vec<String>)>),
+    Enum(Vec<String>),
+}
+
+// 求值没有正常得到值的原因：出错，或者 `?` 遇到 None 或者 Error 时从当前函数返回该值，
+// Propagate 由函数调用捕获，在函数之外使用 `?` 时由 eval_program 转换为运行时错误。
+// 内容放在堆上以减小求值函数的返回值，从而减小每一层递归占用的 Rust 调用栈
+enum Unwind {
+    Error(Box<Error>),
+    Propagate(Box<(Value, Location)>),
+}
+
+impl From<Error> for Unwind {
+    fn from(error: Error) -> Self {
+        Unwind::Error(Box::new(error))
+    }
+}
+
+// 尾部位置表达式的求值结果，Call 为尚未执行的函数调用
+enum Tail {
+    Value(Value),
+    Call(Rc<Function>, Vec<Value>, Location),
+}
+
+enum TypeMember {
+    // 成员的序号
+    Enum(usize),
+    // 成员的名称
+    Union(Vec<String>),
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_output(Box::new(io::stdout()))
+    }
+
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        let globals = Environment::new();
+        for builtin in &builtin::BUILTINS {
+            globals
+                .borrow_mut()
+                .define(builtin.name, Value::Builtin(builtin.name));
+        }
+
+        // 内置的联合体，参见 Value::some 等
+        let types = HashMap::from([
+            (
+                "Option".to_owned(),
+                TypeDefinition::Union(vec![
+                    ("Some".to_owned(), vec!["value".to_owned()]),
+                    ("None".to_owned(), vec![]),
+                ]),
+            ),
+            (
+                "Result".to_owned(),
+                TypeDefinition::Union(vec![
+                    ("Ok".to_owned(), vec!["value".to_owned()]),
+                    ("Error".to_owned(), vec!["error".to_owned()]),
+                ]),
+            ),
+        ]);
+
+        Interpreter {
+            globals,
+            types,
+            output,
+            limits: Limits::default(),
+            steps: 0,
+            depth: 0,
+        }
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    // 在顶层环境定义变量，用于向程序传递数据，比如命令行参数
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.globals.borrow_mut().define(name, value);
+    }
+
+    // 顶层环境里由程序定义的变量（不包括内置函数），按名称排序
+    pub fn globals(&self) -> Vec<(String, Value)> {
+        self.globals
+            .borrow()
+            .bindings()
+            .into_iter()
+            .filter(|(_, value)| !matches!(value, Value::Builtin(_)))
+            .collect()
+    }
+
+    // 依次执行各个语句，返回最后一个语句的值，
+    // 声明语句以及空程序的值为 `()`
+    pub fn eval_program(&mut self, program: &Program) -> Result<Value, Error> {
+        self.steps = 0;
+        self.depth = 0;
+        let globals = Rc::clone(&self.globals);
+        let mut last = Value::unit();
+        for statement in &program.statements {
+            last = match self.eval_statement(statement, &globals) {
+                Ok(value) => value,
+                Err(Unwind::Error(error)) => return Err(*error),
+                Err(Unwind::Propagate(propagated)) => {
+                    let (value, location) = *propagated;
+                    return Err(runtime_error(
+                        format!("cannot propagate {} outside of a function", value),
+                        &location,
+                    ));
+                }
+            };
+        }
+        Ok(last)
+    }
+
+    fn eval_statement(
+        &mut self,
+        statement: &Statement,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        match statement {
+            Statement::Expression(expression) => self.eval_expression(expression, environment),
+            Statement::Function(function) => {
+                let value = new_function(function, environment);
+                environment.borrow_mut().define(&function.name.name, value);
+                Ok(Value::unit())
+            }
+            Statement::Const(declaration) => {
+                match &declaration.value {
+                    ConstValue::Single(expression) => {
+                        let value = self.eval_expression(expression, environment)?;
+                        environment
+                            .borrow_mut()
+                            .define(&declaration.name.name, value);
+                    }
+                    ConstValue::Group(_) => {
+                        return Err(unsupported(
+                            "grouped const declarations",
+                            &declaration.location,
+                        ))
+                    }
+                }
+                Ok(Value::unit())
+            }
+            Statement::Struct(declaration) => {
+                let fields = declaration
+                    .fields
+                    .iter()
+                    .map(|field| field.name.name.clone())
+                    .collect();
+                self.types.insert(
+                    declaration.name.name.clone(),
+                    TypeDefinition::Struct(fields),
+                );
+                Ok(Value::unit())
+            }
+            Statement::Union(declaration) => {
+                let members = declaration
+                    .members
+                    .iter()
+                    .map(|member| {
+                        let fields = member
+                            .fields
+                            .iter()
+                            .map(|field| field.name.name.clone())
+                            .collect();
+                        (member.name.name.clone(), fields)
+                    })
+                    .collect();
+                self.types.insert(
+                    declaration.name.name.clone(),
+                    TypeDefinition::Union(members),
+                );
+                Ok(Value::unit())
+            }
+            Statement::Enum(declaration) => {
+                let members = declaration
+                    .members
+                    .iter()
+                    .map(|member| member.name.clone())
+                    .collect();
+                self.types
+                    .insert(declaration.name.name.clone(), TypeDefinition::Enum(members));
+                Ok(Value::unit())
+            }
+            // 特性和类型别名在运行时没有作用
+            Statement::Trait(_) | Statement::Alias(_) => Ok(Value::unit()),
+            Statement::Impl(declaration) => {
+                Err(unsupported("impl statements", &declaration.location))
+            }
+            Statement::Namespace(declaration) => {
+                Err(unsupported("namespace statements", &declaration.location))
+            }
+            Statement::Use(declaration) => {
+                Err(unsupported("use statements", &declaration.location))
+            }
+            Statement::Error(node) => Err(node.to_error().into()),
+        }
+    }
+
+    fn eval_expression(
+        &mut self,
+        expression: &Expression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        self.steps += 1;
+        if matches!(self.limits.max_steps, Some(max_steps) if self.steps > max_steps) {
+            return Err(limit_error(Limit::Steps, expression.location()).into());
+        }
+
+        match expression {
+            Expression::Literal(literal) => eval_literal(literal).map_err(Unwind::from),
+            Expression::Identifier(identifier) => eval_identifier(identifier, environment),
+            Expression::Block(_)
+            | Expression::If(_)
+            | Expression::Branch(_)
+            | Expression::Match(_)
+            | Expression::Call(_) => self
+                .eval_tail(expression, environment)
+                .and_then(|tail| self.finish(tail)),
+            Expression::Binary(e) => self.eval_binary(e, environment),
+            _ => self.eval_other_expression(expression, environment),
+        }
+    }
+
+    // 递归调用经常经过的表达式（函数调用、二元运算等）直接在 eval_expression 里分派，
+    // 其余的表达式在这里分派，以减小 eval_expression 占用的 Rust 调用栈
+    fn eval_other_expression(
+        &mut self,
+        expression: &Expression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        match expression {
+            Expression::Tuple(e) => self.eval_tuple(e, environment),
+            Expression::Path(path) => self.eval_path(path).map_err(Unwind::from),
+            Expression::List(e) => self.eval_list(e, environment),
+            Expression::Array(e) => self.eval_array(e, environment),
+            Expression::Matrix(e) => self.eval_matrix(e, environment),
+            Expression::Map(e) => self.eval_map(e, environment),
+            Expression::Range(e) => self.eval_range(e, environment),
+            Expression::Sequence(e) => self.eval_sequence(e, environment),
+            Expression::Struct(e) => self.eval_struct(e, environment),
+            Expression::Let(e) => self.eval_let(e, environment),
+            Expression::AnonymousFunction(function) => {
+                Ok(new_anonymous_function(function, None, environment))
+            }
+            Expression::Prefix(e) => self.eval_prefix(e, environment),
+            Expression::Postfix(e) => self.eval_postfix(e, environment),
+            Expression::Member(e) => self.eval_member(e, environment),
+            Expression::TupleIndex(e) => self.eval_tuple_index(e, environment),
+            Expression::Index(e) => self.eval_index(e, environment),
+            Expression::ForLet(e) => Err(unsupported("for-let loops", &e.location)),
+            Expression::ForIn(e) => Err(unsupported("for-in loops", &e.location)),
+            Expression::Each(e) => Err(unsupported("each loops", &e.location)),
+            Expression::Next(e) => Err(unsupported("next expressions", &e.location)),
+            Expression::Slice(e) => Err(unsupported("slices", &e.location)),
+            Expression::Spread(e) => Err(unsupported("spreads outside of lists", &e.location)),
+            Expression::Error(node) => Err(node.to_error().into()),
+            // 已经由 eval_expression 分派
+            Expression::Literal(_)
+            | Expression::Identifier(_)
+            | Expression::Block(_)
+            | Expression::If(_)
+            | Expression::Branch(_)
+            | Expression::Match(_)
+            | Expression::Call(_)
+            | Expression::Binary(_) => unreachable!(),
+        }
+    }
+
+    // 求值位于尾部位置的表达式，其中的函数调用不立即执行，而是返回 Tail::Call，
+    // 由 call_function 的循环执行，所以尾调用（包括相互递归）不会增加调用栈的深度
+    fn eval_tail(
+        &mut self,
+        expression: &Expression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        match expression {
+            Expression::Block(block) => self.eval_block(block, environment),
+            Expression::If(e) => self.eval_if(e, environment),
+            Expression::Branch(e) => self.eval_branch(e, environment),
+            Expression::Match(e) => self.eval_match(e, environment),
+            Expression::Call(e) => self.eval_call(e, environment),
+            _ => Ok(Tail::Value(self.eval_expression(expression, environment)?)),
+        }
+    }
+
+    fn finish(&mut self, tail: Tail) -> Result<Value, Unwind> {
+        match tail {
+            Tail::Value(value) => Ok(value),
+            Tail::Call(function, arguments, location) => {
+                Ok(self.call_function(function, arguments, &location)?)
+            }
+        }
+    }
+
+    fn eval_expressions(
+        &mut self,
+        expressions: &[Expression],
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Vec<Value>, Unwind> {
+        expressions
+            .iter()
+            .map(|expression| self.eval_expression(expression, environment))
+            .collect()
+    }
+
+    fn eval_tuple(
+        &mut self,
+        expression: &TupleExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let elements = self.eval_expressions(&expression.elements, environment)?;
+        Ok(Value::Tuple(Rc::new(elements)))
+    }
+
+    fn eval_list(
+        &mut self,
+        expression: &ListExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let elements = self.eval_elements(expression, environment)?;
+        Ok(Value::List(Rc::new(elements)))
+    }
+
+    fn eval_array(
+        &mut self,
+        expression: &ListExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let elements = self.eval_elements(expression, environment)?;
+        Ok(Value::Array(Rc::new(elements)))
+    }
+
+    // 列表和数组的元素，`...value` 展开另一个列表或者数组
+    fn eval_elements(
+        &mut self,
+        expression: &ListExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Vec<Value>, Unwind> {
+        let mut elements = vec![];
+        for element in &expression.elements {
+            match element {
+                Expression::Spread(spread) => {
+                    match self.eval_expression(&spread.value, environment)? {
+                        Value::List(values) | Value::Array(values) => {
+                            elements.extend(values.iter().cloned())
+                        }
+                        value => {
+                            return Err(runtime_error(
+                                format!("cannot spread {}", value.type_name()),
+                                &spread.location,
+                            ))
+                        }
+                    }
+                }
+                _ => elements.push(self.eval_expression(element, environment)?),
+            }
+            self.check_size(elements.len(), &expression.location)?;
+        }
+        Ok(elements)
+    }
+
+    fn eval_matrix(
+        &mut self,
+        expression: &MatrixExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let columns = expression.rows.first().map_or(0, |row| row.len());
+        let mut elements = vec![];
+        for row in &expression.rows {
+            if row.len() != columns {
+                return Err(runtime_error(
+                    "matrix rows must have the same length",
+                    &expression.location,
+                ));
+            }
+            elements.extend(self.eval_expressions(row, environment)?);
+        }
+
+        self.check_size(elements.len(), &expression.location)?;
+        Ok(Value::Matrix(Rc::new(Matrix {
+            rows: expression.rows.len(),
+            columns,
+            elements,
+        })))
+    }
+
+    // 后出现的同名成员覆盖先出现的，`...other` 展开另一个映射
+    fn eval_map(
+        &mut self,
+        expression: &MapExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let mut entries = BTreeMap::new();
+        for entry in &expression.entries {
+            match entry {
+                MapEntry::Pair(pair) => {
+                    let value = self.eval_expression(&pair.value, environment)?;
+                    entries.insert(pair.name.name.clone(), value);
+                }
+                MapEntry::Spread(spread) => {
+                    match self.eval_expression(&spread.value, environment)? {
+                        Value::Map(other) => {
+                            entries.extend(other.iter().map(|(k, v)| (k.clone(), v.clone())))
+                        }
+                        value => {
+                            return Err(runtime_error(
+                                format!("cannot spread {}", value.type_name()),
+                                &spread.location,
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+        self.check_size(entries.len(), &expression.location)?;
+        Ok(Value::Map(Rc::new(entries)))
+    }
+
+    // 范围 `[start..end]` 包括 end
+    fn eval_range(
+        &mut self,
+        expression: &RangeExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let start = self.eval_integer(&expression.start, environment)?;
+        let end = self.eval_integer(&expression.end, environment)?;
+        Ok(Value::Range(start, end))
+    }
+
+    // 数列 `[1, 3, ..9]` 的步长为前两个元素的差，只有一个元素时步长为 1，
+    // 结果是包括 end（如果恰好落在数列上）的列表
+    fn eval_sequence(
+        &mut self,
+        expression: &SequenceExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let mut elements = vec![];
+        for element in &expression.elements {
+            elements.push(self.eval_integer(element, environment)?);
+        }
+        let end = self.eval_integer(&expression.end, environment)?;
+
+        let step = match elements.as_slice() {
+            [.., previous, last] => match last.checked_sub(*previous) {
+                Some(step) => step,
+                None => return Err(runtime_error("integer overflow", &expression.location)),
+            },
+            _ => 1,
+        };
+        if step == 0 {
+            return Err(runtime_error(
+                "sequence step cannot be zero",
+                &expression.location,
+            ));
+        }
+
+        let mut values: Vec<Value> = elements.iter().map(|i| Value::Integer(*i)).collect();
+        let mut current = elements.last().copied().unwrap_or(end);
+        loop {
+            current = match current.checked_add(step) {
+                Some(next) if (step > 0 && next <= end) || (step < 0 && next >= end) => next,
+                _ => break,
+            };
+            values.push(Value::Integer(current));
+            self.check_size(values.len(), &expression.location)?;
+        }
+        Ok(Value::List(Rc::new(values)))
+    }
+
+    fn eval_integer(
+        &mut self,
+        expression: &Expression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<i64, Unwind> {
+        match self.eval_expression(expression, environment)? {
+            Value::Integer(value) => Ok(value),
+            value => Err(runtime_error(
+                format!("expected Int, found {}", value.type_name()),
+                expression.location(),
+            )),
+        }
+    }
+
+    // 按位置或者按名称实例化已声明的结构体，成员的顺序跟声明一致
+    fn eval_struct(
+        &mut self,
+        expression: &StructExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let name = match &expression.name {
+            Some(name) => name,
+            None => {
+                return Err(runtime_error(
+                    "cannot infer the struct type",
+                    &expression.location,
+                ))
+            }
+        };
+
+        let field_names = match self.types.get(&name.name) {
+            Some(TypeDefinition::Struct(field_names)) => field_names.clone(),
+            _ => {
+                return Err(runtime_error(
+                    format!("undefined struct \"{}\"", name.name),
+                    &name.location,
+                ))
+            }
+        };
+
+        let mut values = vec![];
+        for field in &expression.fields {
+            values.push(self.eval_expression(&field.value, environment)?);
+        }
+
+        let is_positional = expression.fields.iter().all(|field| field.name.is_none());
+        let fields = if is_positional {
+            if values.len() != field_names.len() {
+                return Err(runtime_error(
+                    format!(
+                        "expected {} fields, found {}",
+                        field_names.len(),
+                        values.len()
+                    ),
+                    &expression.location,
+                ));
+            }
+            field_names.into_iter().zip(values).collect()
+        } else {
+            for field in &expression.fields {
+                if let Some(field_name) = &field.name {
+                    if !field_names.contains(&field_name.name) {
+                        return Err(runtime_error(
+                            format!("unknown field \"{}\"", field_name.name),
+                            &field_name.location,
+                        ));
+                    }
+                }
+            }
+
+            let mut fields = vec![];
+            for field_name in field_names {
+                let position = expression
+                    .fields
+                    .iter()
+                    .position(|field| matches!(&field.name, Some(name) if name.name == field_name));
+                match position {
+                    Some(position) => fields.push((field_name, values[position].clone())),
+                    None => {
+                        return Err(runtime_error(
+                            format!("missing field \"{}\"", field_name),
+                            &expression.location,
+                        ))
+                    }
+                }
+            }
+            fields
+        };
+
+        Ok(Value::Struct(Rc::new(StructValue {
+            name: name.name.clone(),
+            fields,
+        })))
+    }
+
+    // `Enum::Member` 或者不带数据的联合体成员 `Union::Member`
+    fn eval_path(&self, path: &Path) -> Result<Value, Error> {
+        match self.find_type_member(path) {
+            Some((name, TypeMember::Enum(index))) => Ok(Value::Enum(Rc::new(EnumValue {
+                name,
+                member: path.segments[1].name.clone(),
+                index,
+            }))),
+            Some((name, TypeMember::Union(field_names))) if field_names.is_empty() => {
+                Ok(Value::Union(Rc::new(UnionValue {
+                    name,
+                    member: path.segments[1].name.clone(),
+                    fields: vec![],
+                })))
+            }
+            Some(_) => Err(runtime_error(
+                "union member with fields must be called with arguments",
+                &path.location,
+            )),
+            None => Err(runtime_error(
+                format!("undefined path \"{}\"", path),
+                &path.location,
+            )),
+        }
+    }
+
+    // 路径的第一段为类型名称，第二段为成员名称
+    fn find_type_member(&self, path: &Path) -> Option<(String, TypeMember)> {
+        let [type_name, member_name] = path.segments.as_slice() else {
+            return None;
+        };
+
+        let member = match self.types.get(&type_name.name)? {
+            TypeDefinition::Enum(members) => {
+                TypeMember::Enum(members.iter().position(|m| m == &member_name.name)?)
+            }
+            TypeDefinition::Union(members) => {
+                let (_, field_names) = members.iter().find(|(m, _)| m == &member_name.name)?;
+                TypeMember::Union(field_names.clone())
+            }
+            TypeDefinition::Struct(_) => return None,
+        };
+        Some((type_name.name.clone(), member))
+    }
+
+    fn eval_member(
+        &mut self,
+        expression: &MemberExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let object = self.eval_expression(&expression.object, environment)?;
+        let name = &expression.property.name;
+        let value = match &object {
+            Value::Struct(value) => find_field(&value.fields, name),
+            Value::Union(value) => find_field(&value.fields, name),
+            Value::Map(entries) => entries.get(name).cloned(),
+            _ => None,
+        };
+
+        value.ok_or_else(|| {
+            runtime_error(
+                format!("{} has no member \"{}\"", object.type_name(), name),
+                &expression.property.location,
+            )
+        })
+    }
+
+    fn eval_tuple_index(
+        &mut self,
+        expression: &TupleIndexExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        match self.eval_expression(&expression.object, environment)? {
+            Value::Tuple(elements) => match elements.get(expression.index) {
+                Some(value) => Ok(value.clone()),
+                None => Err(runtime_error(
+                    format!("index {} out of range", expression.index),
+                    &expression.location,
+                )),
+            },
+            value => Err(runtime_error(
+                format!("cannot index {} with a tuple index", value.type_name()),
+                &expression.location,
+            )),
+        }
+    }
+
+    // 表达式块创建新的作用域，块的值为最后一个表达式的值
+    fn eval_block(
+        &mut self,
+        block: &BlockExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        let scope = Environment::new_child(environment);
+        match block.expressions.split_last() {
+            Some((last, expressions)) => {
+                for expression in expressions {
+                    self.eval_expression(expression, &scope)?;
+                }
+                self.eval_tail(last, &scope)
+            }
+            None => Ok(Tail::Value(Value::unit())),
+        }
+    }
+
+    // `let pattern = value` 的值为 `()`，模式不匹配时是运行时错误；
+    // `let pattern match value` 的值为是否匹配
+    fn eval_let(
+        &mut self,
+        expression: &LetExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        // `let name = fn ...` 以变量名作为函数的名称，用于调用栈
+        let value = match (&expression.pattern, &expression.value) {
+            (Pattern::Identifier(identifier), Expression::AnonymousFunction(function)) => {
+                new_anonymous_function(function, Some(identifier.name.clone()), environment)
+            }
+            _ => self.eval_expression(&expression.value, environment)?,
+        };
+        let is_matched = bind_pattern(&expression.pattern, &value, environment)?;
+
+        if expression.is_match {
+            Ok(Value::Boolean(is_matched))
+        } else if is_matched {
+            Ok(Value::unit())
+        } else {
+            Err(runtime_error(
+                format!("pattern does not match value {}", value),
+                expression.pattern.location(),
+            ))
+        }
+    }
+
+    // where 子表达式定义的变量的作用域为整个 if 表达式，
+    // 没有 else 而且条件不成立时值为 `()`
+    fn eval_if(
+        &mut self,
+        expression: &IfExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        let scope = Environment::new_child(environment);
+        if let Some(where_clause) = &expression.where_clause {
+            self.eval_expression(where_clause, &scope)?;
+        }
+
+        if self.eval_condition(&expression.condition, &scope)? {
+            self.eval_tail(&expression.consequent, &scope)
+        } else {
+            match &expression.alternative {
+                Some(alternative) => self.eval_tail(alternative, &scope),
+                None => Ok(Tail::Value(Value::unit())),
+            }
+        }
+    }
+
+    // 依次检查各个 case 的条件，case 的 where 子表达式在条件之前求值，
+    // 没有条件成立而且没有 default 时值为 `()`
+    fn eval_branch(
+        &mut self,
+        expression: &BranchExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        let scope = Environment::new_child(environment);
+        if let Some(where_clause) = &expression.where_clause {
+            self.eval_expression(where_clause, &scope)?;
+        }
+
+        for case in &expression.cases {
+            let case_scope = Environment::new_child(&scope);
+            if let Some(where_clause) = &case.where_clause {
+                self.eval_expression(where_clause, &case_scope)?;
+            }
+            if self.eval_condition(&case.condition, &case_scope)? {
+                return self.eval_tail(&case.body, &case_scope);
+            }
+        }
+
+        match &expression.default {
+            Some(default) => self.eval_tail(default, &scope),
+            None => Ok(Tail::Value(Value::unit())),
+        }
+    }
+
+    // 依次尝试各个 case 的模式，模式匹配之后绑定 `to` 变量、求值 where 子表达式，
+    // 最后检查 only 条件，没有 case 匹配而且没有 default 时是运行时错误
+    fn eval_match(
+        &mut self,
+        expression: &MatchExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        let scope = Environment::new_child(environment);
+        if let Some(where_clause) = &expression.where_clause {
+            self.eval_expression(where_clause, &scope)?;
+        }
+        let subject = self.eval_expression(&expression.subject, &scope)?;
+
+        for case in &expression.cases {
+            for pattern in &case.patterns {
+                let case_scope = Environment::new_child(&scope);
+                if !bind_pattern(pattern, &subject, &case_scope)? {
+                    continue;
+                }
+
+                let clauses = &case.clauses;
+                if let Some(to) = &clauses.to {
+                    case_scope.borrow_mut().define(&to.name, subject.clone());
+                }
+                if let Some(where_clause) = &clauses.where_clause {
+                    self.eval_expression(where_clause, &case_scope)?;
+                }
+                if let Some(only) = &clauses.only {
+                    if !self.eval_condition(only, &case_scope)? {
+                        continue;
+                    }
+                }
+                return self.eval_tail(&case.body, &case_scope);
+            }
+        }
+
+        match &expression.default {
+            Some(default) => self.eval_tail(default, &scope),
+            None => Err(runtime_error(
+                format!("no case matches value {}", subject),
+                expression.subject.location(),
+            )),
+        }
+    }
+
+    fn eval_condition(
+        &mut self,
+        expression: &Expression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<bool, Unwind> {
+        match self.eval_expression(expression, environment)? {
+            Value::Boolean(value) => Ok(value),
+            value => Err(runtime_error(
+                format!("expected Boolean, found {}", value.type_name()),
+                expression.location(),
+            )),
+        }
+    }
+
+    // `value?` 展开 Some 或者 Ok，遇到 None 或者 Error 时从当前函数返回该值
+    fn eval_postfix(
+        &mut self,
+        expression: &PostfixExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        match expression.operator {
+            PostfixOperator::Unwrap => {
+                let operand = self.eval_expression(&expression.operand, environment)?;
+                match operand.unwrap() {
+                    Some(Ok(value)) => Ok(value),
+                    Some(Err(failure)) => Err(Unwind::Propagate(Box::new((
+                        failure,
+                        expression.location.clone(),
+                    )))),
+                    None => Err(runtime_error(
+                        format!("cannot unwrap {}", operand.type_name()),
+                        &expression.location,
+                    )),
+                }
+            }
+            PostfixOperator::Cast => Err(unsupported("casts (^)", &expression.location)),
+        }
+    }
+
+    fn eval_prefix(
+        &mut self,
+        expression: &PrefixExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let operand = self.eval_expression(&expression.operand, environment)?;
+        match (expression.operator, operand) {
+            (PrefixOperator::Negative, Value::Integer(value)) => match value.checked_neg() {
+                Some(value) => Ok(Value::Integer(value)),
+                None => Err(runtime_error("integer overflow", &expression.location)),
+            },
+            (PrefixOperator::Negative, value) => Err(runtime_error(
+                format!("cannot negate {}", value.type_name()),
+                &expression.location,
+            )),
+        }
+    }
+
+    fn eval_binary(
+        &mut self,
+        expression: &BinaryExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        // 逻辑运算短路求值
+        match expression.operator {
+            BinaryOperator::LogicAnd => {
+                return Ok(Value::Boolean(
+                    self.eval_condition(&expression.left, environment)?
+                        && self.eval_condition(&expression.right, environment)?,
+                ))
+            }
+            BinaryOperator::LogicOr => {
+                return Ok(Value::Boolean(
+                    self.eval_condition(&expression.left, environment)?
+                        || self.eval_condition(&expression.right, environment)?,
+                ))
+            }
+            // 只有左侧为 None 或者 Error 时才求值右侧的默认值
+            BinaryOperator::UnwrapOr => {
+                let left = self.eval_expression(&expression.left, environment)?;
+                return match left.unwrap() {
+                    Some(Ok(value)) => Ok(value),
+                    Some(Err(_)) => self.eval_expression(&expression.right, environment),
+                    None => Err(runtime_error(
+                        format!("cannot unwrap {}", left.type_name()),
+                        expression.left.location(),
+                    )),
+                };
+            }
+            _ => {}
+        }
+
+        let left = self.eval_expression(&expression.left, environment)?;
+        let right = self.eval_expression(&expression.right, environment)?;
+        Ok(self.apply_binary(expression.operator, left, right, &expression.location)?)
+    }
+
+    // 二元运算本身不递归求值，单独成为一个函数以减小 eval_binary 占用的 Rust 调用栈
+    fn apply_binary(
+        &self,
+        operator: BinaryOperator,
+        left: Value,
+        right: Value,
+        location: &Location,
+    ) -> Result<Value, Error> {
+        match (operator, &left, &right) {
+            (BinaryOperator::Equal, _, _) => Ok(Value::Boolean(left == right)),
+            (BinaryOperator::NotEqual, _, _) => Ok(Value::Boolean(left != right)),
+            (BinaryOperator::Add, Value::Integer(l), Value::Integer(r)) => {
+                checked_integer(l.checked_add(*r), location)
+            }
+            (BinaryOperator::Subtract, Value::Integer(l), Value::Integer(r)) => {
+                checked_integer(l.checked_sub(*r), location)
+            }
+            (BinaryOperator::Multiply, Value::Integer(l), Value::Integer(r)) => {
+                checked_integer(l.checked_mul(*r), location)
+            }
+            (BinaryOperator::Divide, Value::Integer(_), Value::Integer(0)) => {
+                Err(runtime_error("division by zero", location))
+            }
+            (BinaryOperator::Divide, Value::Integer(l), Value::Integer(r)) => {
+                checked_integer(l.checked_div(*r), location)
+            }
+            (BinaryOperator::Concat, Value::String(l), Value::String(r)) => {
+                self.check_size(l.chars().count() + r.chars().count(), location)?;
+                Ok(Value::String(Rc::new(format!("{}{}", l, r))))
+            }
+            (BinaryOperator::Concat, Value::List(l), Value::List(r)) => {
+                self.check_size(l.len() + r.len(), location)?;
+                let elements = l.iter().chain(r.iter()).cloned().collect();
+                Ok(Value::List(Rc::new(elements)))
+            }
+            (BinaryOperator::Concat, Value::Array(l), Value::Array(r)) => {
+                self.check_size(l.len() + r.len(), location)?;
+                let elements = l.iter().chain(r.iter()).cloned().collect();
+                Ok(Value::Array(Rc::new(elements)))
+            }
+            (
+                BinaryOperator::GreaterThan
+                | BinaryOperator::GreaterThanOrEqual
+                | BinaryOperator::LessThan
+                | BinaryOperator::LessThanOrEqual,
+                _,
+                _,
+            ) => {
+                let ordering = match left.partial_cmp(&right) {
+                    Some(ordering) => ordering,
+                    None => return Err(operand_error(operator, &left, &right, location)),
+                };
+                let result = match operator {
+                    BinaryOperator::GreaterThan => ordering.is_gt(),
+                    BinaryOperator::GreaterThanOrEqual => ordering.is_ge(),
+                    BinaryOperator::LessThan => ordering.is_lt(),
+                    _ => ordering.is_le(),
+                };
+                Ok(Value::Boolean(result))
+            }
+            _ => Err(operand_error(operator, &left, &right, location)),
+        }
+    }
+
+    // 调用程序里的函数时返回 Tail::Call，由调用者决定何时执行
+    fn eval_call(
+        &mut self,
+        expression: &CallExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Tail, Unwind> {
+        if !expression.keyword_arguments.is_empty() {
+            return Err(unsupported("keyword arguments", &expression.location));
+        }
+
+        // 带数据的联合体成员使用调用的形式构造 `Shape::Circle(1)`
+        if let Expression::Path(path) = &expression.callee {
+            if let Some((name, TypeMember::Union(field_names))) = self.find_type_member(path) {
+                return self
+                    .construct_union(expression, path, name, field_names, environment)
+                    .map(Tail::Value);
+            }
+        }
+
+        let callee = self.eval_expression(&expression.callee, environment)?;
+        let arguments = self.eval_expressions(&expression.arguments, environment)?;
+
+        match callee {
+            Value::Function(function) => {
+                Ok(Tail::Call(function, arguments, expression.location.clone()))
+            }
+            Value::Builtin(name) => Ok(Tail::Value(builtin::call(
+                self,
+                name,
+                &arguments,
+                &expression.location,
+            )?)),
+            value => Err(runtime_error(
+                format!("{} is not callable", value.type_name()),
+                expression.callee.location(),
+            )),
+        }
+    }
+
+    fn construct_union(
+        &mut self,
+        expression: &CallExpression,
+        path: &Path,
+        name: String,
+        field_names: Vec<String>,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let arguments = self.eval_expressions(&expression.arguments, environment)?;
+        if arguments.len() != field_names.len() {
+            return Err(runtime_error(
+                format!(
+                    "expected {} arguments, found {}",
+                    field_names.len(),
+                    arguments.len()
+                ),
+                &expression.location,
+            ));
+        }
+        Ok(Value::Union(Rc::new(UnionValue {
+            name,
+            member: path.segments[1].name.clone(),
+            fields: field_names.into_iter().zip(arguments).collect(),
+        })))
+    }
+
+    // 函数体里的尾调用在这里循环执行，出错时记录当前函数及其调用位置，
+    // 被尾调用取代的函数不再出现在调用栈里
+    fn call_function(
+        &mut self,
+        function: Rc<Function>,
+        arguments: Vec<Value>,
+        location: &Location,
+    ) -> Result<Value, Error> {
+        if matches!(self.limits.max_call_depth, Some(max_depth) if self.depth >= max_depth) {
+            return Err(limit_error(Limit::CallDepth, location));
+        }
+
+        self.depth += 1;
+        let result = self.call_function_body(function, arguments, location);
+        self.depth -= 1;
+        result
+    }
+
+    fn call_function_body(
+        &mut self,
+        function: Rc<Function>,
+        arguments: Vec<Value>,
+        location: &Location,
+    ) -> Result<Value, Error> {
+        let mut function = function;
+        let mut arguments = arguments;
+        let mut location = location.clone();
+        loop {
+            // 参数的默认值也在函数之内求值，其中的 `?` 同样从该函数返回
+            let result = self
+                .bind_arguments(&function, arguments, &location)
+                .and_then(|scope| self.eval_tail(&function.body, &scope));
+            match result {
+                Ok(Tail::Value(value)) => return Ok(value),
+                Ok(Tail::Call(next_function, next_arguments, next_location)) => {
+                    function = next_function;
+                    arguments = next_arguments;
+                    location = next_location;
+                }
+                Err(Unwind::Error(error)) => return Err(push_frame(*error, &function, &location)),
+                Err(Unwind::Propagate(propagated)) => return Ok(propagated.0),
+            }
+        }
+    }
+
+    // 在闭包的环境之上创建函数的作用域，缺少的参数使用默认值（在函数的作用域里求值）
+    fn bind_arguments(
+        &mut self,
+        function: &Function,
+        arguments: Vec<Value>,
+        location: &Location,
+    ) -> Result<Rc<RefCell<Environment>>, Unwind> {
+        if arguments.len() > function.parameters.len() {
+            return Err(runtime_error(
+                format!(
+                    "expected {} arguments, found {}",
+                    function.parameters.len(),
+                    arguments.len()
+                ),
+                location,
+            ));
+        }
+
+        let scope = Environment::new_child(&function.environment);
+        let mut arguments = arguments.into_iter();
+        for parameter in &function.parameters {
+            let value = match (arguments.next(), &parameter.default_value) {
+                (Some(value), _) => value,
+                (None, Some(default_value)) => self.eval_expression(default_value, &scope)?,
+                (None, None) => {
+                    return Err(runtime_error(
+                        format!("missing argument for parameter {}", parameter.pattern),
+                        location,
+                    ))
+                }
+            };
+
+            if !bind_pattern(&parameter.pattern, &value, &scope)? {
+                return Err(runtime_error(
+                    format!(
+                        "argument {} does not match parameter {}",
+                        value, parameter.pattern
+                    ),
+                    location,
+                ));
+            }
+        }
+
+        Ok(scope)
+    }
+
+    // 检查新创建的值的大小（元素或者字符的数量）
+    pub(crate) fn check_size(&self, size: usize, location: &Location) -> Result<(), Error> {
+        match self.limits.max_value_size {
+            Some(max_size) if size > max_size => Err(limit_error(Limit::ValueSize, location)),
+            _ => Ok(()),
+        }
+    }
+
+    // 调用函数值，供内置函数（比如 map）回调程序里的函数
+    pub(crate) fn call_value(
+        &mut self,
+        callee: &Value,
+        arguments: Vec<Value>,
+        location: &Location,
+    ) -> Result<Value, Error> {
+        match callee {
+            Value::Function(function) => {
+                self.call_function(Rc::clone(function), arguments, location)
+            }
+            Value::Builtin(name) => builtin::call(self, name, &arguments, location),
+            value => Err(runtime_error(
+                format!("{} is not callable", value.type_name()),
+                location,
+            )),
+        }
+    }
+
+    pub(crate) fn write_line(&mut self, text: &str, location: &Location) -> Result<Value, Error> {
+        match writeln!(self.output, "{}", text) {
+            Ok(_) => Ok(Value::unit()),
+            Err(e) => Err(runtime_error(
+                format!("failed to write output: {}", e),
+                location,
+            )),
+        }
+    }
+
+    fn eval_index(
+        &mut self,
+        expression: &IndexExpression,
+        environment: &Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let object = self.eval_expression(&expression.object, environment)?;
+        let index = self.eval_expression(&expression.index, environment)?;
+
+        match (&object, &index) {
+            (Value::List(elements) | Value::Array(elements), Value::Integer(index)) => {
+                match usize::try_from(*index).ok().and_then(|i| elements.get(i)) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(runtime_error(
+                        format!("index {} out of range", index),
+                        expression.index.location(),
+                    )),
+                }
+            }
+            (Value::Map(entries), Value::String(key)) => match entries.get(key.as_str()) {
+                Some(value) => Ok(value.clone()),
+                None => Err(runtime_error(
+                    format!("key {} not found", index),
+                    expression.index.location(),
+                )),
+            },
+            _ => Err(runtime_error(
+                format!(
+                    "cannot index {} with {}",
+                    object.type_name(),
+                    index.type_name()
+                ),
+                &expression.location,
+            )),
+        }
+    }
+}
+
+// 解析并执行一段程序，主要用于单元测试和嵌入
+pub fn eval_from_str(text: &str) -> Result<Value, Error> {
+    let tokens = lexer::tokenize(text)?;
+    let program = parser::parse(&tokens)?;
+    Interpreter::new().eval_program(&program)
+}
+
+fn limit_error(limit: Limit, location: &Location) -> Error {
+    Error::LimitError(limit, location.clone(), vec![])
+}
+
+// 能够解析但是还不能求值的语法，construct 为复数形式的名称，比如 "for-let loops"
+fn unsupported<E: From<Error>>(construct: &str, location: &Location) -> E {
+    runtime_error(format!("{} are not supported yet", construct), location)
+}
+
+// 返回值可以是 Error 或者 Unwind
+fn runtime_error<E: From<Error>>(message: impl Into<String>, location: &Location) -> E {
+    E::from(Error::RuntimeError(
+        message.into(),
+        location.clone(),
+        vec![],
+    ))
+}
+
+// 错误离开函数体时记录该函数及其调用位置，所以调用栈按从内到外的顺序排列
+fn push_frame(error: Error, function: &Function, location: &Location) -> Error {
+    let frame = StackFrame {
+        name: function.name.clone(),
+        location: location.clone(),
+    };
+    match error {
+        Error::RuntimeError(message, error_location, mut frames) => {
+            frames.push(frame);
+            Error::RuntimeError(message, error_location, frames)
+        }
+        Error::LimitError(limit, error_location, mut frames) => {
+            frames.push(frame);
+            Error::LimitError(limit, error_location, frames)
+        }
+        _ => error,
+    }
+}
+
+fn operand_error(
+    operator: BinaryOperator,
+    left: &Value,
+    right: &Value,
+    location: &Location,
+) -> Error {
+    runtime_error(
+        format!(
+            "unsupported operand types for {}: {} and {}",
+            operator,
+            left.type_name(),
+            right.type_name()
+        ),
+        location,
+    )
+}
+
+fn find_field(fields: &[(String, Value)], name: &str) -> Option<Value> {
+    fields
+        .iter()
+        .find(|(field_name, _)| field_name == name)
+        .map(|(_, value)| value.clone())
+}
+
+fn eval_identifier(
+    identifier: &Identifier,
+    environment: &Rc<RefCell<Environment>>,
+) -> Result<Value, Unwind> {
+    match environment.borrow().get(&identifier.name) {
+        Some(value) => Ok(value),
+        None => Err(runtime_error(
+            format!("undefined identifier \"{}\"", identifier.name),
+            &identifier.location,
+        )),
+    }
+}
+
+fn checked_integer(value: Option<i64>, location: &Location) -> Result<Value, Error> {
+    match value {
+        Some(value) => Ok(Value::Integer(value)),
+        None => Err(runtime_error("integer overflow", location)),
+    }
+}
+
+fn eval_literal(literal: &Literal) -> Result<Value, Error> {
+    match &literal.value {
+        LiteralValue::Integer(value) => Ok(Value::Integer(*value)),
+        LiteralValue::Boolean(value) => Ok(Value::Boolean(*value)),
+        LiteralValue::Char(value) => Ok(Value::Char(*value)),
+        LiteralValue::String(value) => Ok(Value::String(Rc::new(value.clone()))),
+        LiteralValue::TemplateString(_) => Err(unsupported("template strings", &literal.location)),
+        LiteralValue::HashString(_) => Err(unsupported("hash strings", &literal.location)),
+        LiteralValue::Regexp(_) => Err(unsupported("regular expressions", &literal.location)),
+    }
+}
+
+fn new_function(function: &FunctionDeclaration, environment: &Rc<RefCell<Environment>>) -> Value {
+    let body = match &function.body {
+        Some(body) => body.clone(),
+        None => Expression::Tuple(TupleExpression {
+            elements: vec![],
+            location: function.location.clone(),
+        }),
+    };
+    new_closure(
+        Some(function.name.name.clone()),
+        &function.parameters,
+        body,
+        environment,
+    )
+}
+
+fn new_anonymous_function(
+    function: &AnonymousFunction,
+    name: Option<String>,
+    environment: &Rc<RefCell<Environment>>,
+) -> Value {
+    new_closure(
+        name,
+        &function.parameters,
+        function.body.clone(),
+        environment,
+    )
+}
+
+fn new_closure(
+    name: Option<String>,
+    parameters: &[Parameter],
+    body: Expression,
+    environment: &Rc<RefCell<Environment>>,
+) -> Value {
+    Value::Function(Rc::new(Function {
+        name,
+        parameters: parameters.to_vec(),
+        body,
+        environment: Rc::clone(environment),
+    }))
+}
+
+// 把值绑定到模式，模式里的变量定义在 environment，返回是否匹配
+fn bind_pattern(
+    pattern: &Pattern,
+    value: &Value,
+    environment: &Rc<RefCell<Environment>>,
+) -> Result<bool, Error> {
+    match (pattern, value) {
+        (Pattern::Wildcard(_), _) => Ok(true),
+        (Pattern::Identifier(identifier), _) => {
+            environment
+                .borrow_mut()
+                .define(&identifier.name, value.clone());
+            Ok(true)
+        }
+        (Pattern::Literal(literal), _) => Ok(&eval_literal(literal)? == value),
+        (Pattern::Tuple(tuple), Value::Tuple(elements)) => {
+            bind_elements(&tuple.elements, elements, environment)
+        }
+        (Pattern::List(list), Value::List(elements)) => {
+            bind_elements(&list.elements, elements, environment)
+        }
+        (Pattern::Array(array), Value::Array(elements)) => {
+            bind_elements(&array.elements, elements, environment)
+        }
+        (Pattern::Struct(pattern), Value::Struct(value)) => {
+            let is_name_matched = match &pattern.name {
+                Some(name) => {
+                    matches!(name.segments.as_slice(), [type_name] if type_name.name == value.name)
+                }
+                None => true,
+            };
+            Ok(is_name_matched && bind_fields(pattern, &value.fields, environment)?)
+        }
+        // 联合体成员的名称可以省略联合体的名称，比如 `Some {value}`
+        (Pattern::Struct(pattern), Value::Union(value)) => {
+            let is_name_matched = match pattern.name.as_ref().map(|name| name.segments.as_slice()) {
+                Some([member]) => member.name == value.member,
+                Some([union_name, member]) => {
+                    union_name.name == value.name && member.name == value.member
+                }
+                Some(_) => false,
+                None => true,
+            };
+            Ok(is_name_matched && bind_fields(pattern, &value.fields, environment)?)
+        }
+        (Pattern::Struct(pattern), Value::Map(entries)) if pattern.name.is_none() => {
+            let fields: Vec<(String, Value)> = entries
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            bind_fields(pattern, &fields, environment)
+        }
+        (Pattern::Tuple(_) | Pattern::List(_) | Pattern::Array(_) | Pattern::Struct(_), _) => {
+            Ok(false)
+        }
+        (Pattern::Rest(_), _) => Err(unsupported(
+            "rest patterns outside of lists",
+            pattern.location(),
+        )),
+        (Pattern::Into(_), _) => Err(unsupported("into patterns", pattern.location())),
+        (Pattern::Regular(_), _) => Err(unsupported(
+            "regular expression patterns",
+            pattern.location(),
+        )),
+        (Pattern::Template(_), _) => Err(unsupported("template patterns", pattern.location())),
+        (Pattern::Within(_), _) => Err(unsupported("within patterns", pattern.location())),
+    }
+}
+
+// 元素模式当中最多有一个剩余模式 `...name`，它匹配中间剩余的元素，绑定为列表
+fn bind_elements(
+    patterns: &[Pattern],
+    values: &[Value],
+    environment: &Rc<RefCell<Environment>>,
+) -> Result<bool, Error> {
+    let rest_position = patterns
+        .iter()
+        .position(|pattern| matches!(pattern, Pattern::Rest(_)));
+
+    let (heads, tails) = match rest_position {
+        Some(position) => (&patterns[..position], &patterns[position + 1..]),
+        None => (patterns, &patterns[patterns.len()..]),
+    };
+
+    let is_length_matched = match rest_position {
+        Some(_) => values.len() >= heads.len() + tails.len(),
+        None => values.len() == patterns.len(),
+    };
+    if !is_length_matched {
+        return Ok(false);
+    }
+
+    for (pattern, value) in heads.iter().zip(values) {
+        if !bind_pattern(pattern, value, environment)? {
+            return Ok(false);
+        }
+    }
+
+    let tail_start = values.len() - tails.len();
+    for (pattern, value) in tails.iter().zip(&values[tail_start..]) {
+        if !bind_pattern(pattern, value, environment)? {
+            return Ok(false);
+        }
+    }
+
+    if let Some(Pattern::Rest(rest)) = rest_position.map(|position| &patterns[position]) {
+        if let Some(name) = &rest.name {
+            let middle = values[heads.len()..tail_start].to_vec();
+            environment
+                .borrow_mut()
+                .define(&name.name, Value::List(Rc::new(middle)));
+        }
+    }
+
+    Ok(true)
+}
+
+// 模式里的成员都必须存在，没有列出的成员被忽略，剩余模式 `...name` 把它们绑定为映射
+fn bind_fields(
+    pattern: &StructPattern,
+    fields: &[(String, Value)],
+    environment: &Rc<RefCell<Environment>>,
+) -> Result<bool, Error> {
+    for field in &pattern.fields {
+        let clauses = &field.clauses;
+        if clauses.only.is_some() || clauses.where_clause.is_some() {
+            return Err(unsupported(
+                "only and where clauses in field patterns",
+                &field.location,
+            ));
+        }
+
+        let value = match find_field(fields, &field.name.name) {
+            Some(value) => value,
+            None => return Ok(false),
+        };
+        if let Some(sub_pattern) = &field.pattern {
+            if !bind_pattern(sub_pattern, &value, environment)? {
+                return Ok(false);
+            }
+        }
+        if field.is_shorthand {
+            environment
+                .borrow_mut()
+                .define(&field.name.name, value.clone());
+        }
+        if let Some(to) = &clauses.to {
+            environment.borrow_mut().define(&to.name, value);
+        }
+    }
+
+    if let Some(RestPattern {
+        name: Some(name), ..
+    }) = &pattern.rest
+    {
+        let others = fields
+            .iter()
+            .filter(|(field_name, _)| pattern.fields.iter().all(|f| &f.name.name != field_name))
+            .cloned()
+            .collect();
+        environment
+            .borrow_mut()
+            .define(&name.name, Value::Map(Rc::new(others)));
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::error::Error;
+    use crate::error::Limit;
+    use crate::lexer;
+    use crate::parser;
+
+    use super::{eval_from_str, Interpreter, Limits, DEFAULT_MAX_CALL_DEPTH};
+
+    // 收集 puts 的输出
+    #[derive(Clone, Default)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // 返回程序的值以及输出
+    fn run(text: &str) -> (String, String) {
+        let buffer = Buffer::default();
+        let mut interpreter = Interpreter::with_output(Box::new(buffer.clone()));
+        let tokens = lexer::tokenize(text).unwrap();
+        let program = parser::parse(&tokens).unwrap();
+        let value = interpreter.eval_program(&program).unwrap();
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        (value.to_string(), output)
+    }
+
+    fn eval(text: &str) -> String {
+        eval_from_str(text).unwrap().to_string()
+    }
+
+    fn eval_error(text: &str) -> (String, usize) {
+        match eval_from_str(text) {
+            Err(Error::RuntimeError(message, location, _)) => (message, location.start),
+            other => panic!("expected runtime error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            run(include_str!("../examples/01-expression.toy")),
+            ("3".to_owned(), "".to_owned())
+        );
+        assert_eq!(
+            run(include_str!("../examples/02-func.toy")),
+            (
+                "()".to_owned(),
+                "[1, 2, 3, 4, 5]\n[Option::Some {value: 1}, Option::Some {value: 5}]\n[Option::None, Option::None]\n".to_owned()
+            )
+        );
+        assert_eq!(
+            run(include_str!("../examples/03-sum.toy")),
+            ("()".to_owned(), "15\n".to_owned())
+        );
+        assert_eq!(
+            run(include_str!("../examples/04-fib.toy")),
+            ("34".to_owned(), "".to_owned())
+        );
+        assert_eq!(
+            run(include_str!("../examples/05-builtin.toy")),
+            (
+                "47".to_owned(),
+                "[1, 2, 3, 4, 5]\n[4, 16]\n120 in total\n[\"age\", \"name\"]\n[20, \"foo\"]\n"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_eval_expression() {
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * -3"), "-9");
+        assert_eq!(eval("7 / 2"), "3");
+        assert_eq!(eval("1 < 2 && 'a' >= 'b'"), "false");
+        assert_eq!(eval("1 == 1 || 1 / 0 == 0"), "true");
+        assert_eq!(eval("\"foo\" ++ \"bar\""), "\"foobar\"");
+        assert_eq!(eval("[1, 2] ++ [3]"), "[1, 2, 3]");
+        assert_eq!(eval("(1, (2,), ())"), "(1, (2,), ())");
+        assert_eq!(eval("[1, 2, 3][1]"), "2");
+        assert_eq!(eval("[[1], [2]] == [[1], [2]]"), "true");
+        assert_eq!(eval("if 1 > 2 then 3"), "()");
+        assert_eq!(eval("if x > 2 where let x = 3 then x else 0"), "3");
+        assert_eq!(eval("let Max = 3\nif 1 < Max {1} else {2}"), "1");
+        assert_eq!(eval("let Cond = false\nif Cond {1}"), "()");
+    }
+
+    #[test]
+    fn test_eval_binding() {
+        assert_eq!(eval("let a = 1\nlet b = a + 1\nb"), "2");
+        assert_eq!(eval("let a = 1\n{let a = 2}\na"), "1");
+        assert_eq!(eval("let (a, b) = (1, 2)\na + b"), "3");
+        assert_eq!(
+            eval("let [a, ...b, c] = [1, 2, 3, 4]\n(a, b, c)"),
+            "(1, [2, 3], 4)"
+        );
+        assert_eq!(eval("let [a, b] match [1]"), "false");
+        assert_eq!(eval("let 1 match 1"), "true");
+    }
+
+    #[test]
+    fn test_eval_function() {
+        assert_eq!(eval("let add = (a, b) => a + b\nadd(1, 2)"), "3");
+        assert_eq!(eval("let inc = x => x + 1\ninc(inc(1))"), "3");
+
+        // 闭包
+        assert_eq!(
+            eval("let adder = fn(a) {fn(b) {a + b}}\nlet add2 = adder(2)\nadd2(3)"),
+            "5"
+        );
+        assert_eq!(eval("let a = 1\nlet f = () => a\nlet a = 2\nf()"), "2");
+
+        // 递归以及函数声明
+        assert_eq!(
+            eval("function fact(Int n) type Int = if n == 0 then 1 else n * fact(n - 1)\nfact(5)"),
+            "120"
+        );
+        assert_eq!(
+            eval("let even = n => if n == 0 then true else odd(n - 1)\nlet odd = n => if n == 0 then false else even(n - 1)\neven(10)"),
+            "true"
+        );
+
+        // 默认值
+        assert_eq!(
+            eval("let f = (a, b = a * 2) => a + b\n(f(1), f(1, 1))"),
+            "(3, 2)"
+        );
+
+        // 内置函数
+        assert_eq!(
+            eval("(len([1, 2]), len(\"abc\"), first([1, 2]) ?? 0, last([1, 2]) ?? 0, rest([1, 2]) ?? [])"),
+            "(2, 3, 1, 2, [2])"
+        );
+    }
+
+    #[test]
+    fn test_eval_data_type() {
+        assert_eq!(eval("#[1, 2] ++ #[3]"), "#[1, 2, 3]");
+        assert_eq!(eval("let a = [2, 3]\n[1, ...a, 4]"), "[1, 2, 3, 4]");
+        assert_eq!(eval("![1, 2\n3, 4]"), "![1, 2; 3, 4]");
+        assert_eq!(
+            eval("let m = {b: 2, a: 1}\n{...m, c: 3, a: 0}"),
+            "{a: 0, b: 2, c: 3}"
+        );
+        assert_eq!(eval("{a: 1}[\"a\"] + {b: 2}.b"), "3");
+        assert_eq!(eval("[1..10]"), "[1..10]");
+        assert_eq!(eval("[1, 3, ..10]"), "[1, 3, 5, 7, 9]");
+        assert_eq!(eval("[5, ..3]"), "[5]");
+        assert_eq!(eval("(1, 'a').1"), "'a'");
+        assert_eq!(eval("[1, 2] < [1, 3] && (2, \"a\") > (1, \"b\")"), "true");
+
+        // 自定义类型
+        assert_eq!(
+            eval("struct User {Int id, String name}\n(User {1, \"foo\"}, User {name: \"bar\", id: 2}.name)"),
+            "(User {id: 1, name: \"foo\"}, \"bar\")"
+        );
+        assert_eq!(
+            eval("enum Color {Red, Green}\n(Color::Green, Color::Red < Color::Green)"),
+            "(Color::Green, true)"
+        );
+        assert_eq!(
+            eval("union Shape {Circle {Int radius}, Empty}\n(Shape::Circle(1), Shape::Empty, Shape::Circle(2).radius)"),
+            "(Shape::Circle {radius: 1}, Shape::Empty, 2)"
+        );
+        assert_eq!(eval("struct P {Int x}\nP {1} == P {x: 1}"), "true");
+    }
+
+    #[test]
+    fn test_eval_branch_and_match() {
+        assert_eq!(
+            eval("let sign = n => branch {\n case n > 0: 1\n case n < 0: -1\n default: 0\n}\n(sign(5), sign(-5), sign(0))"),
+            "(1, -1, 0)"
+        );
+        assert_eq!(eval("branch {\n case false: 1\n}"), "()");
+        assert_eq!(
+            eval("branch where let a = 2 {\n case b > a where let b = 3: b\n}"),
+            "3"
+        );
+        assert_eq!(
+            eval("let f = v => match v {\n case 0, 1: \"small\"\n case [x, ...] to l only len(l) > 1: x\n case (a, b) where let c = a + b: c\n default: \"other\"\n}\n(f(1), f([7, 8]), f([9]), f((1, 2)))"),
+            "(\"small\", 7, \"other\", 3)"
+        );
+        assert_eq!(
+            eval("struct P {Int x, Int y}\nlet P {x, y: [a, ...]} = P {1, [2, 3]}\n(x, a)"),
+            "(1, 2)"
+        );
+        assert_eq!(
+            eval("let {a, ...others} = {a: 1, b: 2, c: 3}\n(a, others)"),
+            "(1, {b: 2, c: 3})"
+        );
+        assert_eq!(eval("struct P {Int x}\nlet P {z} match P {1}"), "false");
+        assert_eq!(
+            eval_error("match 1 {\n case 2: 0\n}").0,
+            "no case matches value 1"
+        );
+    }
+
+    #[test]
+    fn test_eval_option_and_result() {
+        assert_eq!(eval("Option::Some(1)"), "Option::Some {value: 1}");
+        assert_eq!(
+            eval("(Option::None, Result::Ok(1) == Result::Ok(1))"),
+            "(Option::None, true)"
+        );
+
+        // `??` 只在失败时求值默认值，1 / 0 被求值时会产生运行时错误
+        assert_eq!(eval("Option::Some(1) ?? (1 / 0)"), "1");
+        assert_eq!(eval("Option::None ?? 2"), "2");
+        assert_eq!(eval("parse_int(\"x\") ?? -1"), "-1");
+
+        // 解构联合体，成员名称可以带上联合体的名称
+        let text = "let f = o => match o {\n case Option::Some {value}: value\n case None {}: 0\n}";
+        assert_eq!(
+            eval(&format!("{}\n(f(Option::Some(5)), f(Option::None))", text)),
+            "(5, 0)"
+        );
+        assert_eq!(
+            eval("match parse_int(\"x\") {\n case Ok {value}: value\n case Result::Error {error}: error\n}"),
+            "\"invalid integer \\\"x\\\"\""
+        );
+        assert_eq!(eval("let Option::Some {value} match Option::None"), "false");
+
+        // `?` 从当前函数返回失败的值
+        let text = "let add = (a, b) => parse_int(a)? + parse_int(b)?";
+        assert_eq!(eval(&format!("{}\nadd(\"1\", \"2\")", text)), "3");
+        assert_eq!(
+            eval(&format!("{}\nadd(\"1\", \"x\")", text)),
+            "Result::Error {error: \"invalid integer \\\"x\\\"\"}"
+        );
+        assert_eq!(
+            eval("let f = list => {\n let head = first(list)?\n Option::Some(head * 10)\n}\nmap([[1], [], [3]], f)"),
+            "[Option::Some {value: 10}, Option::None, Option::Some {value: 30}]"
+        );
+
+        assert_eq!(
+            eval("let f = (a, b = parse_int(\"x\")?) => a\nlet g = () => {f(1)\n 5}\ng()"),
+            "5"
+        );
+
+        assert_eq!(eval_error("1 ?? 2"), ("cannot unwrap Int".to_owned(), 0));
+        assert_eq!(eval_error("(1, 2)?"), ("cannot unwrap Tuple".to_owned(), 0));
+        assert_eq!(
+            eval_error("1 + Option::None?"),
+            (
+                "cannot propagate Option::None outside of a function".to_owned(),
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn test_tail_call() {
+        // 按索引遍历的折叠函数，每个元素递归一次
+        let text = "\
+            let fold = fn(list, initial, func) {
+                let iter = fn(index, accumulator) {
+                    if index == len(list) then accumulator
+                    else iter(index + 1, func(accumulator, list[index]))
+                }
+                iter(0, initial)
+            }
+            fold(range(0, 100000), 0, (a, b) => a + b)";
+        assert_eq!(eval(text), "4999950000");
+
+        // README 里使用 first 和 rest 的折叠函数，递归次数超过默认的调用深度限制
+        let text = "\
+            let fold = fn(list, initial, func) {
+                let iter = fn(list, accumulator) {
+                    match first(list) {
+                        case Option::Some {value}: iter(rest(list) ?? [], func(accumulator, value))
+                        default: accumulator
+                    }
+                };
+                iter(list, initial);
+            };
+            fold(range(0, 5000), 0, fn(accumulator, element) {accumulator + element})";
+        assert_eq!(eval(text), "12497500");
+
+        // 尾部位置的 branch 与 match 分支，以及相互递归
+        assert_eq!(
+            eval("let count = (n, acc) => branch {\n case n == 0: acc\n default: count(n - 1, acc + 1)\n}\ncount(20000, 0)"),
+            "20000"
+        );
+        assert_eq!(
+            eval("let down = n => match n {\n case 0: \"done\"\n default: {let m = n - 1\n down(m)}\n}\ndown(20000)"),
+            "\"done\""
+        );
+        assert_eq!(
+            eval("let even = n => if n == 0 then true else odd(n - 1)\nlet odd = n => if n == 0 then false else even(n - 1)\neven(20001)"),
+            "false"
+        );
+    }
+
+    // 一百万个元素的折叠以及二十万次尾调用，未经优化的代码运行较慢，
+    // 使用 `cargo test -- --ignored` 运行
+    #[test]
+    #[ignore]
+    fn test_tail_call_large() {
+        let text = "\
+            let fold = fn(list, initial, func) {
+                let iter = fn(index, accumulator) {
+                    if index == len(list) then accumulator
+                    else iter(index + 1, func(accumulator, list[index]))
+                }
+                iter(0, initial)
+            }
+            fold(range(0, 1000000), 0, (a, b) => a + b)";
+        assert_eq!(eval(text), "499999500000");
+
+        assert_eq!(
+            eval("let count = (n, acc) => branch {\n case n == 0: acc\n default: count(n - 1, acc + 1)\n}\ncount(200000, 0)"),
+            "200000"
+        );
+        assert_eq!(
+            eval("let down = n => match n {\n case 0: \"done\"\n default: {let m = n - 1\n down(m)}\n}\ndown(200000)"),
+            "\"done\""
+        );
+        assert_eq!(
+            eval("let even = n => if n == 0 then true else odd(n - 1)\nlet odd = n => if n == 0 then false else even(n - 1)\neven(200001)"),
+            "false"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let eval_with_limits = |text: &str, limits: Limits| {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_limits(limits);
+            let tokens = lexer::tokenize(text).unwrap();
+            let program = parser::parse(&tokens).unwrap();
+            interpreter.eval_program(&program)
+        };
+        let unlimited = Limits {
+            max_call_depth: None,
+            max_steps: None,
+            max_value_size: None,
+        };
+
+        // 调用深度，尾调用不增加深度
+        let limits = Limits {
+            max_call_depth: Some(50),
+            ..unlimited
+        };
+        let text = "let f = n => if n == 0 then 0 else 1 + f(n - 1)";
+        assert!(eval_with_limits(&format!("{}\nf(49)", text), limits).is_ok());
+        match eval_with_limits(&format!("{}\nf(50)", text), limits) {
+            Err(Error::LimitError(Limit::CallDepth, _, frames)) => assert_eq!(frames.len(), 50),
+            other => panic!("expected call depth limit error, found {:?}", other),
+        }
+        assert!(eval_with_limits(
+            "let g = n => if n == 0 then 0 else g(n - 1)\ng(1000)",
+            limits
+        )
+        .is_ok());
+
+        // 默认的调用深度限制在默认大小的线程里先于调用栈耗尽生效
+        for (depth, expected) in [
+            (DEFAULT_MAX_CALL_DEPTH - 1, true),
+            (DEFAULT_MAX_CALL_DEPTH, false),
+        ] {
+            let shapes = [
+                "let f = n => if n == 0 then 0 else 1 + f(n - 1)",
+                "let f = n => if n == 0 then 0 else len([f(n - 1)])",
+                "let f = n => match n {\n case 0: 0\n default: (f(n - 1), 1).0\n}",
+            ];
+            for shape in shapes {
+                let text = format!("{}\nf({})", shape, depth);
+                let result =
+                    std::thread::spawn(move || match eval_with_limits(&text, Limits::default()) {
+                        Ok(_) => true,
+                        Err(Error::LimitError(Limit::CallDepth, _, _)) => false,
+                        Err(error) => panic!("unexpected error {:?}", error),
+                    })
+                    .join()
+                    .unwrap();
+                assert_eq!(result, expected, "{} at depth {}", shape, depth);
+            }
+        }
+
+        // 求值步数，每次执行程序重新计数
+        let limits = Limits {
+            max_steps: Some(1000),
+            ..unlimited
+        };
+        assert!(matches!(
+            eval_with_limits("let f = () => f()\nf()", limits),
+            Err(Error::LimitError(Limit::Steps, _, _))
+        ));
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(limits);
+        let program = parser::parse(&lexer::tokenize("[1..10]").unwrap()).unwrap();
+        for _ in 0..500 {
+            assert!(interpreter.eval_program(&program).is_ok());
+        }
+
+        // 值的大小
+        let limits = Limits {
+            max_value_size: Some(100),
+            ..unlimited
+        };
+        assert!(eval_with_limits("range(0, 100)", limits).is_ok());
+        for text in [
+            "range(0, 101)",
+            "[1, ..1000000000000]",
+            "let a = range(0, 60)\na ++ a",
+            "let a = range(0, 60)\n[...a, ...a]",
+            "push(range(0, 100), 1)",
+            "map([1..1000000000000], x => x)",
+        ] {
+            assert!(
+                matches!(
+                    eval_with_limits(text, limits),
+                    Err(Error::LimitError(Limit::ValueSize, _, _))
+                ),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_stack_trace() {
+        let text = "let div = (a, b) => a / b\nlet apply = f => f(1, 0) + 1\napply(div)";
+        match eval_from_str(text) {
+            Err(Error::RuntimeError(message, location, frames)) => {
+                assert_eq!(message, "division by zero");
+                assert_eq!(location.start, 20);
+                let frames: Vec<(Option<String>, usize)> = frames
+                    .into_iter()
+                    .map(|frame| (frame.name, frame.location.start))
+                    .collect();
+                assert_eq!(
+                    frames,
+                    vec![(Some("div".to_owned()), 43), (Some("apply".to_owned()), 55)]
+                );
+            }
+            other => panic!("expected runtime error, found {:?}", other),
+        }
+
+        // 尾调用取代了调用者的栈帧
+        match eval_from_str("let f = () => 1 / 0\nlet g = () => f()\ng()") {
+            Err(Error::RuntimeError(_, _, frames)) => {
+                assert_eq!(frames.len(), 1);
+                assert_eq!(frames[0].name, Some("f".to_owned()));
+            }
+            other => panic!("expected runtime error, found {:?}", other),
+        }
+
+        // 内置函数回调的函数
+        match eval_from_str("map([1], x => x / 0)") {
+            Err(Error::RuntimeError(_, _, frames)) => {
+                assert_eq!(frames.len(), 1);
+                assert_eq!(frames[0].name, None);
+            }
+            other => panic!("expected runtime error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported() {
+        // 能够解析但是还不能求值的语法，错误信息里包含语法的名称
+        for (text, construct) in [
+            ("for let i = 0 {i}", "for-let loops"),
+            ("for let i in [1] {i}", "for-in loops"),
+            ("each let i in [1] {i}", "each loops"),
+            ("[1, 2][0:1]", "slices"),
+            ("1^", "casts (^)"),
+            ("let f = a => a\nf(1, b = 2)", "keyword arguments"),
+            ("const Int Code {\n Ok = 1\n}", "grouped const declarations"),
+            ("impl User {}", "impl statements"),
+            ("namespace a {}", "namespace statements"),
+            ("use a::b", "use statements"),
+            ("`/user/{id}`", "template strings"),
+            ("#abc", "hash strings"),
+        ] {
+            assert_eq!(
+                eval_error(text).0,
+                format!("{} are not supported yet", construct),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_runtime_error() {
+        assert_eq!(
+            eval_error("1 + foo"),
+            ("undefined identifier \"foo\"".to_owned(), 4)
+        );
+        assert_eq!(
+            eval_error("let a = 1\na / (a - 1)"),
+            ("division by zero".to_owned(), 10)
+        );
+        assert_eq!(
+            eval_error("1 + true"),
+            (
+                "unsupported operand types for +: Int and Boolean".to_owned(),
+                0
+            )
+        );
+        assert_eq!(
+            eval_error("if 1 then 2"),
+            ("expected Boolean, found Int".to_owned(), 3)
+        );
+        assert_eq!(
+            eval_error("first(1)"),
+            (
+                "first: expected List or Array for argument 1, found Int".to_owned(),
+                0
+            )
+        );
+        assert_eq!(
+            eval_error("len(1, 2)"),
+            ("expected 1 argument, found 2".to_owned(), 0)
+        );
+        assert_eq!(
+            eval_error("let f = a => a\nf(1, 2)"),
+            ("expected 1 arguments, found 2".to_owned(), 15)
+        );
+        assert_eq!(
+            eval_error("let f = a => a\nf()"),
+            ("missing argument for parameter a".to_owned(), 15)
+        );
+        assert_eq!(eval_error("1(2)"), ("Int is not callable".to_owned(), 0));
+        assert_eq!(eval_error("[1][1]"), ("index 1 out of range".to_owned(), 4));
+        assert_eq!(
+            eval_error("let (a, b) = 1"),
+            ("pattern does not match value 1".to_owned(), 4)
+        );
+        assert_eq!(
+            eval_error("struct P {Int x}\nP {y: 1}"),
+            ("unknown field \"y\"".to_owned(), 20)
+        );
+        assert_eq!(
+            eval_error("{1, 2}"),
+            ("cannot infer the struct type".to_owned(), 0)
+        );
+        assert_eq!(
+            eval_error("[1, 1, ..3]"),
+            ("sequence step cannot be zero".to_owned(), 0)
+        );
+        assert_eq!(
+            eval_error("let m = 0 - 9223372036854775807\n[m, 9223372036854775807, ..0]"),
+            ("integer overflow".to_owned(), 32)
+        );
+        assert_eq!(
+            eval_error("(1, 2) < (1, true)"),
+            (
+                "unsupported operand types for <: Tuple and Tuple".to_owned(),
+                0
+            )
+        );
+    }
+}
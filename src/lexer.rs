@@ -13,184 +13,266 @@ use crate::token::Token;
 use crate::token::TokenType;
 
 pub fn tokenize(program: &str) -> Result<Vec<Token>, Error> {
+    tokenize_file(program, 0)
+}
+
+// 记号位置的 file_id 为源文件在 SourceMap 里的编号
+pub fn tokenize_file(program: &str, file_id: usize) -> Result<Vec<Token>, Error> {
     let vec_char: Vec<char> = program.chars().collect();
 
     let mut chars = &vec_char[..];
     let mut tokens: Vec<Token> = vec![];
 
-    loop {
-        match chars.split_first() {
-            Some((first, rest)) => {
-                chars = match *first {
-                    ' ' | '\t' => {
-                        // skip whitespace
-                        rest
-                    }
-                    '/' => {
-                        if match_char('/', rest) {
-                            // skip comment
-                            skip_comment(chars) // "//..."
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Slash)); // "/"
-                            rest
-                        }
-                    }
-                    '\n' => {
-                        add_token(&mut tokens, new_token(TokenType::NewLine)); // "\n"
-                        rest
-                    }
-                    '\r' => {
-                        if match_char('\n', rest) {
-                            add_token(&mut tokens, new_token(TokenType::NewLine)); // "\r\n"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::NewLine)); // "\r"
-                            rest
-                        }
-                    }
-                    '{' => {
-                        add_token(&mut tokens, new_token(TokenType::LeftBrace)); // "{"
-                        rest
-                    }
-                    '}' => {
-                        add_token(&mut tokens, new_token(TokenType::RightBrace)); // "}"
-                        rest
-                    }
-                    '=' => {
-                        if match_char('=', rest) {
-                            add_token(&mut tokens, new_token(TokenType::Equal)); // "=="
-                            move_forword(rest, 1)
-                        } else if match_char('>', rest) {
-                            add_token(&mut tokens, new_token(TokenType::Arrow)); // "=>"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Assign)); // "="
-                            rest
-                        }
-                    }
-                    '>' => {
-                        if match_char('>', rest) {
-                            add_token(&mut tokens, new_token(TokenType::Forward)); // ">>"
-                            move_forword(rest, 1)
-                        } else if match_char('=', rest) {
-                            add_token(&mut tokens, new_token(TokenType::GreaterThanOrEqual)); // ">="
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::GreaterThan)); // ">"
-                            rest
-                        }
-                    }
-                    '|' => {
-                        if match_char('|', rest) {
-                            add_token(&mut tokens, new_token(TokenType::LogicOr)); // "||"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Pipe)); // "|"
-                            rest
-                        }
-                    }
-                    '&' => {
-                        if match_char('&', rest) {
-                            add_token(&mut tokens, new_token(TokenType::LogicAnd)); // "&&"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Combine)); // "&"
-                            rest
-                        }
-                    }
-                    '!' => {
-                        if match_char('=', rest) {
-                            add_token(&mut tokens, new_token(TokenType::NotEqual)); // "!="
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Exclamation)); // "!"
-                            rest
-                        }
-                    }
-                    '<' => {
-                        if match_char('=', rest) {
-                            add_token(&mut tokens, new_token(TokenType::LessThanOrEqual)); // "<="
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::LessThan)); // "<"
-                            rest
-                        }
-                    }
-                    '+' => {
-                        if match_char('+', rest) {
-                            add_token(&mut tokens, new_token(TokenType::Concat)); // "++"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Plus)); // "+"
-                            rest
-                        }
-                    }
-                    '-' => {
-                        add_token(&mut tokens, new_token(TokenType::Minus)); // "-"
-                        rest
+    while let Some((first, rest)) = chars.split_first() {
+        let start = vec_char.len() - chars.len();
+        let count = tokens.len();
+
+        // 词法错误的位置为出错记号的第一个字符
+        let error = |message| {
+            Error::LexerError(
+                message,
+                Location {
+                    file_id,
+                    start,
+                    end: start + 1,
+                },
+            )
+        };
+
+        chars = match *first {
+            ' ' | '\t' => {
+                // skip whitespace
+                rest
+            }
+            '/' => {
+                if match_char('/', rest) {
+                    // skip comment
+                    skip_comment(chars) // "//..."
+                } else if match_char('*', rest) {
+                    // skip block comment
+                    skip_block_comment(move_forword(rest, 1)).map_err(error)? // "/*...*/"
+                } else if is_regexp_allowed(&tokens) {
+                    let (value, post_rest) = lex_regexp(rest).map_err(error)?; // "/.../"
+                    add_token(&mut tokens, new_token(TokenType::Regexp(value)));
+                    post_rest
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Slash)); // "/"
+                    rest
+                }
+            }
+            ';' => {
+                add_token(&mut tokens, new_token(TokenType::NewLine)); // ";"
+                rest
+            }
+            '\n' => {
+                add_token(&mut tokens, new_token(TokenType::NewLine)); // "\n"
+                rest
+            }
+            '\r' => {
+                if match_char('\n', rest) {
+                    add_token(&mut tokens, new_token(TokenType::NewLine)); // "\r\n"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::NewLine)); // "\r"
+                    rest
+                }
+            }
+            '{' => {
+                add_token(&mut tokens, new_token(TokenType::LeftBrace)); // "{"
+                rest
+            }
+            '}' => {
+                add_token(&mut tokens, new_token(TokenType::RightBrace)); // "}"
+                rest
+            }
+            '=' => {
+                if match_char('=', rest) {
+                    add_token(&mut tokens, new_token(TokenType::Equal)); // "=="
+                    move_forword(rest, 1)
+                } else if match_char('>', rest) {
+                    add_token(&mut tokens, new_token(TokenType::Arrow)); // "=>"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Assign)); // "="
+                    rest
+                }
+            }
+            '>' => {
+                if match_char('>', rest) {
+                    add_token(&mut tokens, new_token(TokenType::Forward)); // ">>"
+                    move_forword(rest, 1)
+                } else if match_char('=', rest) {
+                    add_token(&mut tokens, new_token(TokenType::GreaterThanOrEqual)); // ">="
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::GreaterThan)); // ">"
+                    rest
+                }
+            }
+            '|' => {
+                if match_char('|', rest) {
+                    add_token(&mut tokens, new_token(TokenType::LogicOr)); // "||"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Pipe)); // "|"
+                    rest
+                }
+            }
+            '&' => {
+                if match_char('&', rest) {
+                    add_token(&mut tokens, new_token(TokenType::LogicAnd)); // "&&"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Combine)); // "&"
+                    rest
+                }
+            }
+            '!' => {
+                if match_char('=', rest) {
+                    add_token(&mut tokens, new_token(TokenType::NotEqual)); // "!="
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Exclamation)); // "!"
+                    rest
+                }
+            }
+            '<' => {
+                if match_char('=', rest) {
+                    add_token(&mut tokens, new_token(TokenType::LessThanOrEqual)); // "<="
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::LessThan)); // "<"
+                    rest
+                }
+            }
+            '+' => {
+                if match_char('+', rest) {
+                    add_token(&mut tokens, new_token(TokenType::Concat)); // "++"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Plus)); // "+"
+                    rest
+                }
+            }
+            '-' => {
+                add_token(&mut tokens, new_token(TokenType::Minus)); // "-"
+                rest
+            }
+            '*' => {
+                add_token(&mut tokens, new_token(TokenType::Asterisk)); // "*"
+                rest
+            }
+            '?' => {
+                if match_char('?', rest) {
+                    add_token(&mut tokens, new_token(TokenType::UnwrapOr)); // "??"
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Unwrap)); // "?"
+                    rest
+                }
+            }
+            '^' => {
+                add_token(&mut tokens, new_token(TokenType::Cast)); // "^"
+                rest
+            }
+            '.' => {
+                if match_chars(['.', '.'], rest) {
+                    add_token(&mut tokens, new_token(TokenType::Ellipsis)); // "..."
+                    move_forword(rest, 2)
+                } else if match_char('.', rest) {
+                    add_token(&mut tokens, new_token(TokenType::Range)); // ".."
+                    move_forword(rest, 1)
+                } else {
+                    add_token(&mut tokens, new_token(TokenType::Dot)); // "."
+                    rest
+                }
+            }
+            '[' => {
+                add_token(&mut tokens, new_token(TokenType::LeftBracket)); // "["
+                rest
+            }
+            ']' => {
+                add_token(&mut tokens, new_token(TokenType::RightBracket)); // "]"
+                rest
+            }
+            '(' => {
+                add_token(&mut tokens, new_token(TokenType::LeftParen)); // "("
+                rest
+            }
+            ')' => {
+                add_token(&mut tokens, new_token(TokenType::RightParen)); // ")"
+                rest
+            }
+            '#' => {
+                match rest.first() {
+                    Some(c) if is_identifier_start(*c) => {
+                        let (value, post_rest) = lex_identifier(rest); // "#abc"
+                        add_token(&mut tokens, new_token(TokenType::HashString(value)));
+                        post_rest
                     }
-                    '*' => {
-                        add_token(&mut tokens, new_token(TokenType::Asterisk)); // "*"
+                    _ => {
+                        add_token(&mut tokens, new_token(TokenType::Hash)); // "#"
                         rest
                     }
-                    '?' => {
-                        if match_char('?', rest) {
-                            add_token(&mut tokens, new_token(TokenType::UnwrapOr)); // "??"
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Unwrap)); // "?"
-                            rest
+                }
+            }
+            ':' => {
+                if match_char(':', rest) {
+                    add_token(&mut tokens, new_token(TokenType::PathSeparator)); // "::"
+                    move_forword(rest, 1)
+                } else {
+                    match lex_named_operator(rest) {
+                        Some((name, post_rest)) => {
+                            add_token(&mut tokens, new_token(TokenType::NamedOperator(name))); // ":name:"
+                            post_rest
                         }
-                    }
-                    '^' => {
-                        add_token(&mut tokens, new_token(TokenType::Cast)); // "^"
-                        rest
-                    }
-                    '.' => {
-                        if match_chars(['.', '.'], rest) {
-                            add_token(&mut tokens, new_token(TokenType::Ellipsis)); // "..."
-                            move_forword(rest, 2)
-                        } else if match_char('.', rest) {
-                            add_token(&mut tokens, new_token(TokenType::Range)); // ".."
-                            move_forword(rest, 1)
-                        } else {
-                            add_token(&mut tokens, new_token(TokenType::Dot)); // "."
+                        None => {
+                            add_token(&mut tokens, new_token(TokenType::Colon)); // ":"
                             rest
                         }
                     }
-                    '[' => {
-                        add_token(&mut tokens, new_token(TokenType::LeftBracket)); // "["
-                        rest
-                    }
-                    ']' => {
-                        add_token(&mut tokens, new_token(TokenType::RightBracket)); // "]"
-                        rest
-                    }
-                    '(' => {
-                        add_token(&mut tokens, new_token(TokenType::LeftParen)); // "("
-                        rest
-                    }
-                    ')' => {
-                        add_token(&mut tokens, new_token(TokenType::RightParen)); // ")"
-                        rest
-                    }
-                    '#' => {
-                        add_token(&mut tokens, new_token(TokenType::Hash)); // "#"
-                        rest
-                    }
-                    ':' => {
-                        add_token(&mut tokens, new_token(TokenType::Colon)); // ":"
-                        rest
-                    }
-                    ',' => {
-                        add_token(&mut tokens, new_token(TokenType::Comma)); // ","
-                        rest
-                    }
-                    _ => return Err(Error::LexerError("unexpected char")),
-                };
+                }
+            }
+            ',' => {
+                add_token(&mut tokens, new_token(TokenType::Comma)); // ","
+                rest
+            }
+            '\'' => {
+                if match_chars(['\'', '\''], rest) {
+                    // skip document comment "'''...'''"
+                    skip_document_comment(move_forword(rest, 2)).map_err(error)?
+                } else {
+                    let (value, post_rest) = lex_char(rest).map_err(error)?; // "'a'"
+                    add_token(&mut tokens, new_token(TokenType::Char(value)));
+                    post_rest
+                }
+            }
+            '"' => {
+                let (value, post_rest) = lex_string(rest).map_err(error)?; // "\"abc\""
+                add_token(&mut tokens, new_token(TokenType::String(value)));
+                post_rest
+            }
+            '`' => {
+                let (value, post_rest) = lex_template_string(rest).map_err(error)?; // "`abc`"
+                add_token(&mut tokens, new_token(TokenType::TemplateString(value)));
+                post_rest
             }
-            None => break,
+            '0'..='9' => {
+                let (value, post_rest) = lex_integer(chars).map_err(error)?; // "123"
+                add_token(&mut tokens, new_token(TokenType::Integer(value)));
+                post_rest
+            }
+            c if is_identifier_start(c) => {
+                let (name, post_rest) = lex_identifier(chars); // "abc"
+                add_token(&mut tokens, new_token(lookup_keyword(&name)));
+                post_rest
+            }
+            _ => return Err(error("unexpected char")),
         };
+
+        // 更新新添加的 token 的位置信息
+        let end = vec_char.len() - chars.len();
+        update_location(&mut tokens[count..], file_id, start, end);
     }
 
     Ok(tokens)
@@ -221,20 +303,51 @@ fn match_char(expectd: char, chars: &[char]) -> bool {
 
 fn match_chars(expected: [char; 2], chars: &[char]) -> bool {
     match chars.split_first() {
-        Some((first, rest)) => {
-            if *first == expected[0] {
-                match rest.first() {
-                    Some(second) => *second == expected[1],
-                    None => false,
-                }
-            } else {
-                false
-            }
-        }
+        Some((first, rest)) if *first == expected[0] => match rest.first() {
+            Some(second) => *second == expected[1],
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn update_location(tokens: &mut [Token], file_id: usize, start: usize, end: usize) {
+    for token in tokens {
+        token.location.file_id = file_id;
+        token.location.start = start;
+        token.location.end = end;
+    }
+}
+
+// 检查最后一个 token 之后是否允许出现正则表达式，用于区分除号和正则表达式
+fn is_regexp_allowed(tokens: &[Token]) -> bool {
+    match tokens.last() {
+        Some(token) => matches!(
+            token.token_type,
+            TokenType::Regular
+                | TokenType::Assign
+                | TokenType::Arrow
+                | TokenType::LeftParen
+                | TokenType::LeftBracket
+                | TokenType::LeftBrace
+                | TokenType::Comma
+                | TokenType::Colon
+                | TokenType::Then
+                | TokenType::Else
+                | TokenType::In
+        ),
         None => false,
     }
 }
 
+fn is_identifier_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
 fn skip_comment(chars: &[char]) -> &[char] {
     // 寻找 '\n'
     match chars.iter().position(|c| *c == '\n') {
@@ -243,6 +356,149 @@ fn skip_comment(chars: &[char]) -> &[char] {
     }
 }
 
+fn skip_block_comment(chars: &[char]) -> Result<&[char], &'static str> {
+    // 寻找 "*/"
+    match chars.windows(2).position(|w| w == ['*', '/']) {
+        Some(index) => Ok(&chars[index + 2..]),
+        None => Err("unterminated block comment"),
+    }
+}
+
+fn skip_document_comment(chars: &[char]) -> Result<&[char], &'static str> {
+    // 寻找 "'''"
+    match chars.windows(3).position(|w| w == ['\'', '\'', '\'']) {
+        Some(index) => Ok(&chars[index + 3..]),
+        None => Err("unterminated document comment"),
+    }
+}
+
+fn lex_identifier(chars: &[char]) -> (String, &[char]) {
+    let length = chars
+        .iter()
+        .position(|c| !is_identifier_char(*c))
+        .unwrap_or(chars.len());
+    (chars[..length].iter().collect(), &chars[length..])
+}
+
+fn lex_integer(chars: &[char]) -> Result<(i64, &[char]), &'static str> {
+    let length = chars
+        .iter()
+        .position(|c| !(c.is_ascii_digit() || *c == '_'))
+        .unwrap_or(chars.len());
+
+    let digits: String = chars[..length].iter().filter(|c| **c != '_').collect();
+    match digits.parse::<i64>() {
+        Ok(value) => Ok((value, &chars[length..])),
+        Err(_) => Err("integer overflow"),
+    }
+}
+
+// 命名操作符 ":name:"，参数 chars 不包括开头的冒号
+fn lex_named_operator(chars: &[char]) -> Option<(String, &[char])> {
+    match chars.first() {
+        Some(c) if is_identifier_start(*c) => {
+            let (name, rest) = lex_identifier(chars);
+            if match_char(':', rest) {
+                Some((name, move_forword(rest, 1)))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn lex_escape(chars: &[char]) -> Result<(char, &[char]), &'static str> {
+    match chars.split_first() {
+        Some((first, rest)) => {
+            let c = match *first {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '0' => '\0',
+                '\\' => '\\',
+                '\'' => '\'',
+                '"' => '"',
+                _ => return Err("unknown escape char"),
+            };
+            Ok((c, rest))
+        }
+        None => Err("unterminated escape char"),
+    }
+}
+
+// 参数 chars 不包括开头的单引号
+fn lex_char(chars: &[char]) -> Result<(char, &[char]), &'static str> {
+    let (value, rest) = match chars.split_first() {
+        Some(('\\', rest)) => lex_escape(rest)?,
+        Some(('\'', _)) => return Err("empty char"),
+        Some((first, rest)) => (*first, rest),
+        None => return Err("unterminated char"),
+    };
+
+    if match_char('\'', rest) {
+        Ok((value, move_forword(rest, 1)))
+    } else {
+        Err("unterminated char")
+    }
+}
+
+// 参数 chars 不包括开头的双引号
+fn lex_string(chars: &[char]) -> Result<(String, &[char]), &'static str> {
+    let mut value = String::new();
+    let mut chars = chars;
+
+    loop {
+        chars = match chars.split_first() {
+            Some(('"', rest)) => return Ok((value, rest)),
+            Some(('\\', rest)) => {
+                let (c, post_rest) = lex_escape(rest)?;
+                value.push(c);
+                post_rest
+            }
+            Some((first, rest)) => {
+                value.push(*first);
+                rest
+            }
+            None => return Err("unterminated string"),
+        };
+    }
+}
+
+// 参数 chars 不包括开头的反引号，模板字符串的内容保持原样
+fn lex_template_string(chars: &[char]) -> Result<(String, &[char]), &'static str> {
+    match chars.iter().position(|c| *c == '`') {
+        Some(index) => Ok((chars[..index].iter().collect(), &chars[index + 1..])),
+        None => Err("unterminated template string"),
+    }
+}
+
+// 参数 chars 不包括开头的斜杠，正则表达式的内容除了 "\/" 之外保持原样
+fn lex_regexp(chars: &[char]) -> Result<(String, &[char]), &'static str> {
+    let mut value = String::new();
+    let mut chars = chars;
+
+    loop {
+        chars = match chars.split_first() {
+            Some(('/', rest)) => return Ok((value, rest)),
+            Some(('\\', rest)) if match_char('/', rest) => {
+                value.push('/');
+                move_forword(rest, 1)
+            }
+            Some(('\\', rest)) if !rest.is_empty() => {
+                value.push('\\');
+                value.push(rest[0]);
+                move_forword(rest, 1)
+            }
+            Some(('\n', _)) | None => return Err("unterminated regexp"),
+            Some((first, rest)) => {
+                value.push(*first);
+                rest
+            }
+        };
+    }
+}
+
 fn move_forword(chars: &[char], count: usize) -> &[char] {
     &chars[count..]
 }
@@ -280,6 +536,9 @@ fn lookup_keyword(name: &str) -> TokenType {
         "impl" => TokenType::Impl,
         "alias" => TokenType::Alias,
 
+        "true" => TokenType::Boolean(true),
+        "false" => TokenType::Boolean(false),
+
         // 返回标识符
         _ => TokenType::Identifier(name.to_string()),
     }
@@ -287,9 +546,10 @@ fn lookup_keyword(name: &str) -> TokenType {
 
 #[cfg(test)]
 mod tests {
-    use crate::token::Token;
+    use crate::error::Error;
+    use crate::token::{Location, Token, TokenType};
 
-    use super::tokenize;
+    use super::{tokenize, tokenize_file};
 
     #[test]
     fn test_whitespace() {
@@ -327,6 +587,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_identifier_and_keyword() {
+        let tokens1 = tokenize("foo _bar let match case true false").unwrap();
+        assert_eq!(
+            tokens1
+                .iter()
+                .map(|t| t.token_type.clone())
+                .collect::<Vec<TokenType>>(),
+            vec![
+                TokenType::Identifier("foo".to_string()),
+                TokenType::Identifier("_bar".to_string()),
+                TokenType::Let,
+                TokenType::Match,
+                TokenType::Identifier("case".to_string()),
+                TokenType::Boolean(true),
+                TokenType::Boolean(false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_literal() {
+        let tokens1 = tokenize("123 'a' '\\n' \"foo\\\"bar\" `/user/{id}` #abc").unwrap();
+        assert_eq!(
+            tokens1
+                .iter()
+                .map(|t| t.token_type.clone())
+                .collect::<Vec<TokenType>>(),
+            vec![
+                TokenType::Integer(123),
+                TokenType::Char('a'),
+                TokenType::Char('\n'),
+                TokenType::String("foo\"bar".to_string()),
+                TokenType::TemplateString("/user/{id}".to_string()),
+                TokenType::HashString("abc".to_string()),
+            ]
+        );
+
+        assert!(tokenize("\"foo").is_err());
+        assert!(tokenize("99999999999999999999").is_err());
+
+        // 错误位置为出错记号的第一个字符
+        assert_eq!(
+            tokenize_file("a = 'b", 2),
+            Err(Error::LexerError(
+                "unterminated char",
+                Location {
+                    file_id: 2,
+                    start: 4,
+                    end: 5
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_regexp_and_slash() {
+        let tokens1 = tokenize("a / b").unwrap();
+        assert_eq!(tokens_to_string(&tokens1), vec!["a", "/", "b"]);
+
+        let tokens2 = tokenize("regular /^(.+)@(.+)$/ [a]").unwrap();
+        assert_eq!(
+            tokens2[1].token_type,
+            TokenType::Regexp("^(.+)@(.+)$".to_string())
+        );
+
+        let tokens3 = tokenize("(/^(\\w+):\\/\\/(.+)$/)").unwrap();
+        assert_eq!(
+            tokens3[1].token_type,
+            TokenType::Regexp("^(\\w+):\\/\\/(.+)$".replace("\\/", "/"))
+        );
+    }
+
+    #[test]
+    fn test_colon_and_named_operator() {
+        let tokens1 = tokenize("a :add: b").unwrap();
+        assert_eq!(
+            tokens1[1].token_type,
+            TokenType::NamedOperator("add".to_string())
+        );
+
+        let tokens2 = tokenize("Color::Red: x").unwrap();
+        assert_eq!(
+            tokens_to_string(&tokens2),
+            vec!["Color", "::", "Red", ":", "x"]
+        );
+    }
+
+    #[test]
+    fn test_new_line_and_comments() {
+        let tokens1 = tokenize("a;b\nc /* d \n e */ f\n\'\'\'doc\'\'\' g").unwrap();
+        assert_eq!(
+            tokens_to_string(&tokens1),
+            vec!["a", "\n", "b", "\n", "c", "f", "\n", "g"]
+        );
+    }
+
+    #[test]
+    fn test_location() {
+        let tokens1 = tokenize("let abc = 12").unwrap();
+        let locations: Vec<(usize, usize)> = tokens1
+            .iter()
+            .map(|t| (t.location.start, t.location.end))
+            .collect();
+        assert_eq!(locations, vec![(0, 3), (4, 7), (8, 9), (10, 12)]);
+
+        let tokens2 = tokenize_file("a\nb", 3).unwrap();
+        assert!(tokens2.iter().all(|t| t.location.file_id == 3));
+    }
+
     fn tokens_to_string(tokens: &[Token]) -> Vec<String> {
         let strings: Vec<String> = tokens.iter().map(|t| t.token_type.to_string()).collect();
         strings
@@ -5,7 +5,18 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
-
 pub mod error;
 pub mod token;
-pub mod lexer;
\ No newline at end of file
+pub mod lexer;
+pub mod ast;
+pub mod parser;
+pub mod printer;
+pub mod dump;
+pub mod visitor;
+pub mod value;
+pub mod environment;
+pub mod interpreter;
+pub mod builtin;
+pub mod source_map;
+pub mod diagnostic;
+pub mod repl;
@@ -0,0 +1,4025 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use crate::ast::AliasDeclaration;
+use crate::ast::AnonymousFunction;
+use crate::ast::AssociatedType;
+use crate::ast::BinaryExpression;
+use crate::ast::BinaryOperator;
+use crate::ast::BlockExpression;
+use crate::ast::BranchCase;
+use crate::ast::BranchExpression;
+use crate::ast::CallExpression;
+use crate::ast::ConstDeclaration;
+use crate::ast::ConstMember;
+use crate::ast::ConstValue;
+use crate::ast::DataType;
+use crate::ast::EachExpression;
+use crate::ast::EnumDeclaration;
+use crate::ast::ErrorNode;
+use crate::ast::Expression;
+use crate::ast::FieldDeclaration;
+use crate::ast::FieldPattern;
+use crate::ast::FieldValue;
+use crate::ast::ForInExpression;
+use crate::ast::ForLetExpression;
+use crate::ast::FunctionDeclaration;
+use crate::ast::Identifier;
+use crate::ast::IfExpression;
+use crate::ast::ImplDeclaration;
+use crate::ast::InBinding;
+use crate::ast::IndexExpression;
+use crate::ast::IntoPattern;
+use crate::ast::KeywordArgument;
+use crate::ast::LetExpression;
+use crate::ast::ListExpression;
+use crate::ast::ListPattern;
+use crate::ast::Literal;
+use crate::ast::LiteralValue;
+use crate::ast::MapEntry;
+use crate::ast::MapExpression;
+use crate::ast::MapPair;
+use crate::ast::MatchCase;
+use crate::ast::MatchExpression;
+use crate::ast::MatrixExpression;
+use crate::ast::MemberExpression;
+use crate::ast::NamedDataType;
+use crate::ast::NamespaceDeclaration;
+use crate::ast::NextExpression;
+use crate::ast::Parameter;
+use crate::ast::Path;
+use crate::ast::Pattern;
+use crate::ast::PatternClauses;
+use crate::ast::PostfixExpression;
+use crate::ast::PostfixOperator;
+use crate::ast::PrefixExpression;
+use crate::ast::PrefixOperator;
+use crate::ast::Program;
+use crate::ast::RangeExpression;
+use crate::ast::RegularPattern;
+use crate::ast::RestPattern;
+use crate::ast::SequenceExpression;
+use crate::ast::SignDataType;
+use crate::ast::SignParameter;
+use crate::ast::SliceExpression;
+use crate::ast::SpreadExpression;
+use crate::ast::Statement;
+use crate::ast::StructDeclaration;
+use crate::ast::StructExpression;
+use crate::ast::StructPattern;
+use crate::ast::TemplatePattern;
+use crate::ast::TraitDeclaration;
+use crate::ast::TupleExpression;
+use crate::ast::TupleIndexExpression;
+use crate::ast::TuplePattern;
+use crate::ast::TypeConstraint;
+use crate::ast::UnionDeclaration;
+use crate::ast::UnionMember;
+use crate::ast::UseDeclaration;
+use crate::ast::UseKind;
+use crate::ast::UseTree;
+use crate::ast::WhichEntry;
+use crate::ast::WithinPattern;
+use crate::error::Error;
+use crate::token::Location;
+use crate::token::Token;
+use crate::token::TokenType;
+use crate::visitor::walk_anonymous_function;
+use crate::visitor::walk_function_declaration;
+use crate::visitor::walk_next_expression;
+use crate::visitor::Visitor;
+
+// 解析整个程序，遇到语法错误时返回第一个错误
+pub fn parse(tokens: &[Token]) -> Result<Program, Error> {
+    let (program, mut errors) = parse_with_recovery(tokens);
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        Err(errors.remove(0))
+    }
+}
+
+// 解析整个程序，出错的语句或者表达式被替换为错误节点，然后从同步点继续解析，
+// 返回的语法树总是完整的，同时返回按出现顺序排列的所有错误，
+// 适用于需要一次列出所有错误的场合，比如编辑器
+pub fn parse_with_recovery(tokens: &[Token]) -> (Program, Vec<Error>) {
+    let (statements, _) = parse_statements(tokens, None);
+
+    let program = Program { statements };
+    let mut checker = Checker {
+        is_in_for_let: false,
+        errors: vec![],
+    };
+    checker.visit_program(&program);
+
+    (program, checker.errors)
+}
+
+// 判断输入是否还没有结束，用于交互模式决定是否继续读取下一行：
+// - 存在未闭合的括号；
+// - 最后一个记号是 `=`、`=>`、then、else 或者中置运算符，这些记号之后允许换行。
+pub fn is_incomplete(tokens: &[Token]) -> bool {
+    let mut depth: usize = 0;
+    for token in tokens {
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftParen | TokenType::LeftBracket => depth += 1,
+            TokenType::RightBrace | TokenType::RightParen | TokenType::RightBracket => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+    }
+
+    let last = tokens
+        .iter()
+        .rev()
+        .find(|token| token.token_type != TokenType::NewLine);
+    depth > 0
+        || match last {
+            Some(token) => {
+                matches!(
+                    token.token_type,
+                    TokenType::Assign | TokenType::Arrow | TokenType::Then | TokenType::Else
+                ) || infix_operator(&token.token_type).is_some()
+            }
+            None => false,
+        }
+}
+
+// 解析以换行分隔的语句，直到遇到 closing 记号（不消耗）或者文件末尾，
+// 参数 closing 为 None 表示顶层语句
+fn parse_statements<'a>(
+    source: &'a [Token],
+    closing: Option<&TokenType>,
+) -> (Vec<Statement>, &'a [Token]) {
+    let mut statements: Vec<Statement> = vec![];
+    let mut source = skip_new_lines(source);
+
+    while let Some(first) = source.first() {
+        if Some(&first.token_type) == closing {
+            break;
+        }
+
+        let post_statement = match parse_statement(source) {
+            Ok((statement, rest)) => {
+                statements.push(statement);
+                consume_end_of_line(rest, closing)
+            }
+            Err(error) => Err(error),
+        };
+
+        source = match post_statement {
+            Ok(rest) => skip_new_lines(rest),
+            Err(error) => {
+                let (node, rest) = recover(error, source, closing.is_some());
+                statements.push(Statement::Error(node));
+                skip_new_lines(rest)
+            }
+        };
+    }
+
+    (statements, source)
+}
+
+fn parse_statement(source: &[Token]) -> Result<(Statement, &[Token]), Error> {
+    match source.first() {
+        Some(token) if token.token_type == TokenType::Function => {
+            let (function, rest) = parse_function(source)?;
+            Ok((Statement::Function(function), rest))
+        }
+        Some(token) if token.token_type == TokenType::Struct => {
+            let (declaration, rest) = parse_struct_declaration(source)?;
+            Ok((Statement::Struct(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Union => {
+            let (declaration, rest) = parse_union_declaration(source)?;
+            Ok((Statement::Union(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Enum => {
+            let (declaration, rest) = parse_enum_declaration(source)?;
+            Ok((Statement::Enum(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Trait => {
+            let (declaration, rest) = parse_trait_declaration(source)?;
+            Ok((Statement::Trait(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Impl => {
+            let (declaration, rest) = parse_impl_declaration(source)?;
+            Ok((Statement::Impl(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Namespace => {
+            let (declaration, rest) = parse_namespace_declaration(source)?;
+            Ok((Statement::Namespace(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Use => {
+            let (declaration, rest) = parse_use_declaration(source)?;
+            Ok((Statement::Use(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Alias => {
+            let (declaration, rest) = parse_alias_declaration(source)?;
+            Ok((Statement::Alias(declaration), rest))
+        }
+        Some(token) if token.token_type == TokenType::Const => {
+            let (declaration, rest) = parse_const_declaration(source)?;
+            Ok((Statement::Const(declaration), rest))
+        }
+        _ if match_identifier("pattern", source)
+            && match_token(&TokenType::Function, move_forword(source, 1)) =>
+        {
+            let (function, rest) = parse_function(source)?;
+            Ok((Statement::Function(function), rest))
+        }
+        _ => {
+            let (expression, rest) = parse_expression(source)?;
+            Ok((Statement::Expression(expression), rest))
+        }
+    }
+}
+
+// `namespace name { statement ... }`，语句之间使用换行分隔，命名空间可以嵌套
+fn parse_namespace_declaration(
+    source: &[Token],
+) -> Result<(NamespaceDeclaration, &[Token]), Error> {
+    let (start, post_namespace) = consume_token(&TokenType::Namespace, source)?;
+    let (name, post_name) = parse_identifier(post_namespace)?;
+    let (_, post_left_brace) = consume_token(&TokenType::LeftBrace, skip_new_lines(post_name))?;
+
+    let (statements, post_statements) =
+        parse_statements(post_left_brace, Some(&TokenType::RightBrace));
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, post_statements)?;
+    Ok((
+        NamespaceDeclaration {
+            name,
+            statements,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+// `use foo::bar`、`use foo::*`、`use foo::{bar, baz::*}`
+fn parse_use_declaration(source: &[Token]) -> Result<(UseDeclaration, &[Token]), Error> {
+    let (start, post_use) = consume_token(&TokenType::Use, source)?;
+    let (tree, post_rest) = parse_use_tree(post_use)?;
+    if tree.path.is_empty() {
+        return Err(error_at("expected identifier", post_use));
+    }
+
+    let location = join_location(&start, &tree.location);
+    Ok((UseDeclaration { tree, location }, post_rest))
+}
+
+fn parse_use_tree(source: &[Token]) -> Result<(UseTree, &[Token]), Error> {
+    let mut path: Vec<Identifier> = vec![];
+    let mut source = source;
+
+    loop {
+        match source.first() {
+            Some(Token {
+                token_type: TokenType::Identifier(_),
+                ..
+            }) => {
+                let (name, post_name) = parse_identifier(source)?;
+                path.push(name);
+                if !match_token(&TokenType::PathSeparator, post_name) {
+                    let location = join_location(&path[0].location, &path[path.len() - 1].location);
+                    return Ok((
+                        UseTree {
+                            path,
+                            kind: UseKind::Single,
+                            location,
+                        },
+                        post_name,
+                    ));
+                }
+                source = move_forword(post_name, 1);
+            }
+            Some(Token {
+                token_type: TokenType::Asterisk,
+                location: end,
+            }) => {
+                let start = path.first().map_or(end, |name| &name.location);
+                let location = join_location(start, end);
+                return Ok((
+                    UseTree {
+                        path,
+                        kind: UseKind::Glob,
+                        location,
+                    },
+                    move_forword(source, 1),
+                ));
+            }
+            Some(Token {
+                token_type: TokenType::LeftBrace,
+                location: left_brace,
+            }) => {
+                let (items, post_items) = parse_comma_separated(
+                    move_forword(source, 1),
+                    &TokenType::RightBrace,
+                    parse_use_tree,
+                )?;
+                let (end, post_rest) = consume_token(&TokenType::RightBrace, post_items)?;
+                if items.items.is_empty() {
+                    return Err(error_at("expected identifier", move_forword(source, 1)));
+                }
+
+                let start = path.first().map_or(left_brace, |name| &name.location);
+                let location = join_location(start, &end);
+                return Ok((
+                    UseTree {
+                        path,
+                        kind: UseKind::Group(items.items),
+                        location,
+                    },
+                    post_rest,
+                ));
+            }
+            _ => return Err(error_at("expected identifier", source)),
+        }
+    }
+}
+
+// `alias Name = DataType`
+fn parse_alias_declaration(source: &[Token]) -> Result<(AliasDeclaration, &[Token]), Error> {
+    let (start, post_alias) = consume_token(&TokenType::Alias, source)?;
+    let (name, post_name) = parse_identifier(post_alias)?;
+    let (_, post_assign) = consume_token(&TokenType::Assign, post_name)?;
+    let (data_type, post_rest) = parse_data_type(skip_new_lines(post_assign))?;
+
+    let location = join_location(&start, data_type.location());
+    Ok((
+        AliasDeclaration {
+            name,
+            data_type,
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// `[pattern] function name (...) type Type which {...} {...}`，
+// 或者 `function name (...) type Type = expression`，
+// 或者特性里没有函数体的 `empty function name (...) type Type`，
+// 函数的各部分之间允许换行
+fn parse_function(source: &[Token]) -> Result<(FunctionDeclaration, &[Token]), Error> {
+    let start = source[0].location.clone();
+    let (is_pattern, source) = if match_identifier("pattern", source) {
+        (true, move_forword(source, 1))
+    } else {
+        (false, source)
+    };
+    let (is_empty, source) = if match_identifier("empty", source) {
+        (true, move_forword(source, 1))
+    } else {
+        (false, source)
+    };
+
+    let (_, post_function) = consume_token(&TokenType::Function, source)?;
+    let (name, post_name) = parse_identifier(post_function)?;
+
+    let (generic_parameters, post_generic) = if match_token(&TokenType::LessThan, post_name) {
+        let (items, rest) = parse_comma_separated(
+            move_forword(post_name, 1),
+            &TokenType::GreaterThan,
+            parse_identifier,
+        )?;
+        let (_, post_greater_than) = consume_token(&TokenType::GreaterThan, rest)?;
+        if items.items.is_empty() {
+            return Err(error_at("expected generic parameter", rest));
+        }
+        (items.items, post_greater_than)
+    } else {
+        (vec![], post_name)
+    };
+
+    let (_, post_left_paren) = consume_token(&TokenType::LeftParen, post_generic)?;
+    let parse_item = if is_pattern {
+        parse_pattern_parameter
+    } else {
+        parse_parameter
+    };
+    let (parameters, post_parameters) =
+        parse_comma_separated(post_left_paren, &TokenType::RightParen, parse_item)?;
+    let (right_paren, post_right_paren) = consume_token(&TokenType::RightParen, post_parameters)?;
+
+    let (return_type, post_return_type) = parse_optional_return_type(post_right_paren)?;
+
+    let post_new_lines = skip_new_lines(post_return_type);
+    let (which, post_which) = if match_token(&TokenType::Which, post_new_lines) {
+        parse_which(move_forword(post_new_lines, 1))?
+    } else {
+        (vec![], post_return_type)
+    };
+
+    if is_empty {
+        let end = match (&which.last(), &return_type) {
+            (Some(entry), _) => entry.location.clone(),
+            (None, Some(data_type)) => data_type.location().clone(),
+            (None, None) => right_paren,
+        };
+        let location = join_location(&start, &end);
+        return Ok((
+            FunctionDeclaration {
+                name,
+                is_pattern,
+                generic_parameters,
+                parameters: parameters.items,
+                return_type,
+                which,
+                body: None,
+                location,
+            },
+            post_which,
+        ));
+    }
+
+    let post_new_lines = skip_new_lines(post_which);
+    let (body, post_rest) = if match_token(&TokenType::Assign, post_new_lines) {
+        parse_expression(skip_new_lines(move_forword(post_new_lines, 1)))?
+    } else {
+        let (block, post_block) = parse_block(post_new_lines)?;
+        (Expression::Block(block), post_block)
+    };
+
+    let location = join_location(&start, body.location());
+    Ok((
+        FunctionDeclaration {
+            name,
+            is_pattern,
+            generic_parameters,
+            parameters: parameters.items,
+            return_type,
+            which,
+            body: Some(body),
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// `trait Name type (ItemType, ItemType = Default) { [empty] function ... }`，
+// 各部分之间允许换行
+fn parse_trait_declaration(source: &[Token]) -> Result<(TraitDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Trait, source)?;
+    let (name, post_name) = parse_identifier(rest)?;
+    let (associated_types, post_types) = parse_optional_associated_types(post_name, false)?;
+    let (functions, end, post_rest) =
+        parse_members(skip_new_lines(post_types), parse_trait_function)?;
+    Ok((
+        TraitDeclaration {
+            name,
+            associated_types,
+            functions,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_trait_function(source: &[Token]) -> Result<(FunctionDeclaration, &[Token]), Error> {
+    let post_empty = if match_identifier("empty", source) {
+        move_forword(source, 1)
+    } else {
+        source
+    };
+
+    if match_token(&TokenType::Function, post_empty) {
+        parse_function(source)
+    } else {
+        Err(error_at("expected function", post_empty))
+    }
+}
+
+// `impl DataType trait Name type (ItemType = Type) { function ... }`，
+// 其中 trait 和 type 部分都可以省略，各部分之间允许换行
+fn parse_impl_declaration(source: &[Token]) -> Result<(ImplDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Impl, source)?;
+    let (data_type, post_data_type) = parse_data_type(rest)?;
+
+    let post_new_lines = skip_new_lines(post_data_type);
+    let (trait_name, post_trait) = if match_token(&TokenType::Trait, post_new_lines) {
+        let (trait_name, rest) = parse_data_type(move_forword(post_new_lines, 1))?;
+        (Some(trait_name), rest)
+    } else {
+        (None, post_data_type)
+    };
+
+    let (associated_types, post_types) = parse_optional_associated_types(post_trait, true)?;
+    let (functions, end, post_rest) =
+        parse_members(skip_new_lines(post_types), parse_impl_function)?;
+    Ok((
+        ImplDeclaration {
+            data_type,
+            trait_name,
+            associated_types,
+            functions,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_impl_function(source: &[Token]) -> Result<(FunctionDeclaration, &[Token]), Error> {
+    if match_token(&TokenType::Function, source) {
+        parse_function(source)
+    } else {
+        Err(error_at("expected function", source))
+    }
+}
+
+// 关联类型 `type Name` 或者 `type (Name, Name = Type)`，关键字 type 可以位于下一行，
+// 参数 is_type_required 表示每一项是否都必须指定类型
+fn parse_optional_associated_types(
+    source: &[Token],
+    is_type_required: bool,
+) -> Result<(Vec<AssociatedType>, &[Token]), Error> {
+    let post_new_lines = skip_new_lines(source);
+    if !match_identifier("type", post_new_lines) {
+        return Ok((vec![], source));
+    }
+
+    let parse_item = if is_type_required {
+        parse_required_associated_type
+    } else {
+        parse_associated_type
+    };
+
+    let post_type = move_forword(post_new_lines, 1);
+    if match_token(&TokenType::LeftParen, post_type) {
+        let (items, post_items) = parse_comma_separated(
+            move_forword(post_type, 1),
+            &TokenType::RightParen,
+            parse_item,
+        )?;
+        let (_, post_rest) = consume_token(&TokenType::RightParen, post_items)?;
+        Ok((items.items, post_rest))
+    } else {
+        let (item, post_rest) = parse_item(post_type)?;
+        Ok((vec![item], post_rest))
+    }
+}
+
+fn parse_associated_type(source: &[Token]) -> Result<(AssociatedType, &[Token]), Error> {
+    let (name, post_name) = parse_identifier(source)?;
+    if match_token(&TokenType::Assign, post_name) {
+        let (data_type, post_rest) = parse_data_type(move_forword(post_name, 1))?;
+        let location = join_location(&name.location, data_type.location());
+        Ok((
+            AssociatedType {
+                name,
+                data_type: Some(data_type),
+                location,
+            },
+            post_rest,
+        ))
+    } else {
+        let location = name.location.clone();
+        Ok((
+            AssociatedType {
+                name,
+                data_type: None,
+                location,
+            },
+            post_name,
+        ))
+    }
+}
+
+fn parse_required_associated_type(source: &[Token]) -> Result<(AssociatedType, &[Token]), Error> {
+    let (associated_type, rest) = parse_associated_type(source)?;
+    if associated_type.data_type.is_none() {
+        return Err(error_at("expected \"=\"", rest));
+    }
+    Ok((associated_type, rest))
+}
+
+// `struct Name { Type name, Type name }`
+fn parse_struct_declaration(source: &[Token]) -> Result<(StructDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Struct, source)?;
+    let (name, post_name) = parse_identifier(rest)?;
+    let (fields, end, post_rest) =
+        parse_members(skip_new_lines(post_name), parse_field_declaration)?;
+    Ok((
+        StructDeclaration {
+            name,
+            fields,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_field_declaration(source: &[Token]) -> Result<(FieldDeclaration, &[Token]), Error> {
+    let (data_type, post_data_type) = parse_data_type(source)?;
+    let (name, post_rest) = parse_identifier(post_data_type)?;
+    let location = join_location(data_type.location(), &name.location);
+    Ok((
+        FieldDeclaration {
+            data_type,
+            name,
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// `union Name { Member {Type name, ...}, Member }`
+fn parse_union_declaration(source: &[Token]) -> Result<(UnionDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Union, source)?;
+    let (name, post_name) = parse_identifier(rest)?;
+    let (members, end, post_rest) = parse_members(skip_new_lines(post_name), parse_union_member)?;
+    Ok((
+        UnionDeclaration {
+            name,
+            members,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_union_member(source: &[Token]) -> Result<(UnionMember, &[Token]), Error> {
+    let (name, post_name) = parse_identifier(source)?;
+    if match_token(&TokenType::LeftBrace, post_name) {
+        let (fields, end, post_rest) = parse_members(post_name, parse_field_declaration)?;
+        let location = join_location(&name.location, &end);
+        Ok((
+            UnionMember {
+                name,
+                fields,
+                location,
+            },
+            post_rest,
+        ))
+    } else {
+        let location = name.location.clone();
+        Ok((
+            UnionMember {
+                name,
+                fields: vec![],
+                location,
+            },
+            post_name,
+        ))
+    }
+}
+
+// `enum Name { Member Member Member }`
+fn parse_enum_declaration(source: &[Token]) -> Result<(EnumDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Enum, source)?;
+    let (name, post_name) = parse_identifier(rest)?;
+    let (members, end, post_rest) = parse_members(skip_new_lines(post_name), parse_identifier)?;
+    Ok((
+        EnumDeclaration {
+            name,
+            members,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+// `const Type Name = value` 或者 `const Type Name { Member = value, Member = value }`
+fn parse_const_declaration(source: &[Token]) -> Result<(ConstDeclaration, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Const, source)?;
+    let (data_type, post_data_type) = parse_data_type(rest)?;
+    let (name, post_name) = parse_identifier(post_data_type)?;
+
+    let (value, end, post_rest) = if match_token(&TokenType::Assign, post_name) {
+        let (value, post_value) = parse_expression(skip_new_lines(move_forword(post_name, 1)))?;
+        let end = value.location().clone();
+        (ConstValue::Single(value), end, post_value)
+    } else {
+        let (members, end, post_members) =
+            parse_members(skip_new_lines(post_name), parse_const_member)?;
+        (ConstValue::Group(members), end, post_members)
+    };
+
+    Ok((
+        ConstDeclaration {
+            data_type,
+            name,
+            value,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_const_member(source: &[Token]) -> Result<(ConstMember, &[Token]), Error> {
+    let (name, post_name) = parse_identifier(source)?;
+    let (_, post_assign) = consume_token(&TokenType::Assign, post_name)?;
+    let (value, post_rest) = parse_expression(skip_new_lines(post_assign))?;
+    let location = join_location(&name.location, value.location());
+    Ok((
+        ConstMember {
+            name,
+            value,
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// 解析声明语句花括号里的成员列表，成员之间使用逗号、换行或者空白分隔，
+// 参数 source 从左花括号开始，返回的位置为右花括号的位置
+fn parse_members<T>(
+    source: &[Token],
+    parse_item: ParseFn<T>,
+) -> Result<(Vec<T>, Location, &[Token]), Error> {
+    let (_, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    let mut source = skip_new_lines(rest);
+    let mut items: Vec<T> = vec![];
+
+    while !match_token(&TokenType::RightBrace, source) {
+        let (item, post_item) = parse_item(source)?;
+        items.push(item);
+
+        source = if match_token(&TokenType::Comma, post_item) {
+            skip_new_lines(move_forword(post_item, 1))
+        } else {
+            skip_new_lines(post_item)
+        };
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, source)?;
+    Ok((items, end, post_rest))
+}
+
+// 参数 `Type name = default`，其中数据类型和默认值都可以省略
+fn parse_parameter(source: &[Token]) -> Result<(Parameter, &[Token]), Error> {
+    let (data_type, post_data_type) = parse_optional_parameter_type(source)?;
+    let (name, post_name) = parse_identifier(post_data_type)?;
+
+    let (default_value, post_rest) = if match_token(&TokenType::Assign, post_name) {
+        let (value, rest) = parse_expression(skip_new_lines(move_forword(post_name, 1)))?;
+        (Some(value), rest)
+    } else {
+        (None, post_name)
+    };
+
+    let start = match &data_type {
+        Some(data_type) => data_type.location(),
+        None => &name.location,
+    };
+    let end = match &default_value {
+        Some(value) => value.location(),
+        None => &name.location,
+    };
+    let location = join_location(start, end);
+
+    Ok((
+        Parameter {
+            data_type,
+            pattern: Pattern::Identifier(name),
+            default_value,
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// 模式匹配函数的参数 `Type pattern`，其中数据类型可以省略
+fn parse_pattern_parameter(source: &[Token]) -> Result<(Parameter, &[Token]), Error> {
+    let (data_type, post_data_type) = if is_data_type_followed_by_pattern(source) {
+        let (data_type, rest) = parse_data_type(source)?;
+        (Some(data_type), rest)
+    } else {
+        (None, source)
+    };
+    let (pattern, post_rest) = parse_pattern(post_data_type)?;
+
+    let start = match &data_type {
+        Some(data_type) => data_type.location(),
+        None => pattern.location(),
+    };
+    let location = join_location(start, pattern.location());
+
+    Ok((
+        Parameter {
+            data_type,
+            pattern,
+            default_value: None,
+            location,
+        },
+        post_rest,
+    ))
+}
+
+// 参数的数据类型后面紧跟着参数名称（或者模式）
+fn parse_optional_parameter_type(source: &[Token]) -> Result<(Option<DataType>, &[Token]), Error> {
+    if is_data_type_followed_by_name(source) {
+        let (data_type, rest) = parse_data_type(source)?;
+        Ok((Some(data_type), rest))
+    } else {
+        Ok((None, source))
+    }
+}
+
+fn is_data_type_followed_by_name(source: &[Token]) -> bool {
+    match parse_data_type(source) {
+        Ok((_, rest)) => matches!(
+            rest.first(),
+            Some(Token {
+                token_type: TokenType::Identifier(_),
+                ..
+            })
+        ),
+        Err(_) => false,
+    }
+}
+
+fn is_data_type_followed_by_pattern(source: &[Token]) -> bool {
+    match parse_data_type(source) {
+        Ok((_, rest)) => matches!(
+            rest.first(),
+            Some(Token {
+                token_type: TokenType::Identifier(_)
+                    | TokenType::LeftParen
+                    | TokenType::LeftBracket,
+                ..
+            })
+        ),
+        Err(_) => false,
+    }
+}
+
+// 返回值类型 `type Type`，关键字 type 可以位于下一行
+fn parse_optional_return_type(source: &[Token]) -> Result<(Option<DataType>, &[Token]), Error> {
+    let post_new_lines = skip_new_lines(source);
+    if match_identifier("type", post_new_lines) {
+        let (data_type, rest) = parse_data_type(move_forword(post_new_lines, 1))?;
+        Ok((Some(data_type), rest))
+    } else {
+        Ok((None, source))
+    }
+}
+
+// which 块 `{ T: Type, F: sign (...) type Type, U: limit Display, Ordered }`，
+// 参数 source 从 which 关键字之后开始，各项之间使用逗号或者换行分隔
+fn parse_which(source: &[Token]) -> Result<(Vec<WhichEntry>, &[Token]), Error> {
+    let (_, rest) = consume_token(&TokenType::LeftBrace, skip_new_lines(source))?;
+    let mut source = skip_new_lines(rest);
+    let mut entries: Vec<WhichEntry> = vec![];
+
+    while !match_token(&TokenType::RightBrace, source) {
+        let (name, post_name) = parse_identifier(source)?;
+        let (_, post_colon) = consume_token(&TokenType::Colon, post_name)?;
+        let post_colon = skip_new_lines(post_colon);
+
+        let (constraint, end, post_constraint) = if match_identifier("limit", post_colon) {
+            let mut traits: Vec<DataType> = vec![];
+            let mut post_traits = skip_new_lines(move_forword(post_colon, 1));
+            loop {
+                let (data_type, rest) = parse_data_type(post_traits)?;
+                traits.push(data_type);
+                post_traits = rest;
+
+                // 逗号后面如果是 `name:` 则表示下一项
+                let post_comma = match post_traits.split_first() {
+                    Some((first, rest)) if first.token_type == TokenType::Comma => {
+                        skip_new_lines(rest)
+                    }
+                    _ => break,
+                };
+                if match_token(&TokenType::Colon, move_forword(post_comma, 1)) {
+                    break;
+                }
+                post_traits = post_comma;
+            }
+
+            let end = traits[traits.len() - 1].location().clone();
+            (TypeConstraint::Limit(traits), end, post_traits)
+        } else {
+            let (data_type, rest) = parse_data_type(post_colon)?;
+            let end = data_type.location().clone();
+            (TypeConstraint::DataType(data_type), end, rest)
+        };
+
+        let location = join_location(&name.location, &end);
+        entries.push(WhichEntry {
+            name,
+            constraint,
+            location,
+        });
+
+        source = if match_token(&TokenType::Comma, post_constraint) {
+            skip_new_lines(move_forword(post_constraint, 1))
+        } else {
+            skip_new_lines(consume_end_of_line(
+                post_constraint,
+                Some(&TokenType::RightBrace),
+            )?)
+        };
+    }
+
+    let (_, post_rest) = consume_token(&TokenType::RightBrace, source)?;
+    Ok((entries, post_rest))
+}
+
+fn parse_expression(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    parse_binary_expression(source, 0)
+}
+
+// 中置运算符，包括二元运算符和中置调用 `a :name: b`
+enum InfixOperator {
+    Binary(BinaryOperator),
+    NamedFunction(String),
+}
+
+fn infix_operator(token_type: &TokenType) -> Option<(InfixOperator, u8)> {
+    let operator = match token_type {
+        TokenType::Forward => BinaryOperator::Forward,
+        TokenType::Pipe => BinaryOperator::Pipe,
+        TokenType::NamedOperator(name) => {
+            return Some((InfixOperator::NamedFunction(name.clone()), 3))
+        }
+        TokenType::LogicOr => BinaryOperator::LogicOr,
+        TokenType::LogicAnd => BinaryOperator::LogicAnd,
+        TokenType::Equal => BinaryOperator::Equal,
+        TokenType::NotEqual => BinaryOperator::NotEqual,
+        TokenType::GreaterThan => BinaryOperator::GreaterThan,
+        TokenType::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
+        TokenType::LessThan => BinaryOperator::LessThan,
+        TokenType::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
+        TokenType::Concat => BinaryOperator::Concat,
+        TokenType::Plus => BinaryOperator::Add,
+        TokenType::Minus => BinaryOperator::Subtract,
+        TokenType::Asterisk => BinaryOperator::Multiply,
+        TokenType::Slash => BinaryOperator::Divide,
+        TokenType::UnwrapOr => BinaryOperator::UnwrapOr,
+        TokenType::Combine => BinaryOperator::Combine,
+        _ => return None,
+    };
+    Some((InfixOperator::Binary(operator), binary_precedence(operator)))
+}
+
+// 二元运算符的优先级，数值越大优先级越高，
+// 中置调用 `:name:` 的优先级为 3，位于管道和逻辑或之间
+pub(crate) fn binary_precedence(operator: BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::Forward => 1,
+        BinaryOperator::Pipe => 2,
+        BinaryOperator::LogicOr => 4,
+        BinaryOperator::LogicAnd => 5,
+        BinaryOperator::Equal | BinaryOperator::NotEqual => 6,
+        BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqual
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqual => 7,
+        BinaryOperator::Concat => 8,
+        BinaryOperator::Add | BinaryOperator::Subtract => 9,
+        BinaryOperator::Multiply | BinaryOperator::Divide => 10,
+        BinaryOperator::UnwrapOr => 11,
+        BinaryOperator::Combine => 12,
+    }
+}
+
+fn parse_binary_expression(
+    source: &[Token],
+    min_precedence: u8,
+) -> Result<(Expression, &[Token]), Error> {
+    let (mut left, mut source) = parse_prefix_expression(source)?;
+
+    loop {
+        let post_new_lines = skip_leading_operator_new_lines(source);
+        let (operator, precedence, operator_location, rest) = match post_new_lines.split_first() {
+            Some((first, rest)) => match infix_operator(&first.token_type) {
+                Some((operator, precedence)) if precedence > min_precedence => {
+                    (operator, precedence, &first.location, rest)
+                }
+                _ => break,
+            },
+            None => break,
+        };
+
+        // 二元运算符后面允许换行
+        let (right, post_rest) = parse_binary_expression(skip_new_lines(rest), precedence)?;
+        let location = join_location(left.location(), right.location());
+        left = match operator {
+            InfixOperator::Binary(operator) => Expression::Binary(Box::new(BinaryExpression {
+                operator,
+                left,
+                right,
+                location,
+            })),
+            InfixOperator::NamedFunction(name) => Expression::Call(Box::new(CallExpression {
+                callee: Expression::Identifier(Identifier {
+                    name,
+                    location: operator_location.clone(),
+                }),
+                generic_arguments: vec![],
+                arguments: vec![left, right],
+                keyword_arguments: vec![],
+                location,
+            })),
+        };
+        source = post_rest;
+    }
+
+    Ok((left, source))
+}
+
+fn skip_leading_operator_new_lines(source: &[Token]) -> &[Token] {
+    let post_new_lines = skip_new_lines(source);
+    match post_new_lines.first() {
+        Some(token)
+            if token.token_type != TokenType::Minus
+                && infix_operator(&token.token_type).is_some() =>
+        {
+            post_new_lines
+        }
+        _ => source,
+    }
+}
+
+fn parse_prefix_expression(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    match source.split_first() {
+        Some((first, rest)) if first.token_type == TokenType::Minus => {
+            let (operand, post_rest) = parse_prefix_expression(rest)?;
+            let location = join_location(&first.location, operand.location());
+            Ok((
+                Expression::Prefix(Box::new(PrefixExpression {
+                    operator: PrefixOperator::Negative,
+                    operand,
+                    location,
+                })),
+                post_rest,
+            ))
+        }
+        _ => parse_postfix_expression(source),
+    }
+}
+
+// 后置表达式链，包括函数调用 `f(...)`、对象成员 `.name`、元组元素 `.0`、
+// 索引 `x[...]`、框选 `x[start:end]`，以及后置运算符 `^` 和 `?`，
+// 它们按照从左到右的顺序结合，比如 `a.b(c)[0]?`
+fn parse_postfix_expression(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (mut expression, mut source) = parse_primary_expression(source)?;
+
+    loop {
+        if let Some((generic_arguments, _, post_generic)) =
+            try_parse_call_generic_arguments(&expression, source)
+        {
+            (expression, source) = parse_call(expression, generic_arguments, post_generic)?;
+            continue;
+        }
+
+        // 函数调用和索引的左括号必须紧跟在前一个表达式之后，
+        // 以避免跟后面的元组、分组或者列表混淆，比如 `each let i in xs (i, i)`
+        if match_adjacent_token(&TokenType::LeftParen, expression.location(), source) {
+            (expression, source) = parse_call(expression, vec![], source)?;
+            continue;
+        }
+
+        if match_adjacent_token(&TokenType::LeftBracket, expression.location(), source) {
+            (expression, source) = parse_index_or_slice(expression, source)?;
+            continue;
+        }
+
+        // 以 `.` 开头的行是上一行的方法调用链的延续
+        let post_new_lines = skip_new_lines(source);
+        if match_token(&TokenType::Dot, post_new_lines) {
+            (expression, source) = parse_member(expression, post_new_lines)?;
+            continue;
+        }
+
+        let operator = match source.first() {
+            Some(token) if token.token_type == TokenType::Cast => PostfixOperator::Cast,
+            Some(token) if token.token_type == TokenType::Unwrap => PostfixOperator::Unwrap,
+            _ => break,
+        };
+        let location = join_location(expression.location(), &source[0].location);
+        expression = Expression::Postfix(Box::new(PostfixExpression {
+            operator,
+            operand: expression,
+            location,
+        }));
+        source = move_forword(source, 1);
+    }
+
+    Ok((expression, source))
+}
+
+// 参数 source 从左括号开始
+fn parse_call(
+    callee: Expression,
+    generic_arguments: Vec<DataType>,
+    source: &[Token],
+) -> Result<(Expression, &[Token]), Error> {
+    let (arguments, post_rest) = parse_call_arguments(source)?;
+    let location = join_location(callee.location(), &arguments.end);
+    Ok((
+        Expression::Call(Box::new(CallExpression {
+            callee,
+            generic_arguments,
+            arguments: arguments.positional,
+            keyword_arguments: arguments.keyword,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+struct CallArguments {
+    positional: Vec<Expression>,
+    keyword: Vec<KeywordArgument>,
+    end: Location,
+}
+
+enum CallArgument {
+    Positional(Expression),
+    Keyword(KeywordArgument),
+}
+
+// 参数列表 `(a, b, name=value)`，参数 source 从左括号开始
+fn parse_call_arguments(source: &[Token]) -> Result<(CallArguments, &[Token]), Error> {
+    let (_, post_left_paren) = consume_token(&TokenType::LeftParen, source)?;
+    let (items, rest) =
+        parse_comma_separated(post_left_paren, &TokenType::RightParen, parse_call_argument)?;
+    let (end, post_rest) = consume_token(&TokenType::RightParen, rest)?;
+
+    let mut positional = vec![];
+    let mut keyword: Vec<KeywordArgument> = vec![];
+    for item in items.items {
+        match item {
+            CallArgument::Positional(argument) if !keyword.is_empty() => {
+                return Err(Error::ParserError(
+                    "positional argument cannot follow keyword argument",
+                    Some(argument.location().clone()),
+                ))
+            }
+            CallArgument::Positional(argument) => positional.push(argument),
+            CallArgument::Keyword(argument) => {
+                if keyword.iter().any(|k| k.name.name == argument.name.name) {
+                    return Err(Error::ParserError(
+                        "duplicate keyword argument",
+                        Some(argument.location.clone()),
+                    ));
+                }
+                keyword.push(argument)
+            }
+        }
+    }
+
+    Ok((
+        CallArguments {
+            positional,
+            keyword,
+            end,
+        },
+        post_rest,
+    ))
+}
+
+fn parse_call_argument(source: &[Token]) -> Result<(CallArgument, &[Token]), Error> {
+    match source {
+        [Token {
+            token_type: TokenType::Identifier(_),
+            ..
+        }, Token {
+            token_type: TokenType::Assign,
+            ..
+        }, ..] => {
+            let (name, post_name) = parse_identifier(source)?;
+            let (value, post_rest) = parse_expression(skip_new_lines(move_forword(post_name, 1)))?;
+            let location = join_location(&name.location, value.location());
+            Ok((
+                CallArgument::Keyword(KeywordArgument {
+                    name,
+                    value,
+                    location,
+                }),
+                post_rest,
+            ))
+        }
+        _ => {
+            let (argument, rest) = parse_expression(source)?;
+            Ok((CallArgument::Positional(argument), rest))
+        }
+    }
+}
+
+// 前置调用 `!name (data, arg1, arg2)`，被调用者可以是路径，
+// 参数列表不要求紧跟在名称之后
+fn parse_prefix_call(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Exclamation, source)?;
+    let (callee, post_callee) = if match_token(&TokenType::PathSeparator, move_forword(rest, 1)) {
+        let (path, post_path) = parse_path(rest)?;
+        (Expression::Path(path), post_path)
+    } else {
+        let (name, post_name) = parse_identifier(rest)?;
+        (Expression::Identifier(name), post_name)
+    };
+
+    let (arguments, post_rest) = parse_call_arguments(post_callee)?;
+    Ok((
+        Expression::Call(Box::new(CallExpression {
+            callee,
+            generic_arguments: vec![],
+            arguments: arguments.positional,
+            keyword_arguments: arguments.keyword,
+            location: join_location(&start, &arguments.end),
+        })),
+        post_rest,
+    ))
+}
+
+// 索引 `x[index]` 或者框选 `x[start:end]`，参数 source 从左方括号开始
+fn parse_index_or_slice(
+    object: Expression,
+    source: &[Token],
+) -> Result<(Expression, &[Token]), Error> {
+    let post_left_bracket = skip_new_lines(move_forword(source, 1));
+    let (start, post_start) = if match_token(&TokenType::Colon, post_left_bracket) {
+        (None, post_left_bracket)
+    } else {
+        let (start, rest) = parse_expression(post_left_bracket)?;
+        (Some(start), skip_new_lines(rest))
+    };
+
+    if !match_token(&TokenType::Colon, post_start) {
+        let index = match start {
+            Some(index) => index,
+            None => return Err(error_at("expected expression", post_start)),
+        };
+        let (end, post_rest) = consume_token(&TokenType::RightBracket, post_start)?;
+        let location = join_location(object.location(), &end);
+        return Ok((
+            Expression::Index(Box::new(IndexExpression {
+                object,
+                index,
+                location,
+            })),
+            post_rest,
+        ));
+    }
+
+    let post_colon = skip_new_lines(move_forword(post_start, 1));
+    let (end, post_end) = if match_token(&TokenType::RightBracket, post_colon) {
+        (None, post_colon)
+    } else {
+        let (end, rest) = parse_expression(post_colon)?;
+        (Some(end), skip_new_lines(rest))
+    };
+
+    let (right_bracket, post_rest) = consume_token(&TokenType::RightBracket, post_end)?;
+    let location = join_location(object.location(), &right_bracket);
+    Ok((
+        Expression::Slice(Box::new(SliceExpression {
+            object,
+            start,
+            end,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+// 对象成员 `.name` 或者元组元素 `.0`，参数 source 从点号开始。
+// 词法分析器不识别浮点数，所以 `t.0.1` 会被分析为 `t . 0 . 1`，
+// 即依次访问两层元组元素
+fn parse_member(object: Expression, source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let post_dot = move_forword(source, 1);
+    match post_dot.first() {
+        Some(Token {
+            token_type: TokenType::Integer(index),
+            location: end,
+        }) => {
+            let location = join_location(object.location(), end);
+            Ok((
+                Expression::TupleIndex(Box::new(TupleIndexExpression {
+                    object,
+                    index: *index as usize,
+                    location,
+                })),
+                move_forword(post_dot, 1),
+            ))
+        }
+        _ => {
+            let (property, post_rest) = parse_identifier(post_dot)?;
+            let location = join_location(object.location(), &property.location);
+            Ok((
+                Expression::Member(Box::new(MemberExpression {
+                    object,
+                    property,
+                    location,
+                })),
+                post_rest,
+            ))
+        }
+    }
+}
+
+// 泛型参数具体化的调用 `name<Type>(a, b)`，
+// 只有当 `<` 紧跟在名称之后、`>` 紧跟着左括号，且尖括号之间是合法的数据类型时，
+// 才视为泛型参数，否则视为比较运算，比如 `a < b > c`、`a<b>c`
+fn try_parse_call_generic_arguments<'a>(
+    callee: &Expression,
+    source: &'a [Token],
+) -> Option<(Vec<DataType>, Location, &'a [Token])> {
+    if !matches!(callee, Expression::Identifier(_) | Expression::Path(_))
+        || !match_adjacent_token(&TokenType::LessThan, callee.location(), source)
+    {
+        return None;
+    }
+
+    match parse_generic_arguments(move_forword(source, 1)) {
+        Ok((arguments, rest))
+            if !arguments.is_closing_pending
+                && match_adjacent_token(&TokenType::LeftParen, &arguments.end, rest) =>
+        {
+            Some((arguments.items, arguments.end, rest))
+        }
+        _ => None,
+    }
+}
+
+fn parse_primary_expression(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (first, rest) = match source.split_first() {
+        Some(item) => item,
+        None => return Err(error_at("unexpected end of file", source)),
+    };
+
+    match &first.token_type {
+        TokenType::Identifier(_) if match_token(&TokenType::Arrow, rest) => {
+            parse_single_parameter_function(source)
+        }
+        TokenType::Identifier(name) if name == "fn" && match_token(&TokenType::LeftParen, rest) => {
+            match try_parse_fn_function(source) {
+                Some(result) => result,
+                None => Ok((
+                    Expression::Identifier(Identifier {
+                        name: name.clone(),
+                        location: first.location.clone(),
+                    }),
+                    rest,
+                )),
+            }
+        }
+        TokenType::Identifier(_) if match_token(&TokenType::PathSeparator, rest) => {
+            let (path, post_path) = parse_path(source)?;
+            Ok((Expression::Path(path), post_path))
+        }
+        TokenType::Identifier(name)
+            if is_type_name(name) && match_token(&TokenType::LeftBrace, rest) =>
+        {
+            let (name, post_name) = parse_identifier(source)?;
+            parse_struct_expression(Some(name), post_name)
+        }
+        TokenType::Identifier(name) => Ok((
+            Expression::Identifier(Identifier {
+                name: name.clone(),
+                location: first.location.clone(),
+            }),
+            rest,
+        )),
+        TokenType::LeftParen => match try_parse_anonymous_function(source) {
+            Some(result) => result,
+            None => parse_tuple_or_group(source),
+        },
+        TokenType::LeftBracket => parse_list(source),
+        TokenType::Hash if match_token(&TokenType::LeftBracket, rest) => parse_array(source),
+        TokenType::Exclamation if match_token(&TokenType::LeftBracket, rest) => {
+            parse_matrix(source)
+        }
+        TokenType::Exclamation => parse_prefix_call(source),
+        TokenType::LeftBrace => parse_block_or_struct(source),
+        TokenType::Namespace | TokenType::Use | TokenType::Alias => Err(Error::ParserError(
+            "namespace, use and alias are only allowed at module level",
+            Some(first.location.clone()),
+        )),
+        TokenType::Let => parse_let(source),
+        TokenType::If => parse_if(source),
+        TokenType::Branch => parse_branch(source),
+        TokenType::Match => parse_match(source),
+        TokenType::For => parse_for(source),
+        TokenType::Each => parse_each(source),
+        TokenType::Next => parse_next(source),
+        _ => match to_literal(first) {
+            Some(literal) => Ok((Expression::Literal(literal), rest)),
+            None => Err(error_at("unexpected token", source)),
+        },
+    }
+}
+
+fn to_literal(token: &Token) -> Option<Literal> {
+    let value = match &token.token_type {
+        TokenType::Integer(value) => LiteralValue::Integer(*value),
+        TokenType::Boolean(value) => LiteralValue::Boolean(*value),
+        TokenType::Char(value) => LiteralValue::Char(*value),
+        TokenType::String(value) => LiteralValue::String(value.clone()),
+        TokenType::TemplateString(value) => LiteralValue::TemplateString(value.clone()),
+        TokenType::HashString(value) => LiteralValue::HashString(value.clone()),
+        TokenType::Regexp(value) => LiteralValue::Regexp(value.clone()),
+        _ => return None,
+    };
+
+    Some(Literal {
+        value,
+        location: token.location.clone(),
+    })
+}
+
+// 匿名函数跟分组以及元组都是以左括号开始，所以先尝试按照匿名函数的参数列表解析，
+// 只有参数列表（以及可选的返回值类型）之后是 `=>` 时才确定为匿名函数，
+// 否则返回 None 并回溯到左括号重新按照分组或者元组解析
+fn try_parse_anonymous_function(source: &[Token]) -> Option<Result<(Expression, &[Token]), Error>> {
+    let start = source[0].location.clone();
+    let (parameters, post_parameters) = parse_comma_separated(
+        move_forword(source, 1),
+        &TokenType::RightParen,
+        parse_parameter,
+    )
+    .ok()?;
+    let (_, post_right_paren) = consume_token(&TokenType::RightParen, post_parameters).ok()?;
+
+    let (return_type, post_return_type) = if match_identifier("type", post_right_paren) {
+        let (data_type, rest) = parse_data_type(move_forword(post_right_paren, 1)).ok()?;
+        (Some(data_type), rest)
+    } else {
+        (None, post_right_paren)
+    };
+
+    if !match_token(&TokenType::Arrow, post_return_type) {
+        return None;
+    }
+
+    // 确定是匿名函数之后，函数体的错误不再回溯
+    let result = parse_anonymous_function_body(
+        start,
+        parameters.items,
+        return_type,
+        move_forword(post_return_type, 1),
+    );
+    Some(result)
+}
+
+// `fn (params) type Type {...}` 形式的匿名函数，函数体必须是表达式块，
+// 跟 `=>` 形式一样先尝试解析参数列表，之后不是左花括号时返回 None，
+// 此时 fn 作为普通的标识符解析
+fn try_parse_fn_function(source: &[Token]) -> Option<Result<(Expression, &[Token]), Error>> {
+    let start = source[0].location.clone();
+    let (parameters, post_parameters) = parse_comma_separated(
+        move_forword(source, 2),
+        &TokenType::RightParen,
+        parse_parameter,
+    )
+    .ok()?;
+    let (_, post_right_paren) = consume_token(&TokenType::RightParen, post_parameters).ok()?;
+
+    let (return_type, post_return_type) = if match_identifier("type", post_right_paren) {
+        let (data_type, rest) = parse_data_type(move_forword(post_right_paren, 1)).ok()?;
+        (Some(data_type), rest)
+    } else {
+        (None, post_right_paren)
+    };
+
+    if !match_token(&TokenType::LeftBrace, post_return_type) {
+        return None;
+    }
+
+    let result = parse_block(post_return_type).map(|(body, post_body)| {
+        let location = join_location(&start, &body.location);
+        (
+            Expression::AnonymousFunction(Box::new(AnonymousFunction {
+                parameters: parameters.items,
+                return_type,
+                body: Expression::Block(body),
+                location,
+            })),
+            post_body,
+        )
+    });
+    Some(result)
+}
+
+// `name => expression`
+fn parse_single_parameter_function(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (name, post_name) = parse_identifier(source)?;
+    let (_, post_arrow) = consume_token(&TokenType::Arrow, post_name)?;
+    let start = name.location.clone();
+    let parameter = Parameter {
+        data_type: None,
+        location: name.location.clone(),
+        pattern: Pattern::Identifier(name),
+        default_value: None,
+    };
+    parse_anonymous_function_body(start, vec![parameter], None, post_arrow)
+}
+
+// 参数 source 从 `=>` 之后开始，`=>` 后面允许换行
+fn parse_anonymous_function_body(
+    start: Location,
+    parameters: Vec<Parameter>,
+    return_type: Option<DataType>,
+    source: &[Token],
+) -> Result<(Expression, &[Token]), Error> {
+    let (body, post_rest) = parse_expression(skip_new_lines(source))?;
+    let location = join_location(&start, body.location());
+    Ok((
+        Expression::AnonymousFunction(Box::new(AnonymousFunction {
+            parameters,
+            return_type,
+            body,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+// `(a)` 为分组，`()`、`(a,)`、`(a, b)` 为元组
+fn parse_tuple_or_group(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::LeftParen, source)?;
+    let (elements, post_elements) =
+        parse_comma_separated(rest, &TokenType::RightParen, parse_expression)?;
+    let (end, post_rest) = consume_token(&TokenType::RightParen, post_elements)?;
+
+    if elements.items.len() == 1 && !elements.has_trailing_comma {
+        let mut items = elements.items;
+        return Ok((items.remove(0), post_rest));
+    }
+
+    Ok((
+        Expression::Tuple(TupleExpression {
+            elements: elements.items,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+// 列表 `[a, b, ...c]`、范围 `[start..end]` 或者数列 `[one, two, ..end]`
+fn parse_list(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::LeftBracket, source)?;
+    let post_left_bracket = skip_new_lines(rest);
+    if let Some((end, post_rest)) = match_closing_bracket(post_left_bracket) {
+        return Ok((
+            Expression::List(ListExpression {
+                elements: vec![],
+                location: join_location(&start, &end),
+            }),
+            post_rest,
+        ));
+    }
+
+    let (first, post_first) = parse_list_element(post_left_bracket)?;
+    let mut source = skip_new_lines(post_first);
+
+    if match_token(&TokenType::Range, source) {
+        if matches!(first, Expression::Spread(_)) {
+            return Err(Error::ParserError(
+                "unexpected \"..\"",
+                Some(first.location().clone()),
+            ));
+        }
+        let (range_end, post_range_end) =
+            parse_expression(skip_new_lines(move_forword(source, 1)))?;
+        let (end, post_rest) =
+            consume_token(&TokenType::RightBracket, skip_new_lines(post_range_end))?;
+        return Ok((
+            Expression::Range(Box::new(RangeExpression {
+                start: first,
+                end: range_end,
+                location: join_location(&start, &end),
+            })),
+            post_rest,
+        ));
+    }
+
+    let mut elements = vec![first];
+    while match_token(&TokenType::Comma, source) {
+        let post_comma = skip_new_lines(move_forword(source, 1));
+        if match_token(&TokenType::Range, post_comma) {
+            return parse_sequence_end(start, elements, move_forword(post_comma, 1));
+        }
+        if match_token(&TokenType::RightBracket, post_comma) {
+            source = post_comma;
+            break;
+        }
+
+        let (element, post_element) = parse_list_element(post_comma)?;
+        elements.push(element);
+        source = skip_new_lines(post_element);
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBracket, source)?;
+    Ok((
+        Expression::List(ListExpression {
+            elements,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+// 数列的结束值 `..end]`，参数 source 从 `..` 之后开始
+fn parse_sequence_end(
+    start: Location,
+    elements: Vec<Expression>,
+    source: &[Token],
+) -> Result<(Expression, &[Token]), Error> {
+    if let Some(spread) = elements.iter().find(|e| matches!(e, Expression::Spread(_))) {
+        return Err(Error::ParserError(
+            "unexpected \"...\" in sequence",
+            Some(spread.location().clone()),
+        ));
+    }
+
+    let (sequence_end, post_sequence_end) = parse_expression(skip_new_lines(source))?;
+    let (end, post_rest) =
+        consume_token(&TokenType::RightBracket, skip_new_lines(post_sequence_end))?;
+    Ok((
+        Expression::Sequence(SequenceExpression {
+            elements,
+            end: Box::new(sequence_end),
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+fn match_closing_bracket(source: &[Token]) -> Option<(Location, &[Token])> {
+    match source.split_first() {
+        Some((token, rest)) if token.token_type == TokenType::RightBracket => {
+            Some((token.location.clone(), rest))
+        }
+        _ => None,
+    }
+}
+
+// 列表和数组的元素，可以是重组表达式 `...value`
+fn parse_list_element(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    if match_token(&TokenType::Ellipsis, source) {
+        let (spread, rest) = parse_spread(source)?;
+        Ok((Expression::Spread(Box::new(spread)), rest))
+    } else {
+        parse_expression(source)
+    }
+}
+
+fn parse_spread(source: &[Token]) -> Result<(SpreadExpression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Ellipsis, source)?;
+    let (value, post_rest) = parse_expression(rest)?;
+    let location = join_location(&start, value.location());
+    Ok((SpreadExpression { value, location }, post_rest))
+}
+
+// 数组 `#[a, b, ...c]`
+fn parse_array(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Hash, source)?;
+    let (_, post_left_bracket) = consume_token(&TokenType::LeftBracket, rest)?;
+    let (elements, post_elements) = parse_comma_separated(
+        post_left_bracket,
+        &TokenType::RightBracket,
+        parse_list_element,
+    )?;
+    let (end, post_rest) = consume_token(&TokenType::RightBracket, post_elements)?;
+
+    Ok((
+        Expression::Array(ListExpression {
+            elements: elements.items,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+// 矩阵 `![1, 2, 3\n4, 5, 6]`，
+// 行内的元素使用逗号分隔，行之间使用换行分隔，所有行的元素数量必须相同
+fn parse_matrix(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Exclamation, source)?;
+    let (_, post_left_bracket) = consume_token(&TokenType::LeftBracket, rest)?;
+
+    let mut rows: Vec<Vec<Expression>> = vec![];
+    let mut source = skip_new_lines(post_left_bracket);
+    while !match_token(&TokenType::RightBracket, source) {
+        let (first, mut post_row) = parse_expression(source)?;
+        let mut row = vec![first];
+        while match_token(&TokenType::Comma, post_row) {
+            let (element, rest) = parse_expression(move_forword(post_row, 1))?;
+            row.push(element);
+            post_row = rest;
+        }
+
+        if matches!(rows.first(), Some(first_row) if first_row.len() != row.len()) {
+            return Err(error_at("inconsistent matrix row length", source));
+        }
+
+        rows.push(row);
+        source = skip_new_lines(consume_end_of_line(
+            post_row,
+            Some(&TokenType::RightBracket),
+        )?);
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBracket, source)?;
+    Ok((
+        Expression::Matrix(MatrixExpression {
+            rows,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+// 映射 `{name: value, ...other}`，参数 source 从左花括号开始
+fn parse_map(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    let (entries, post_entries) =
+        parse_comma_separated(rest, &TokenType::RightBrace, parse_map_entry)?;
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, post_entries)?;
+
+    Ok((
+        Expression::Map(MapExpression {
+            entries: entries.items,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+fn parse_map_entry(source: &[Token]) -> Result<(MapEntry, &[Token]), Error> {
+    if match_token(&TokenType::Ellipsis, source) {
+        let (spread, rest) = parse_spread(source)?;
+        return Ok((MapEntry::Spread(spread), rest));
+    }
+
+    let (name, post_name) = parse_identifier(source)?;
+    let (_, post_colon) = consume_token(&TokenType::Colon, post_name)?;
+    let (value, post_rest) = parse_expression(skip_new_lines(post_colon))?;
+    let location = join_location(&name.location, value.location());
+    Ok((
+        MapEntry::Pair(MapPair {
+            name,
+            value,
+            location,
+        }),
+        post_rest,
+    ))
+}
+
+// 位于左花括号之后的 `name:` 或者 `...`，表示映射而不是表达式块
+fn is_map_entry_start(source: &[Token]) -> bool {
+    match source {
+        [Token {
+            token_type: TokenType::Identifier(_),
+            ..
+        }, Token {
+            token_type: TokenType::Colon,
+            ..
+        }, ..] => true,
+        _ => match_token(&TokenType::Ellipsis, source),
+    }
+}
+
+fn is_type_name(name: &str) -> bool {
+    match name.chars().next() {
+        Some(c) => c.is_uppercase(),
+        None => false,
+    }
+}
+
+// 表达式块跟省略名称的结构体实例化都是以左花括号开始，
+// 第一个表达式后面如果是逗号则为结构体实例化，比如 `{1, "foo", true}`
+fn parse_block_or_struct(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    let post_left_brace = skip_new_lines(rest);
+    if is_map_entry_start(post_left_brace) {
+        return parse_map(source);
+    }
+    if match_token(&TokenType::RightBrace, post_left_brace) {
+        let (block, post_rest) = parse_block_expressions(start, vec![], post_left_brace)?;
+        return Ok((Expression::Block(block), post_rest));
+    }
+
+    let (first, post_first) = parse_expression(post_left_brace)?;
+    if match_token(&TokenType::Comma, post_first) {
+        let (fields, post_fields) = parse_comma_separated(
+            move_forword(post_first, 1),
+            &TokenType::RightBrace,
+            parse_field_value,
+        )?;
+        let (end, post_rest) = consume_token(&TokenType::RightBrace, post_fields)?;
+
+        let mut values = vec![FieldValue {
+            name: None,
+            value: first,
+        }];
+        values.extend(fields.items);
+        if values.iter().any(|f| f.name.is_some()) {
+            return Err(Error::ParserError(
+                "cannot mix positional and named struct fields",
+                Some(join_location(&start, &end)),
+            ));
+        }
+
+        return Ok((
+            Expression::Struct(StructExpression {
+                name: None,
+                fields: values,
+                location: join_location(&start, &end),
+            }),
+            post_rest,
+        ));
+    }
+
+    let post_end_of_line = consume_end_of_line(post_first, Some(&TokenType::RightBrace))?;
+    let (block, post_rest) =
+        parse_block_expressions(start, vec![first], skip_new_lines(post_end_of_line))?;
+    Ok((Expression::Block(block), post_rest))
+}
+
+// `Name {value, value}` 或者 `Name {name: value, name: value}`，参数 source 从左花括号开始，
+// 按位置和按名称的成员不能混合使用
+fn parse_struct_expression(
+    name: Option<Identifier>,
+    source: &[Token],
+) -> Result<(Expression, &[Token]), Error> {
+    let (left_brace, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    let (fields, post_fields) =
+        parse_comma_separated(rest, &TokenType::RightBrace, parse_field_value)?;
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, post_fields)?;
+
+    let named_count = fields.items.iter().filter(|f| f.name.is_some()).count();
+    if named_count != 0 && named_count != fields.items.len() {
+        return Err(Error::ParserError(
+            "cannot mix positional and named struct fields",
+            Some(join_location(&left_brace, &end)),
+        ));
+    }
+
+    let start = match &name {
+        Some(name) => name.location.clone(),
+        None => left_brace,
+    };
+
+    Ok((
+        Expression::Struct(StructExpression {
+            name,
+            fields: fields.items,
+            location: join_location(&start, &end),
+        }),
+        post_rest,
+    ))
+}
+
+fn parse_field_value(source: &[Token]) -> Result<(FieldValue, &[Token]), Error> {
+    match source {
+        [Token {
+            token_type: TokenType::Identifier(_),
+            ..
+        }, Token {
+            token_type: TokenType::Colon,
+            ..
+        }, ..] => {
+            let (name, post_name) = parse_identifier(source)?;
+            let (value, post_rest) = parse_expression(skip_new_lines(move_forword(post_name, 1)))?;
+            Ok((
+                FieldValue {
+                    name: Some(name),
+                    value,
+                },
+                post_rest,
+            ))
+        }
+        _ => {
+            let (value, post_rest) = parse_expression(source)?;
+            Ok((FieldValue { name: None, value }, post_rest))
+        }
+    }
+}
+
+// 表达式块里的表达式使用换行分隔
+fn parse_block(source: &[Token]) -> Result<(BlockExpression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    parse_block_expressions(start, vec![], skip_new_lines(rest))
+}
+
+// 继续解析表达式块里剩余的表达式直到右花括号，参数 expressions 为已经解析的表达式
+fn parse_block_expressions(
+    start: Location,
+    expressions: Vec<Expression>,
+    source: &[Token],
+) -> Result<(BlockExpression, &[Token]), Error> {
+    let mut expressions = expressions;
+    let mut source = source;
+
+    while !match_token(&TokenType::RightBrace, source) {
+        // 未闭合的块由外层的语句负责恢复
+        if source.is_empty() {
+            return Err(error_at("unexpected end of file", source));
+        }
+
+        let post_expression = match parse_expression(source) {
+            Ok((expression, rest)) => {
+                expressions.push(expression);
+                consume_end_of_line(rest, Some(&TokenType::RightBrace))
+            }
+            Err(error) => Err(error),
+        };
+
+        source = match post_expression {
+            Ok(rest) => skip_new_lines(rest),
+            Err(error) => {
+                let (node, rest) = recover(error, source, true);
+                expressions.push(Expression::Error(node));
+                skip_new_lines(rest)
+            }
+        };
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, source)?;
+    Ok((
+        BlockExpression {
+            expressions,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+fn parse_let(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Let, source)?;
+    let (pattern, post_pattern) = parse_pattern(rest)?;
+
+    let (is_match, post_operator) = match post_pattern.split_first() {
+        Some((first, post_first)) if first.token_type == TokenType::Assign => (false, post_first),
+        Some((first, post_first)) if first.token_type == TokenType::Match => (true, post_first),
+        _ => return Err(error_at("expected \"=\" or \"match\"", post_pattern)),
+    };
+
+    let (value, post_rest) = parse_expression(skip_new_lines(post_operator))?;
+    let location = join_location(&start, value.location());
+    Ok((
+        Expression::Let(Box::new(LetExpression {
+            pattern,
+            value,
+            is_match,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+// if 表达式各部分的后面都允许换行，
+// 当 consequent 是位于同一行的表达式块时，关键字 then 可以省略，即 `if (...) {...} else {...}`
+fn parse_if(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::If, source)?;
+    let rest = skip_new_lines(rest);
+
+    // 条件表达式里不允许（不在括号内的）结构体实例化，
+    // 以免 `if a < Max {1}` 的 consequent 被当成结构体 `Max {1}` 的字段
+    let length = struct_free_length(rest);
+    let (condition, post_condition) = parse_expression(&rest[..length])?;
+    let post_condition = &rest[length - post_condition.len()..];
+    let (where_clause, post_where) = parse_optional_where(post_condition)?;
+
+    let post_then = if match_token(&TokenType::LeftBrace, post_where) {
+        post_where
+    } else {
+        consume_token(&TokenType::Then, skip_new_lines(post_where))?.1
+    };
+    let (consequent, post_consequent) = parse_expression(skip_new_lines(post_then))?;
+
+    let post_new_lines = skip_new_lines(post_consequent);
+    let (alternative, post_rest) = if match_token(&TokenType::Else, post_new_lines) {
+        let (alternative, post_alternative) =
+            parse_expression(skip_new_lines(move_forword(post_new_lines, 1)))?;
+        (Some(alternative), post_alternative)
+    } else {
+        (None, post_consequent)
+    };
+
+    let end = match &alternative {
+        Some(alternative) => alternative.location(),
+        None => consequent.location(),
+    };
+    let location = join_location(&start, end);
+
+    Ok((
+        Expression::If(Box::new(IfExpression {
+            condition,
+            where_clause,
+            consequent,
+            alternative,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+// 返回第一个位于括号之外的 `TypeName {` 的左花括号之前的 token 数量，
+// 扫描在条件表达式结束的位置（括号之外的 then、where 或者不接着运算符的换行）停止，
+// 以免每个 if 都扫描到源码的末尾
+fn struct_free_length(source: &[Token]) -> usize {
+    let mut depth = 0;
+    for (index, token) in source.iter().enumerate() {
+        match &token.token_type {
+            TokenType::Then | TokenType::Where if depth == 0 => return index,
+            TokenType::NewLine if depth == 0 => {
+                let after_operator =
+                    index > 0 && infix_operator(&source[index - 1].token_type).is_some();
+                let before_operator =
+                    skip_leading_operator_new_lines(&source[index..]).len() < source.len() - index;
+                if !after_operator && !before_operator {
+                    return index;
+                }
+            }
+            TokenType::LeftBrace if depth == 0 && index > 0 => {
+                if let TokenType::Identifier(name) = &source[index - 1].token_type {
+                    if is_type_name(name) {
+                        return index;
+                    }
+                }
+                depth += 1;
+            }
+            TokenType::LeftParen | TokenType::LeftBracket | TokenType::LeftBrace => depth += 1,
+            TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+// where 子表达式，关键字 where 可以位于下一行
+fn parse_optional_where(source: &[Token]) -> Result<(Option<Expression>, &[Token]), Error> {
+    let post_new_lines = skip_new_lines(source);
+    if match_token(&TokenType::Where, post_new_lines) {
+        let (expression, rest) = parse_expression(skip_new_lines(move_forword(post_new_lines, 1)))?;
+        Ok((Some(expression), rest))
+    } else {
+        Ok((None, source))
+    }
+}
+
+fn parse_branch(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Branch, source)?;
+    let (where_clause, post_where) = parse_optional_where(rest)?;
+    let (_, post_left_brace) = consume_token(&TokenType::LeftBrace, skip_new_lines(post_where))?;
+
+    let mut source = skip_new_lines(post_left_brace);
+    let mut cases: Vec<BranchCase> = vec![];
+    let mut default: Option<Expression> = None;
+
+    while !match_token(&TokenType::RightBrace, source) {
+        if default.is_some() {
+            return Err(error_at("default must be the last case", source));
+        }
+
+        let post_case = if match_identifier("case", source) {
+            let case_start = source[0].location.clone();
+            let (condition, post_condition) = parse_expression(move_forword(source, 1))?;
+            let (where_clause, post_where) = parse_optional_where(post_condition)?;
+            let (body, post_body) = parse_case_body(post_where)?;
+            let location = join_location(&case_start, body.location());
+            cases.push(BranchCase {
+                condition,
+                where_clause,
+                body,
+                location,
+            });
+            post_body
+        } else if match_identifier("default", source) {
+            let (body, post_body) = parse_case_body(move_forword(source, 1))?;
+            default = Some(body);
+            post_body
+        } else {
+            return Err(error_at("expected \"case\" or \"default\"", source));
+        };
+
+        source = skip_new_lines(consume_case_separator(post_case)?);
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, source)?;
+    Ok((
+        Expression::Branch(Box::new(BranchExpression {
+            where_clause,
+            cases,
+            default,
+            location: join_location(&start, &end),
+        })),
+        post_rest,
+    ))
+}
+
+fn parse_match(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Match, source)?;
+    let (subject, post_subject) = parse_expression(skip_new_lines(rest))?;
+    let (where_clause, post_where) = parse_optional_where(post_subject)?;
+    let (_, post_left_brace) = consume_token(&TokenType::LeftBrace, skip_new_lines(post_where))?;
+
+    let mut source = skip_new_lines(post_left_brace);
+    let mut cases: Vec<MatchCase> = vec![];
+    let mut default: Option<Expression> = None;
+
+    while !match_token(&TokenType::RightBrace, source) {
+        if default.is_some() {
+            return Err(error_at("default must be the last case", source));
+        }
+
+        let post_case = if match_identifier("case", source) {
+            let (case, post_body) = parse_match_case(source)?;
+            cases.push(case);
+            post_body
+        } else if match_identifier("default", source) {
+            let (body, post_body) = parse_case_body(move_forword(source, 1))?;
+            default = Some(body);
+            post_body
+        } else {
+            return Err(error_at("expected \"case\" or \"default\"", source));
+        };
+
+        source = skip_new_lines(consume_case_separator(post_case)?);
+    }
+
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, source)?;
+    Ok((
+        Expression::Match(Box::new(MatchExpression {
+            subject,
+            where_clause,
+            cases,
+            default,
+            location: join_location(&start, &end),
+        })),
+        post_rest,
+    ))
+}
+
+// `case pattern, pattern ... to name only guard where ...: body`
+fn parse_match_case(source: &[Token]) -> Result<(MatchCase, &[Token]), Error> {
+    let start = source[0].location.clone();
+    let mut patterns: Vec<Pattern> = vec![];
+    let mut source = move_forword(source, 1); // 跳过 "case"
+
+    loop {
+        let (pattern, rest) = parse_pattern(source)?;
+        patterns.push(pattern);
+
+        if match_token(&TokenType::Comma, rest) {
+            source = skip_new_lines(move_forword(rest, 1));
+        } else {
+            source = rest;
+            break;
+        }
+    }
+
+    let (clauses, post_clauses) = parse_pattern_clauses(source)?;
+    let (body, post_body) = parse_case_body(post_clauses)?;
+    let location = join_location(&start, body.location());
+
+    Ok((
+        MatchCase {
+            patterns,
+            clauses,
+            body,
+            location,
+        },
+        post_body,
+    ))
+}
+
+// case 的冒号后面允许换行
+fn parse_case_body(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (_, rest) = consume_token(&TokenType::Colon, source)?;
+    parse_expression(skip_new_lines(rest))
+}
+
+// case 之间使用换行或者逗号分隔
+fn consume_case_separator(source: &[Token]) -> Result<&[Token], Error> {
+    if match_token(&TokenType::Comma, source) {
+        Ok(move_forword(source, 1))
+    } else {
+        consume_end_of_line(source, Some(&TokenType::RightBrace))
+    }
+}
+
+// `for let pattern = initial body` 或者 `for let pattern in iterable mix ... body`
+fn parse_for(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::For, source)?;
+    let (_, post_let) = consume_token(&TokenType::Let, rest)?;
+    let (pattern, post_pattern) = parse_pattern(post_let)?;
+
+    match post_pattern.first() {
+        Some(token) if token.token_type == TokenType::Assign => {
+            let (initial, post_initial) =
+                parse_expression(skip_new_lines(move_forword(post_pattern, 1)))?;
+            let (body, post_rest) = parse_expression(skip_new_lines(post_initial))?;
+            let location = join_location(&start, body.location());
+            Ok((
+                Expression::ForLet(Box::new(ForLetExpression {
+                    pattern,
+                    initial,
+                    body,
+                    location,
+                })),
+                post_rest,
+            ))
+        }
+        Some(token) if token.token_type == TokenType::In => {
+            let (bindings, post_bindings) =
+                parse_in_bindings(pattern, start.clone(), move_forword(post_pattern, 1))?;
+            let (body, post_rest) = parse_expression(skip_new_lines(post_bindings))?;
+            let location = join_location(&start, body.location());
+            Ok((
+                Expression::ForIn(Box::new(ForInExpression {
+                    bindings,
+                    body,
+                    location,
+                })),
+                post_rest,
+            ))
+        }
+        _ => Err(error_at("expected \"=\" or \"in\"", post_pattern)),
+    }
+}
+
+// `each let pattern in iterable mix ... body`
+fn parse_each(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Each, source)?;
+    let (_, post_let) = consume_token(&TokenType::Let, rest)?;
+    let (pattern, post_pattern) = parse_pattern(post_let)?;
+    let (_, post_in) = consume_token(&TokenType::In, post_pattern)?;
+
+    let (bindings, post_bindings) = parse_in_bindings(pattern, start.clone(), post_in)?;
+    let (body, post_rest) = parse_expression(skip_new_lines(post_bindings))?;
+    let location = join_location(&start, body.location());
+    Ok((
+        Expression::Each(Box::new(EachExpression {
+            bindings,
+            body,
+            location,
+        })),
+        post_rest,
+    ))
+}
+
+// 解析第一个 binding 的 iterable 以及后面由 mix 连接的其他 binding，
+// 参数 source 从第一个 in 关键字之后开始，mix 关键字后面允许换行
+fn parse_in_bindings(
+    pattern: Pattern,
+    start: Location,
+    source: &[Token],
+) -> Result<(Vec<InBinding>, &[Token]), Error> {
+    let (iterable, mut source) = parse_expression(skip_new_lines(source))?;
+    let location = join_location(&start, iterable.location());
+    let mut bindings = vec![InBinding {
+        pattern,
+        iterable,
+        location,
+    }];
+
+    while match_token(&TokenType::Mix, source) {
+        let post_mix = skip_new_lines(move_forword(source, 1));
+        let (let_start, post_let) = consume_token(&TokenType::Let, post_mix)?;
+        let (pattern, post_pattern) = parse_pattern(post_let)?;
+        let (_, post_in) = consume_token(&TokenType::In, post_pattern)?;
+        let (iterable, post_iterable) = parse_expression(skip_new_lines(post_in))?;
+        let location = join_location(&let_start, iterable.location());
+        bindings.push(InBinding {
+            pattern,
+            iterable,
+            location,
+        });
+        source = post_iterable;
+    }
+
+    Ok((bindings, source))
+}
+
+fn parse_next(source: &[Token]) -> Result<(Expression, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Next, source)?;
+    let (value, post_rest) = parse_expression(rest)?;
+    let location = join_location(&start, value.location());
+    Ok((
+        Expression::Next(Box::new(NextExpression { value, location })),
+        post_rest,
+    ))
+}
+
+// `to`、`only` 和 `where` 子表达式可以任意顺序出现，但每种最多出现一次
+fn parse_pattern_clauses(source: &[Token]) -> Result<(PatternClauses, &[Token]), Error> {
+    let mut clauses = PatternClauses::default();
+    let mut source = source;
+
+    while let Some((first, rest)) = source.split_first() {
+        source = match first.token_type {
+            TokenType::To => {
+                if clauses.to.is_some() {
+                    return Err(error_at("duplicate \"to\" clause", source));
+                }
+                let (name, post_rest) = parse_identifier(rest)?;
+                clauses.to = Some(name);
+                post_rest
+            }
+            TokenType::Only => {
+                if clauses.only.is_some() {
+                    return Err(error_at("duplicate \"only\" clause", source));
+                }
+                let (guard, post_rest) = parse_expression(skip_new_lines(rest))?;
+                clauses.only = Some(guard);
+                post_rest
+            }
+            TokenType::Where => {
+                if clauses.where_clause.is_some() {
+                    return Err(error_at("duplicate \"where\" clause", source));
+                }
+                let (expression, post_rest) = parse_expression(skip_new_lines(rest))?;
+                clauses.where_clause = Some(expression);
+                post_rest
+            }
+            _ => break,
+        };
+    }
+
+    Ok((clauses, source))
+}
+
+fn parse_pattern(source: &[Token]) -> Result<(Pattern, &[Token]), Error> {
+    let (first, rest) = match source.split_first() {
+        Some(item) => item,
+        None => return Err(error_at("unexpected end of file", source)),
+    };
+
+    match &first.token_type {
+        TokenType::Identifier(name) if name == "_" => {
+            Ok((Pattern::Wildcard(first.location.clone()), rest))
+        }
+        TokenType::Identifier(_)
+            if match_token(&TokenType::LeftBrace, rest)
+                || match_token(&TokenType::PathSeparator, rest) =>
+        {
+            let (name, post_name) = parse_path(source)?;
+            let (pattern, post_rest) = parse_struct_pattern(Some(name), post_name)?;
+            Ok((Pattern::Struct(pattern), post_rest))
+        }
+        TokenType::Identifier(_) => {
+            let (name, post_name) = parse_identifier(source)?;
+            Ok((Pattern::Identifier(name), post_name))
+        }
+        TokenType::Minus => match rest.split_first() {
+            Some((
+                Token {
+                    token_type: TokenType::Integer(value),
+                    location,
+                },
+                post_rest,
+            )) => Ok((
+                Pattern::Literal(Literal {
+                    value: LiteralValue::Integer(-value),
+                    location: join_location(&first.location, location),
+                }),
+                post_rest,
+            )),
+            _ => Err(error_at("expected integer", rest)),
+        },
+        TokenType::LeftParen => {
+            let (elements, post_elements) =
+                parse_comma_separated(rest, &TokenType::RightParen, parse_pattern)?;
+            let (end, post_rest) = consume_token(&TokenType::RightParen, post_elements)?;
+
+            if elements.items.len() == 1 && !elements.has_trailing_comma {
+                let mut items = elements.items;
+                return Ok((items.remove(0), post_rest));
+            }
+
+            Ok((
+                Pattern::Tuple(TuplePattern {
+                    elements: elements.items,
+                    location: join_location(&first.location, &end),
+                }),
+                post_rest,
+            ))
+        }
+        TokenType::LeftBracket => {
+            let (elements, post_elements) =
+                parse_comma_separated(rest, &TokenType::RightBracket, parse_pattern)?;
+            let (end, post_rest) = consume_token(&TokenType::RightBracket, post_elements)?;
+            Ok((
+                Pattern::List(ListPattern {
+                    elements: elements.items,
+                    location: join_location(&first.location, &end),
+                }),
+                post_rest,
+            ))
+        }
+        TokenType::Hash if match_token(&TokenType::LeftBracket, rest) => {
+            let (elements, post_elements) = parse_comma_separated(
+                move_forword(rest, 1),
+                &TokenType::RightBracket,
+                parse_pattern,
+            )?;
+            let (end, post_rest) = consume_token(&TokenType::RightBracket, post_elements)?;
+            Ok((
+                Pattern::Array(ListPattern {
+                    elements: elements.items,
+                    location: join_location(&first.location, &end),
+                }),
+                post_rest,
+            ))
+        }
+        TokenType::LeftBrace => {
+            let (pattern, post_rest) = parse_struct_pattern(None, source)?;
+            Ok((Pattern::Struct(pattern), post_rest))
+        }
+        TokenType::Ellipsis => {
+            let (pattern, post_rest) = parse_rest_pattern(source)?;
+            Ok((Pattern::Rest(pattern), post_rest))
+        }
+        TokenType::Into => {
+            let (data_type, post_data_type) = parse_data_type(rest)?;
+            let (name, post_rest) = parse_identifier(post_data_type)?;
+            let location = join_location(&first.location, &name.location);
+            Ok((
+                Pattern::Into(IntoPattern {
+                    data_type,
+                    name,
+                    location,
+                }),
+                post_rest,
+            ))
+        }
+        TokenType::Regular => {
+            // 正则表达式只能是字面量或者标识符，以避免跟后面的标识符列表混淆
+            let (regexp, post_regexp) = parse_primary_expression(rest)?;
+            let (_, post_left_bracket) = consume_token(&TokenType::LeftBracket, post_regexp)?;
+            let (names, post_names) = parse_comma_separated(
+                post_left_bracket,
+                &TokenType::RightBracket,
+                parse_identifier,
+            )?;
+            let (end, post_rest) = consume_token(&TokenType::RightBracket, post_names)?;
+            Ok((
+                Pattern::Regular(RegularPattern {
+                    regexp,
+                    names: names.items,
+                    location: join_location(&first.location, &end),
+                }),
+                post_rest,
+            ))
+        }
+        TokenType::Template => match rest.split_first() {
+            Some((
+                Token {
+                    token_type: TokenType::TemplateString(template),
+                    location,
+                },
+                post_rest,
+            )) => Ok((
+                Pattern::Template(TemplatePattern {
+                    template: template.clone(),
+                    location: join_location(&first.location, location),
+                }),
+                post_rest,
+            )),
+            _ => Err(error_at("expected template string", rest)),
+        },
+        TokenType::Within => {
+            let (range, post_rest) = parse_expression(rest)?;
+            let location = join_location(&first.location, range.location());
+            Ok((
+                Pattern::Within(WithinPattern { range, location }),
+                post_rest,
+            ))
+        }
+        _ => match to_literal(first) {
+            Some(literal) => Ok((Pattern::Literal(literal), rest)),
+            None => Err(error_at("unexpected token in pattern", source)),
+        },
+    }
+}
+
+// `Name {field, field: pattern, ...rest}` 或者 `Union::Member {...}`，参数 source 从左花括号开始
+fn parse_struct_pattern(
+    name: Option<Path>,
+    source: &[Token],
+) -> Result<(StructPattern, &[Token]), Error> {
+    let (left_brace, rest) = consume_token(&TokenType::LeftBrace, source)?;
+    let (members, post_members) =
+        parse_comma_separated(rest, &TokenType::RightBrace, parse_struct_pattern_member)?;
+    let (end, post_rest) = consume_token(&TokenType::RightBrace, post_members)?;
+
+    let mut fields: Vec<FieldPattern> = vec![];
+    let mut rest_pattern: Option<RestPattern> = None;
+
+    for member in members.items {
+        if rest_pattern.is_some() {
+            return Err(Error::ParserError(
+                "rest pattern must be the last member",
+                rest_pattern.map(|r| r.location),
+            ));
+        }
+
+        match member {
+            StructPatternMember::Field(field) => fields.push(*field),
+            StructPatternMember::Rest(pattern) => rest_pattern = Some(pattern),
+        }
+    }
+
+    let start = match &name {
+        Some(name) => name.location.clone(),
+        None => left_brace,
+    };
+
+    Ok((
+        StructPattern {
+            name,
+            fields,
+            rest: rest_pattern,
+            location: join_location(&start, &end),
+        },
+        post_rest,
+    ))
+}
+
+enum StructPatternMember {
+    Field(Box<FieldPattern>),
+    Rest(RestPattern),
+}
+
+fn parse_struct_pattern_member(source: &[Token]) -> Result<(StructPatternMember, &[Token]), Error> {
+    if match_token(&TokenType::Ellipsis, source) {
+        let (pattern, rest) = parse_rest_pattern(source)?;
+        return Ok((StructPatternMember::Rest(pattern), rest));
+    }
+
+    let (name, post_name) = parse_identifier(source)?;
+
+    let (pattern, is_shorthand, post_pattern) = match post_name.first() {
+        Some(token) if token.token_type == TokenType::Colon => {
+            let (pattern, rest) = parse_pattern(skip_new_lines(move_forword(post_name, 1)))?;
+            (Some(pattern), false, rest)
+        }
+        Some(token)
+            if matches!(
+                token.token_type,
+                TokenType::Into | TokenType::Regular | TokenType::Template | TokenType::Within
+            ) =>
+        {
+            let (pattern, rest) = parse_pattern(post_name)?;
+            (Some(pattern), true, rest)
+        }
+        _ => (None, true, post_name),
+    };
+
+    let (clauses, post_clauses) = parse_pattern_clauses(post_pattern)?;
+
+    // 成员的范围只计算到名称或者模式为止，子表达式的位置由各自的节点记录
+    let end = match &pattern {
+        Some(pattern) => pattern.location().clone(),
+        None => name.location.clone(),
+    };
+    let location = join_location(&name.location, &end);
+
+    Ok((
+        StructPatternMember::Field(Box::new(FieldPattern {
+            name,
+            pattern,
+            is_shorthand,
+            clauses,
+            location,
+        })),
+        post_clauses,
+    ))
+}
+
+fn parse_rest_pattern(source: &[Token]) -> Result<(RestPattern, &[Token]), Error> {
+    let (start, rest) = consume_token(&TokenType::Ellipsis, source)?;
+    match rest.first() {
+        Some(Token {
+            token_type: TokenType::Identifier(_),
+            ..
+        }) => {
+            let (name, post_rest) = parse_identifier(rest)?;
+            let location = join_location(&start, &name.location);
+            Ok((
+                RestPattern {
+                    name: Some(name),
+                    location,
+                },
+                post_rest,
+            ))
+        }
+        _ => Ok((
+            RestPattern {
+                name: None,
+                location: start,
+            },
+            rest,
+        )),
+    }
+}
+
+fn parse_data_type(source: &[Token]) -> Result<(DataType, &[Token]), Error> {
+    let (data_type, is_closing_pending, rest) = parse_nested_data_type(source)?;
+    if is_closing_pending {
+        return Err(error_at("unexpected \">\"", rest));
+    }
+    Ok((data_type, rest))
+}
+
+// 解析可能位于泛型参数列表之内的数据类型，
+// 返回值中的 bool 表示结尾的 `>>` 只消耗了一半（参见 GenericArguments）
+fn parse_nested_data_type(source: &[Token]) -> Result<(DataType, bool, &[Token]), Error> {
+    if match_identifier("sign", source) && match_token(&TokenType::LeftParen, &source[1..]) {
+        return parse_sign_data_type(source);
+    }
+
+    let (name, post_name) = parse_identifier(source)?;
+    if !match_token(&TokenType::LessThan, post_name) {
+        let location = name.location.clone();
+        return Ok((
+            DataType::Named(NamedDataType {
+                name,
+                generic_arguments: vec![],
+                location,
+            }),
+            false,
+            post_name,
+        ));
+    }
+
+    let (arguments, post_rest) = parse_generic_arguments(move_forword(post_name, 1))?;
+    let location = join_location(&name.location, &arguments.end);
+    Ok((
+        DataType::Named(NamedDataType {
+            name,
+            generic_arguments: arguments.items,
+            location,
+        }),
+        arguments.is_closing_pending,
+        post_rest,
+    ))
+}
+
+// 泛型参数列表 `<Type, Type>`。
+// 嵌套泛型结尾的 `>>`（比如 `List<List<Int>>`）会被词法分析为一个 Forward 记号，
+// 此时内层列表只消耗其中的第一个 `>`，is_closing_pending 为 true，
+// 并且剩余的记号仍然从 `>>` 开始，由外层列表消耗剩下的第二个 `>`
+struct GenericArguments {
+    items: Vec<DataType>,
+    end: Location,
+    is_closing_pending: bool,
+}
+
+// 参数 source 从左尖括号之后开始
+fn parse_generic_arguments(source: &[Token]) -> Result<(GenericArguments, &[Token]), Error> {
+    let mut items = vec![];
+    let mut source = skip_new_lines(source);
+
+    loop {
+        let (item, is_closing_pending, rest) = parse_nested_data_type(source)?;
+        items.push(item);
+
+        if is_closing_pending {
+            // 内层列表已经消耗了 `>>` 的第一个 `>`，第二个 `>` 正好是当前列表的结尾
+            let location = &rest[0].location;
+            let end = Location {
+                start: location.start + 1,
+                ..location.clone()
+            };
+            return Ok((
+                GenericArguments {
+                    items,
+                    end,
+                    is_closing_pending: false,
+                },
+                move_forword(rest, 1),
+            ));
+        }
+
+        match rest.first() {
+            Some(Token {
+                token_type: TokenType::Comma,
+                ..
+            }) => source = skip_new_lines(move_forword(rest, 1)),
+            Some(Token {
+                token_type: TokenType::GreaterThan,
+                location,
+            }) => {
+                return Ok((
+                    GenericArguments {
+                        items,
+                        end: location.clone(),
+                        is_closing_pending: false,
+                    },
+                    move_forword(rest, 1),
+                ))
+            }
+            Some(Token {
+                token_type: TokenType::Forward,
+                location,
+            }) => {
+                let end = Location {
+                    end: location.start + 1,
+                    ..location.clone()
+                };
+                return Ok((
+                    GenericArguments {
+                        items,
+                        end,
+                        is_closing_pending: true,
+                    },
+                    rest,
+                ));
+            }
+            _ => return Err(error_at("expected \">\"", rest)),
+        }
+    }
+}
+
+// 函数签名 `sign (Type name, Type) type Type`，
+// 返回值类型可能是泛型，所以同样需要返回 `>>` 是否只消耗了一半
+fn parse_sign_data_type(source: &[Token]) -> Result<(DataType, bool, &[Token]), Error> {
+    let start = source[0].location.clone();
+    let (_, post_left_paren) = consume_token(&TokenType::LeftParen, move_forword(source, 1))?;
+    let (parameters, post_parameters) = parse_comma_separated(
+        post_left_paren,
+        &TokenType::RightParen,
+        parse_sign_parameter,
+    )?;
+    let (end, post_right_paren) = consume_token(&TokenType::RightParen, post_parameters)?;
+
+    let (return_type, is_closing_pending, post_rest) = if match_identifier("type", post_right_paren)
+    {
+        let (data_type, is_closing_pending, rest) =
+            parse_nested_data_type(move_forword(post_right_paren, 1))?;
+        (Some(data_type), is_closing_pending, rest)
+    } else {
+        (None, false, post_right_paren)
+    };
+
+    let end = match &return_type {
+        Some(data_type) => data_type.location().clone(),
+        None => end,
+    };
+
+    Ok((
+        DataType::Sign(Box::new(SignDataType {
+            parameters: parameters.items,
+            return_type,
+            location: join_location(&start, &end),
+        })),
+        is_closing_pending,
+        post_rest,
+    ))
+}
+
+fn parse_sign_parameter(source: &[Token]) -> Result<(SignParameter, &[Token]), Error> {
+    let (data_type, post_data_type) = parse_data_type(source)?;
+    match post_data_type.first() {
+        Some(Token {
+            token_type: TokenType::Identifier(_),
+            ..
+        }) => {
+            let (name, rest) = parse_identifier(post_data_type)?;
+            Ok((
+                SignParameter {
+                    data_type,
+                    name: Some(name),
+                },
+                rest,
+            ))
+        }
+        _ => Ok((
+            SignParameter {
+                data_type,
+                name: None,
+            },
+            post_data_type,
+        )),
+    }
+}
+
+// 命名空间路径 `foo::bar::baz`
+fn parse_path(source: &[Token]) -> Result<(Path, &[Token]), Error> {
+    let (first, mut source) = parse_identifier(source)?;
+    let mut segments = vec![first];
+
+    while match_token(&TokenType::PathSeparator, source) {
+        let (segment, rest) = parse_identifier(move_forword(source, 1))?;
+        segments.push(segment);
+        source = rest;
+    }
+
+    let location = join_location(
+        &segments[0].location,
+        &segments[segments.len() - 1].location,
+    );
+    Ok((Path { segments, location }, source))
+}
+
+fn parse_identifier(source: &[Token]) -> Result<(Identifier, &[Token]), Error> {
+    match source.split_first() {
+        Some((
+            Token {
+                token_type: TokenType::Identifier(name),
+                location,
+            },
+            rest,
+        )) => Ok((
+            Identifier {
+                name: name.clone(),
+                location: location.clone(),
+            },
+            rest,
+        )),
+        _ => Err(error_at("expected identifier", source)),
+    }
+}
+
+// 检查语法树中只能出现在特定位置的表达式，比如 `next` 只能位于 for let 的 body 里面，
+// 同时收集错误恢复时产生的错误节点，所有错误均追加到 errors
+struct Checker {
+    is_in_for_let: bool,
+    errors: Vec<Error>,
+}
+
+impl Checker {
+    fn check_with<F: FnOnce(&mut Self)>(&mut self, is_in_for_let: bool, f: F) {
+        let saved = std::mem::replace(&mut self.is_in_for_let, is_in_for_let);
+        f(self);
+        self.is_in_for_let = saved;
+    }
+}
+
+impl Visitor for Checker {
+    // next 不能跨越函数的边界，所以参数和函数体总是位于 for let 之外
+    fn visit_function_declaration(&mut self, function: &FunctionDeclaration) {
+        self.check_with(false, |checker| {
+            walk_function_declaration(checker, function)
+        });
+    }
+
+    fn visit_anonymous_function(&mut self, function: &AnonymousFunction) {
+        self.check_with(false, |checker| walk_anonymous_function(checker, function));
+    }
+
+    fn visit_for_let_expression(&mut self, expression: &ForLetExpression) {
+        self.visit_pattern(&expression.pattern);
+        self.visit_expression(&expression.initial);
+        self.check_with(true, |checker| checker.visit_expression(&expression.body));
+    }
+
+    fn visit_next_expression(&mut self, expression: &NextExpression) {
+        if !self.is_in_for_let {
+            self.errors.push(Error::ParserError(
+                "\"next\" must be inside the body of \"for let\"",
+                Some(expression.location.clone()),
+            ));
+        }
+        walk_next_expression(self, expression);
+    }
+
+    fn visit_error_node(&mut self, node: &ErrorNode) {
+        self.errors.push(node.to_error());
+    }
+}
+
+// 恐慌模式（panic mode）的错误恢复，从出错的记号开始丢弃记号，直到遇到以下同步点：
+// - 不在括号之内的换行符；
+// - 不在括号之内的右花括号，在块之内（is_in_block）时它是当前块的结尾，不消耗，
+//   在顶层时它是多余的，直接丢弃；
+// - 不在括号之内的语句关键字，比如 function、struct，但不能是当前语句的第一个记号。
+// 参数 source 从出错的语句（或者块里的表达式）的第一个记号开始，
+// 返回错误节点以及从同步点开始的剩余记号
+fn recover(error: Error, source: &[Token], is_in_block: bool) -> (ErrorNode, &[Token]) {
+    let (message, location) = match error {
+        Error::ParserError(message, location) => (message, location),
+        Error::LexerError(message, location) => (message, Some(location)),
+        Error::RuntimeError(..) | Error::LimitError(..) => unreachable!(),
+    };
+
+    let (position, location) = match location {
+        Some(location) => (
+            source
+                .iter()
+                .position(|token| token.location.start >= location.start)
+                .unwrap_or(source.len()),
+            location,
+        ),
+        None => (source.len(), source[source.len() - 1].location.clone()),
+    };
+
+    let node = ErrorNode { message, location };
+    (node, synchronize(source, position, is_in_block))
+}
+
+fn synchronize(source: &[Token], position: usize, is_in_block: bool) -> &[Token] {
+    // 出错之后打开的括号，括号不匹配的右括号会被忽略
+    let mut closings: Vec<TokenType> = vec![];
+
+    for (index, token) in source.iter().enumerate().skip(position) {
+        match token.token_type {
+            TokenType::LeftParen => closings.push(TokenType::RightParen),
+            TokenType::LeftBracket => closings.push(TokenType::RightBracket),
+            TokenType::LeftBrace => closings.push(TokenType::RightBrace),
+            TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace
+                if closings.last() == Some(&token.token_type) =>
+            {
+                closings.pop();
+            }
+            TokenType::RightBrace if is_in_block && closings.is_empty() => return &source[index..],
+            TokenType::NewLine if closings.is_empty() => return &source[index..],
+            TokenType::Function
+            | TokenType::Struct
+            | TokenType::Union
+            | TokenType::Enum
+            | TokenType::Trait
+            | TokenType::Impl
+            | TokenType::Const
+            | TokenType::Namespace
+            | TokenType::Use
+            | TokenType::Alias
+                if closings.is_empty() && index > 0 =>
+            {
+                return &source[index..]
+            }
+            _ => {}
+        }
+    }
+
+    &source[source.len()..]
+}
+
+type ParseFn<T> = fn(&[Token]) -> Result<(T, &[Token]), Error>;
+
+struct CommaSeparated<T> {
+    items: Vec<T>,
+    has_trailing_comma: bool,
+}
+
+// 解析逗号分隔的列表直到遇到 closing（不消耗 closing），
+// 列表位于括号之内，所以元素之间允许换行
+fn parse_comma_separated<'a, T>(
+    source: &'a [Token],
+    closing: &TokenType,
+    parse_item: ParseFn<T>,
+) -> Result<(CommaSeparated<T>, &'a [Token]), Error> {
+    let mut items: Vec<T> = vec![];
+    let mut has_trailing_comma = false;
+    let mut source = skip_new_lines(source);
+
+    while !match_token(closing, source) {
+        let (item, rest) = parse_item(source)?;
+        items.push(item);
+
+        let post_new_lines = skip_new_lines(rest);
+        if match_token(&TokenType::Comma, post_new_lines) {
+            has_trailing_comma = true;
+            source = skip_new_lines(move_forword(post_new_lines, 1));
+        } else {
+            has_trailing_comma = false;
+            source = post_new_lines;
+            break;
+        }
+    }
+
+    Ok((
+        CommaSeparated {
+            items,
+            has_trailing_comma,
+        },
+        source,
+    ))
+}
+
+// 表达式之后必须是换行符、文件结束或者 closing（不消耗 closing）
+fn consume_end_of_line<'a>(
+    source: &'a [Token],
+    closing: Option<&TokenType>,
+) -> Result<&'a [Token], Error> {
+    match source.first() {
+        None => Ok(source),
+        Some(token) if token.token_type == TokenType::NewLine => Ok(move_forword(source, 1)),
+        Some(token) if Some(&token.token_type) == closing => Ok(source),
+        _ => Err(error_at("expected new line", source)),
+    }
+}
+
+fn consume_token<'a>(
+    expected: &TokenType,
+    source: &'a [Token],
+) -> Result<(Location, &'a [Token]), Error> {
+    match source.split_first() {
+        Some((first, rest)) if first.token_type == *expected => Ok((first.location.clone(), rest)),
+        Some(_) => Err(error_at("unexpected token", source)),
+        None => Err(error_at("unexpected end of file", source)),
+    }
+}
+
+// 使用 source 第一个记号的位置构造语法错误，source 为空表示已到达文件末尾
+fn error_at(message: &'static str, source: &[Token]) -> Error {
+    Error::ParserError(message, source.first().map(|token| token.location.clone()))
+}
+
+fn match_token(expected: &TokenType, source: &[Token]) -> bool {
+    match source.first() {
+        Some(first) => first.token_type == *expected,
+        None => false,
+    }
+}
+
+// 检查 source 的第一个 token 是否紧跟在 previous 之后（中间没有空白）
+fn match_adjacent_token(expected: &TokenType, previous: &Location, source: &[Token]) -> bool {
+    match source.first() {
+        Some(first) => first.token_type == *expected && first.location.start == previous.end,
+        None => false,
+    }
+}
+
+// 用于检测上下文关键字，比如 "case"、"default"
+fn match_identifier(expected: &str, source: &[Token]) -> bool {
+    match source.first() {
+        Some(Token {
+            token_type: TokenType::Identifier(name),
+            ..
+        }) => name == expected,
+        _ => false,
+    }
+}
+
+fn skip_new_lines(source: &[Token]) -> &[Token] {
+    match source
+        .iter()
+        .position(|t| t.token_type != TokenType::NewLine)
+    {
+        Some(index) => &source[index..],
+        None => &source[source.len()..],
+    }
+}
+
+fn move_forword(source: &[Token], count: usize) -> &[Token] {
+    &source[count..]
+}
+
+fn join_location(start: &Location, end: &Location) -> Location {
+    Location {
+        file_id: start.file_id,
+        start: start.start,
+        end: end.end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::lexer::tokenize;
+
+    use super::{is_incomplete, parse, parse_with_recovery};
+
+    fn parse_from_str(text: &str) -> String {
+        let tokens = tokenize(text).unwrap();
+        parse(&tokens).unwrap().to_string()
+    }
+
+    fn parse_error(text: &str) -> bool {
+        let tokens = tokenize(text).unwrap();
+        parse(&tokens).is_err()
+    }
+
+    // 返回恢复之后的程序文本以及各个错误的开始位置
+    fn parse_with_errors(text: &str) -> (String, Vec<usize>) {
+        let tokens = tokenize(text).unwrap();
+        let (program, errors) = parse_with_recovery(&tokens);
+        let starts = errors
+            .iter()
+            .map(|error| match error {
+                Error::ParserError(_, location) => location.as_ref().unwrap().start,
+                _ => unreachable!(),
+            })
+            .collect();
+        (program.to_string(), starts)
+    }
+
+    #[test]
+    fn test_binary_expression() {
+        assert_eq!(parse_from_str("1+2*3-4"), "((1 + (2 * 3)) - 4)");
+        assert_eq!(parse_from_str("a || b && c == d"), "(a || (b && (c == d)))");
+        assert_eq!(parse_from_str("a ++ b + c ?? d"), "(a ++ (b + (c ?? d)))");
+        assert_eq!(parse_from_str("-a * -2"), "((-a) * (-2))");
+        assert_eq!(parse_from_str("a >> b | c"), "(a >> (b | c))");
+    }
+
+    #[test]
+    fn test_new_line_continuation() {
+        assert_eq!(parse_from_str("1 +\n2"), "(1 + 2)");
+        assert_eq!(parse_from_str("a\n|| b"), "(a || b)");
+        assert_eq!(parse_from_str("a\n-1"), "a\n(-1)");
+        assert_eq!(parse_from_str("f(\na,\nb\n)"), "f(a, b)");
+        assert!(parse_error("1 2"));
+    }
+
+    #[test]
+    fn test_tuple_list_and_block() {
+        assert_eq!(parse_from_str("(a)"), "a");
+        assert_eq!(parse_from_str("(a,)"), "(a,)");
+        assert_eq!(parse_from_str("(a, b)"), "(a, b)");
+        assert_eq!(parse_from_str("()"), "()");
+        assert_eq!(parse_from_str("[1, 2, 3]"), "[1, 2, 3]");
+        assert_eq!(parse_from_str("{\na\n\nb\n}"), "{a; b}");
+    }
+
+    #[test]
+    fn test_let_expression() {
+        assert_eq!(parse_from_str("let a = 1"), "let a = 1");
+        assert_eq!(parse_from_str("let (a, b, c) = t"), "let (a, b, c) = t");
+        assert_eq!(
+            parse_from_str("let [a, b, ...rest] = l"),
+            "let [a, b, ...rest] = l"
+        );
+        assert_eq!(
+            parse_from_str("let User{id: user_id, name} = u"),
+            "let User{id: user_id, name} = u"
+        );
+        assert_eq!(
+            parse_from_str("let {a, b, ...rest} = m"),
+            "let {a, b, ...rest} = m"
+        );
+        assert_eq!(
+            parse_from_str("let User{id, name} match user001"),
+            "let User{id, name} match user001"
+        );
+        assert_eq!(
+            parse_from_str("let Json::String{value} = j"),
+            "let Json::String{value} = j"
+        );
+        assert_eq!(
+            parse_from_str("match o {\n case Option::Some {value}: value\n default: 0\n}"),
+            "match o {case Option::Some{value}: value; default: 0}"
+        );
+        assert!(parse_error("let Json:: = j"));
+    }
+
+    #[test]
+    fn test_if_expression() {
+        assert_eq!(
+            parse_from_str("if a > 1 then b else c"),
+            "if (a > 1) then b else c"
+        );
+        assert_eq!(
+            parse_from_str("if a > 1 where let a = 2 then b"),
+            "if (a > 1) where let a = 2 then b"
+        );
+        assert_eq!(
+            parse_from_str("if a > b where {\n let a = 2\n let b = 1 } then\n a\nelse\n b"),
+            "if (a > b) where {let a = 2; let b = 1} then a else b"
+        );
+        assert_eq!(
+            parse_from_str("if {let a = c * 2; a > b} then 1 else 2"),
+            "if {let a = (c * 2); (a > b)} then 1 else 2"
+        );
+        assert_eq!(
+            parse_from_str("if (a == 0) {\n b\n} else {\n c\n}"),
+            "if (a == 0) then {b} else {c}"
+        );
+        assert_eq!(
+            parse_from_str("if 1 < Max {1} else {2}"),
+            "if (1 < Max) then {1} else {2}"
+        );
+        assert_eq!(parse_from_str("if Cond {1}"), "if Cond then {1}");
+        assert_eq!(
+            parse_from_str("if p == (Point {x: 0}) {1}"),
+            "if (p == Point {x: 0}) then {1}"
+        );
+        assert!(parse_error("if a\n{b}"));
+
+        // 条件表达式可以在运算符前后换行
+        assert_eq!(parse_from_str("if a &&\n b then 1"), "if (a && b) then 1");
+        assert_eq!(
+            parse_from_str("if a\n || b < Max {1}"),
+            "if (a || (b < Max)) then {1}"
+        );
+        assert_eq!(
+            parse_from_str("if a then P {x: 1} else b"),
+            "if a then P {x: 1} else b"
+        );
+    }
+
+    #[test]
+    fn test_branch_expression() {
+        assert_eq!(
+            parse_from_str("branch {\n case b==0: 1\n case b>a: {2}\n default: 3\n}"),
+            "branch {case (b == 0): 1; case (b > a): {2}; default: 3}"
+        );
+        assert_eq!(
+            parse_from_str("branch where let a = 2 {\n case b>a where let b = 1: b\n}"),
+            "branch where let a = 2 {case (b > a) where let b = 1: b}"
+        );
+        assert!(parse_error("branch {\n default: 1\n case a: 2\n}"));
+    }
+
+    #[test]
+    fn test_for_let_expression() {
+        assert_eq!(parse_from_str("for let a = 1 {a}"), "for let a = 1 {a}");
+        assert_eq!(
+            parse_from_str("for let (a, b) = (0, 1) {\n a + b\n}"),
+            "for let (a, b) = (0, 1) {(a + b)}"
+        );
+        assert_eq!(
+            parse_from_str("for let i = 0 if i < 10 then {\n puts(i)\n next i+1\n}"),
+            "for let i = 0 if (i < 10) then {puts(i); next (i + 1)}"
+        );
+        assert_eq!(
+            parse_from_str("for let (a, b) = (0, 1) if a < 10 then next (b, a + b) else a"),
+            "for let (a, b) = (0, 1) if (a < 10) then next (b, (a + b)) else a"
+        );
+    }
+
+    #[test]
+    fn test_next_outside_for_let() {
+        assert!(parse_error("next 1"));
+        assert!(parse_error("{\n next 1\n}"));
+        assert!(parse_error("for let i in [1, 2] {next i}"));
+        assert!(parse_error("each let i in [1, 2] {next i}"));
+        assert!(parse_error("for let i = next 1 {i}"));
+    }
+
+    #[test]
+    fn test_for_in_expression() {
+        assert_eq!(
+            parse_from_str("for let i in [1,2,3] {puts(i)}"),
+            "for let i in [1, 2, 3] {puts(i)}"
+        );
+        assert_eq!(
+            parse_from_str("for let i in [1,2,3] mix\n let j in [4,5,6] {\n i + j\n}"),
+            "for let i in [1, 2, 3] mix let j in [4, 5, 6] {(i + j)}"
+        );
+        assert_eq!(
+            parse_from_str("for let (k, v) in pairs {k}"),
+            "for let (k, v) in pairs {k}"
+        );
+    }
+
+    #[test]
+    fn test_each_expression() {
+        assert_eq!(
+            parse_from_str("each let i in [1,2,3] {i * 2}"),
+            "each let i in [1, 2, 3] {(i * 2)}"
+        );
+        assert_eq!(
+            parse_from_str("let a =\n each let i in [1,2] mix\n let j in [4,5,6] (i,j)"),
+            "let a = each let i in [1, 2] mix let j in [4, 5, 6] (i, j)"
+        );
+        assert_eq!(
+            parse_from_str("each let (a, b) in xs mix let c in ys mix let d in zs (a, b, c, d)"),
+            "each let (a, b) in xs mix let c in ys mix let d in zs (a, b, c, d)"
+        );
+        assert!(parse_error("each let i = 1 {i}"));
+    }
+
+    #[test]
+    fn test_function_declaration() {
+        assert_eq!(
+            parse_from_str("function add (Int a, Int b=1) type Int {\n a + b\n}"),
+            "function add (Int a, Int b = 1) type Int {(a + b)}"
+        );
+        assert_eq!(
+            parse_from_str("function inc (a) type Int = a + 1"),
+            "function inc (a) type Int = (a + 1)"
+        );
+        assert_eq!(
+            parse_from_str("function zero () = 0"),
+            "function zero () = 0"
+        );
+        assert_eq!(
+            parse_from_str("function name (\n String first,\n String last = \"\"\n)\n type String\n {\n first ++ last\n}"),
+            "function name (String first, String last = \"\") type String {(first ++ last)}"
+        );
+        assert!(parse_error("function (a) = 1"));
+        assert!(parse_error("function f (a)"));
+        assert!(parse_error("function f (a = 1, b) {next a}"));
+    }
+
+    #[test]
+    fn test_function_which() {
+        assert_eq!(
+            parse_from_str(
+                "function name (F f) type Int\n which {\n F: sign (Int x) type Int\n } {\n f(1)\n}"
+            ),
+            "function name (F f) type Int which {F: sign (Int x) type Int} {f(1)}"
+        );
+        assert_eq!(
+            parse_from_str("function name (T t, F f) type T\n which {\n T: String,\n F: sign (Int, Int) type Int\n } {\n t\n}"),
+            "function name (T t, F f) type T which {T: String, F: sign (Int, Int) type Int} {t}"
+        );
+        assert_eq!(
+            parse_from_str(
+                "function max (T left, T right) type T which {\n T: limit Ordered\n } {\n left\n}"
+            ),
+            "function max (T left, T right) type T which {T: limit Ordered} {left}"
+        );
+        assert_eq!(
+            parse_from_str(
+                "function show (T t, U u) which {T: limit Display, Ordered, U: limit Display} = t"
+            ),
+            "function show (T t, U u) which {T: limit Display, Ordered, U: limit Display} = t"
+        );
+        assert_eq!(
+            parse_from_str("function apply (sign (Int) f) = f(1)"),
+            "function apply (sign (Int) f) = f(1)"
+        );
+    }
+
+    #[test]
+    fn test_pattern_function() {
+        assert_eq!(
+            parse_from_str(
+                "pattern function test (into Email email, into Phone phone) {\n email\n}"
+            ),
+            "pattern function test (into Email email, into Phone phone) {email}"
+        );
+        assert_eq!(
+            parse_from_str("pattern function first (Int (a, _), [b, ...]) = a + b"),
+            "pattern function first (Int (a, _), [b, ...]) = (a + b)"
+        );
+        assert_eq!(parse_from_str("pattern(1)"), "pattern(1)");
+        assert!(parse_error("function first ((a, b)) = a"));
+    }
+
+    #[test]
+    fn test_anonymous_function() {
+        assert_eq!(
+            parse_from_str("(Int x) type Int => x + 1"),
+            "(Int x) type Int => (x + 1)"
+        );
+        assert_eq!(parse_from_str("(x) => x * 2"), "(x) => (x * 2)");
+        assert_eq!(parse_from_str("x => x * 2"), "(x) => (x * 2)");
+        assert_eq!(parse_from_str("(a, b) => a + b"), "(a, b) => (a + b)");
+        assert_eq!(parse_from_str("() => 1"), "() => 1");
+        assert_eq!(
+            parse_from_str("(Int a, Int b = 1) => {\n a + b\n}"),
+            "(Int a, Int b = 1) => {(a + b)}"
+        );
+        assert_eq!(parse_from_str("let f = (x) =>\n x"), "let f = (x) => x");
+        assert_eq!(parse_from_str("x => y => x + y"), "(x) => (y) => (x + y)");
+        assert_eq!(
+            parse_from_str("fn(a, Int b) type Int {\n a + b\n}"),
+            "(a, Int b) type Int => {(a + b)}"
+        );
+        assert_eq!(parse_from_str("fn() {1}"), "() => {1}");
+        assert_eq!(parse_from_str("fn(a)"), "fn(a)");
+    }
+
+    #[test]
+    fn test_anonymous_function_and_tuple() {
+        assert_eq!(parse_from_str("(x)"), "x");
+        assert_eq!(parse_from_str("(x, y)"), "(x, y)");
+        assert_eq!(parse_from_str("(x + 1, y)"), "((x + 1), y)");
+        assert!(parse_error("(Int x)"));
+        assert!(parse_error("(x) => "));
+        assert!(parse_error("(x) => next x"));
+    }
+
+    #[test]
+    fn test_anonymous_function_argument() {
+        assert_eq!(
+            parse_from_str("filter(map(users, (x) => x*2), x => x>3)"),
+            "filter(map(users, (x) => (x * 2)), (x) => (x > 3))"
+        );
+        assert_eq!(
+            parse_from_str("fold(\n list,\n 0,\n (acc, e) => acc + e\n)"),
+            "fold(list, 0, (acc, e) => (acc + e))"
+        );
+    }
+
+    #[test]
+    fn test_struct_declaration() {
+        assert_eq!(
+            parse_from_str("struct User {\n Int id,\n String name\n Boolean checked\n}"),
+            "struct User {Int id, String name, Boolean checked}"
+        );
+        assert_eq!(parse_from_str("struct Empty {}"), "struct Empty {}");
+        assert!(parse_error("struct User {Int}"));
+    }
+
+    #[test]
+    fn test_union_declaration() {
+        assert_eq!(
+            parse_from_str("union Name { A {Int x}, B }"),
+            "union Name {A {Int x}, B}"
+        );
+        assert_eq!(
+            parse_from_str(
+                "union Shape {\n Circle {Int radius}\n Rect {Int width, Int height}\n Nothing\n}"
+            ),
+            "union Shape {Circle {Int radius}, Rect {Int width, Int height}, Nothing}"
+        );
+    }
+
+    #[test]
+    fn test_enum_declaration() {
+        assert_eq!(
+            parse_from_str("enum Color { Red Green Blue }"),
+            "enum Color {Red, Green, Blue}"
+        );
+        assert_eq!(
+            parse_from_str("enum Color {\n Red\n Green,\n Blue\n}"),
+            "enum Color {Red, Green, Blue}"
+        );
+    }
+
+    #[test]
+    fn test_const_declaration() {
+        assert_eq!(
+            parse_from_str("const Int Code = 123"),
+            "const Int Code = 123"
+        );
+        assert_eq!(
+            parse_from_str("const Int Code {\n Ok = 123\n Moved = 301\n}"),
+            "const Int Code {Ok = 123, Moved = 301}"
+        );
+        assert!(parse_error("const Code = 123"));
+        assert!(parse_error("const Int Code {Ok}"));
+    }
+
+    #[test]
+    fn test_struct_expression() {
+        assert_eq!(
+            parse_from_str("User {\"value\", \"value\"}"),
+            "User {\"value\", \"value\"}"
+        );
+        assert_eq!(
+            parse_from_str("User {name: \"foo\", id: 1 + 2}"),
+            "User {name: \"foo\", id: (1 + 2)}"
+        );
+        assert_eq!(
+            parse_from_str("User {\n id: 1,\n name: \"foo\"\n}"),
+            "User {id: 1, name: \"foo\"}"
+        );
+        assert_eq!(parse_from_str("Empty {}"), "Empty {}");
+        assert!(parse_error("User {id: 1, \"foo\"}"));
+    }
+
+    #[test]
+    fn test_struct_expression_without_name() {
+        assert_eq!(
+            parse_from_str("let users = [{1, \"foo\", true}, {2, \"bar\", false}]"),
+            "let users = [{1, \"foo\", true}, {2, \"bar\", false}]"
+        );
+        assert_eq!(parse_from_str("{1,}"), "{1,}");
+        assert_eq!(parse_from_str("{1}"), "{1}");
+        assert_eq!(parse_from_str("{}"), "{}");
+        assert_eq!(parse_from_str("{a\n b}"), "{a; b}");
+    }
+
+    #[test]
+    fn test_struct_expression_and_block() {
+        assert_eq!(
+            parse_from_str("match v {\n case 1: a\n}"),
+            "match v {case 1: a}"
+        );
+        assert_eq!(
+            parse_from_str("for let i in items {i}"),
+            "for let i in items {i}"
+        );
+        assert_eq!(
+            parse_from_str("if a then Point {1, 2} else Point {x: 0, y: 0}"),
+            "if a then Point {1, 2} else Point {x: 0, y: 0}"
+        );
+    }
+
+    #[test]
+    fn test_trait_declaration() {
+        assert_eq!(
+            parse_from_str("trait Display {\n empty function show (Self s) type String\n}"),
+            "trait Display {empty function show (Self s) type String}"
+        );
+        assert_eq!(
+            parse_from_str("trait Sequence type ItemType {\n empty function first (Self s) type ItemType\n function isEmpty (Self s) type Boolean = len(s) == 0\n}"),
+            "trait Sequence type ItemType {empty function first (Self s) type ItemType; function isEmpty (Self s) type Boolean = (len(s) == 0)}"
+        );
+        assert_eq!(
+            parse_from_str(
+                "trait Map type (Key, Value) {\n empty function get (Self s, Key k) type Value\n}"
+            ),
+            "trait Map type (Key, Value) {empty function get (Self s, Key k) type Value}"
+        );
+        assert_eq!(
+            parse_from_str("trait Convertable type ItemType=String {}"),
+            "trait Convertable type ItemType = String {}"
+        );
+        assert_eq!(
+            parse_from_str("trait Convertable\n type (From, To=String) {\n}"),
+            "trait Convertable type (From, To = String) {}"
+        );
+        assert!(parse_error("trait Name {\n let a = 1\n}"));
+        assert!(parse_error("empty function show (Self s) type String"));
+    }
+
+    #[test]
+    fn test_impl_declaration() {
+        assert_eq!(
+            parse_from_str("impl User {\n function name (Self s) type String = s\n}"),
+            "impl User {function name (Self s) type String = s}"
+        );
+        assert_eq!(
+            parse_from_str("impl User trait Display {\n function show (Self s) type String {\n \"user\"\n }\n}"),
+            "impl User trait Display {function show (Self s) type String {\"user\"}}"
+        );
+        assert_eq!(
+            parse_from_str("impl Names\n trait Sequence\n type ItemType = String {\n function first (Self s) type String = head(s)\n}"),
+            "impl Names trait Sequence type ItemType = String {function first (Self s) type String = head(s)}"
+        );
+        assert_eq!(
+            parse_from_str("impl Dict trait Map type (Key = String, Value = Int) {}"),
+            "impl Dict trait Map type (Key = String, Value = Int) {}"
+        );
+        assert!(parse_error("impl Dict trait Map type Key {}"));
+        assert!(parse_error(
+            "impl User {\n empty function name (Self s) type String\n}"
+        ));
+    }
+
+    #[test]
+    fn test_generic_function() {
+        assert_eq!(
+            parse_from_str("function max<T> (T left, T right) type T which {\n T: limit Ordered\n} {\n left\n}"),
+            "function max<T> (T left, T right) type T which {T: limit Ordered} {left}"
+        );
+        assert_eq!(
+            parse_from_str("function map<T, U>(List<T> xs, sign (T) type U f) type List<U> = xs"),
+            "function map<T, U> (List<T> xs, sign (T) type U f) type List<U> = xs"
+        );
+        assert!(parse_error("function max<> (T left, T right) = left"));
+        assert!(parse_error("function max<T (T left, T right) = left"));
+    }
+
+    #[test]
+    fn test_generic_data_type() {
+        assert_eq!(
+            parse_from_str("function f(List<User> users, Map<String, Int> counts) = users"),
+            "function f (List<User> users, Map<String, Int> counts) = users"
+        );
+        // 结尾的 `>>` 和 `>>` + `>` 需要拆分
+        assert_eq!(
+            parse_from_str("function f(List<List<Int>> xs) type List<List<List<Int>>> = xs"),
+            "function f (List<List<Int>> xs) type List<List<List<Int>>> = xs"
+        );
+        assert_eq!(
+            parse_from_str("function f(List<List<List<List<Int>>>> xs) = xs"),
+            "function f (List<List<List<List<Int>>>> xs) = xs"
+        );
+        assert_eq!(
+            parse_from_str("function f(List<sign (Int) type List<Int>> fs) = fs"),
+            "function f (List<sign (Int) type List<Int>> fs) = fs"
+        );
+        assert!(parse_error("function f(List<Int xs) = xs"));
+        assert!(parse_error("function f(List<Int>> xs) = xs"));
+        assert!(parse_error("function f(List<List<Int>>> xs) = xs"));
+    }
+
+    #[test]
+    fn test_generic_call_and_comparison() {
+        assert_eq!(
+            parse_from_str("let a = name<Int>(a, b)"),
+            "let a = name<Int>(a, b)"
+        );
+        assert_eq!(
+            parse_from_str("name<List<List<Int>>, String>(x)"),
+            "name<List<List<Int>>, String>(x)"
+        );
+        assert_eq!(
+            parse_from_str("name<List<Int>>(x)(y)"),
+            "name<List<Int>>(x)(y)"
+        );
+
+        // 不满足泛型参数条件的尖括号均视为比较运算
+        assert_eq!(parse_from_str("a < b > c"), "((a < b) > c)");
+        assert_eq!(parse_from_str("a<b>c"), "((a < b) > c)");
+        assert_eq!(parse_from_str("a < b > (c)"), "((a < b) > c)");
+        assert_eq!(parse_from_str("a<b> (c)"), "((a < b) > c)");
+        assert_eq!(parse_from_str("a<b>>c"), "((a < b) >> c)");
+        assert_eq!(parse_from_str("a<1>(c)"), "((a < 1) > c)");
+        assert_eq!(parse_from_str("f(a<b, c>d)"), "f((a < b), (c > d))");
+        assert_eq!(parse_from_str("(a<b)>(c)"), "((a < b) > c)");
+    }
+
+    #[test]
+    fn test_namespace_declaration() {
+        assert_eq!(
+            parse_from_str("namespace tests {\n function f() = 1\n\n namespace inner {\n const Int a = 1\n }\n}"),
+            "namespace tests {function f () = 1; namespace inner {const Int a = 1}}"
+        );
+        assert_eq!(
+            parse_from_str("namespace tests\n{\n}"),
+            "namespace tests {}"
+        );
+        assert!(parse_error(
+            "namespace tests { function f() = 1 function g() = 2 }"
+        ));
+        assert!(parse_error("namespace { }"));
+    }
+
+    #[test]
+    fn test_use_declaration() {
+        assert_eq!(parse_from_str("use foo::bar"), "use foo::bar");
+        assert_eq!(parse_from_str("use foo"), "use foo");
+        assert_eq!(parse_from_str("use foo::bar::*"), "use foo::bar::*");
+        assert_eq!(parse_from_str("use foo::{bar, baz}"), "use foo::{bar, baz}");
+        assert_eq!(
+            parse_from_str("use foo::{\n bar::*,\n baz::{a, b},\n}"),
+            "use foo::{bar::*, baz::{a, b}}"
+        );
+        assert!(parse_error("use *"));
+        assert!(parse_error("use {foo, bar}"));
+        assert!(parse_error("use foo::"));
+        assert!(parse_error("use foo::{}"));
+        assert!(parse_error("use foo::*::bar"));
+    }
+
+    #[test]
+    fn test_alias_declaration() {
+        assert_eq!(parse_from_str("alias Id = Int"), "alias Id = Int");
+        assert_eq!(
+            parse_from_str("alias Users = List<User>"),
+            "alias Users = List<User>"
+        );
+        assert!(parse_error("alias Id Int"));
+    }
+
+    #[test]
+    fn test_path_expression() {
+        assert_eq!(parse_from_str("foo::bar"), "foo::bar");
+        assert_eq!(
+            parse_from_str("User::new(\"value\", 1)"),
+            "User::new(\"value\", 1)"
+        );
+        assert_eq!(parse_from_str("foo::max<Int>(a, b)"), "foo::max<Int>(a, b)");
+        assert!(parse_error("foo::"));
+        assert!(parse_error("foo::1"));
+    }
+
+    #[test]
+    fn test_module_statement_in_expression() {
+        assert!(parse_error("let a = use foo::bar"));
+        assert!(parse_error("function f() {\n alias Id = Int\n}"));
+        assert!(parse_error("if a then {\n namespace tests {}\n}"));
+        assert!(parse_error("1 + use foo"));
+    }
+
+    #[test]
+    fn test_member_and_tuple_index() {
+        assert_eq!(parse_from_str("user.name"), "user.name");
+        assert_eq!(parse_from_str("user.name.len()"), "user.name.len()");
+        assert_eq!(parse_from_str("(a,b,c).0"), "(a, b, c).0");
+        assert_eq!(parse_from_str("(a,b,c).2"), "(a, b, c).2");
+        // 词法分析器没有浮点数，`.0.1` 是两层元组元素
+        assert_eq!(parse_from_str("t.0.1"), "t.0.1");
+        assert_eq!(parse_from_str("t.0.name"), "t.0.name");
+        assert_eq!(parse_from_str("-a.b * c.d"), "((-a.b) * c.d)");
+        assert!(parse_error("a."));
+        assert!(parse_error("a.(b)"));
+    }
+
+    #[test]
+    fn test_method_chain() {
+        assert_eq!(
+            parse_from_str("users.map(x => x*2).filter(x => x>3)"),
+            "users.map((x) => (x * 2)).filter((x) => (x > 3))"
+        );
+        assert_eq!(
+            parse_from_str("users\n    .map((x) => x*2)\n    .filter(x => x>3)"),
+            "users.map((x) => (x * 2)).filter((x) => (x > 3))"
+        );
+        assert_eq!(parse_from_str("f(x)[0].name"), "f(x)[0].name");
+    }
+
+    #[test]
+    fn test_index_and_slice() {
+        assert_eq!(parse_from_str("x[0]"), "x[0]");
+        assert_eq!(parse_from_str("x[i + 1]"), "x[(i + 1)]");
+        assert_eq!(parse_from_str("m[i][j]"), "m[i][j]");
+        assert_eq!(parse_from_str("x[1:3]"), "x[1:3]");
+        assert_eq!(parse_from_str("x[:3]"), "x[:3]");
+        assert_eq!(parse_from_str("x[1:]"), "x[1:]");
+        assert_eq!(parse_from_str("x[:]"), "x[:]");
+        assert_eq!(parse_from_str("x[a-1:b+1]"), "x[(a - 1):(b + 1)]");
+        // 左方括号不紧跟时不是索引
+        assert_eq!(
+            parse_from_str("each let i in xs [i]"),
+            "each let i in xs [i]"
+        );
+        assert!(parse_error("x[]"));
+        assert!(parse_error("x[1:2:3]"));
+    }
+
+    #[test]
+    fn test_postfix_operator() {
+        assert_eq!(parse_from_str("a?"), "(a?)");
+        assert_eq!(parse_from_str("a^"), "(a^)");
+        assert_eq!(parse_from_str("a.b?.c^"), "((a.b?).c^)");
+        assert_eq!(parse_from_str("-a?"), "(-(a?))");
+        assert_eq!(parse_from_str("a? ?? b"), "((a?) ?? b)");
+        assert_eq!(parse_from_str("a^ + 1"), "((a^) + 1)");
+        assert_eq!(parse_from_str("f(x)?"), "(f(x)?)");
+    }
+
+    #[test]
+    fn test_list_and_array() {
+        assert_eq!(parse_from_str("[1,2,3]"), "[1, 2, 3]");
+        assert_eq!(parse_from_str("[\n1,\n2,\n]"), "[1, 2]");
+        assert_eq!(parse_from_str("#[1,2,3]"), "#[1, 2, 3]");
+        assert_eq!(parse_from_str("#[]"), "#[]");
+        assert_eq!(parse_from_str("[1, 2, ...a]"), "[1, 2, ...a]");
+        assert_eq!(parse_from_str("#[...a, ...b]"), "#[...a, ...b]");
+        assert!(parse_error("[1, 2"));
+        assert!(parse_error("#[1 2]"));
+    }
+
+    #[test]
+    fn test_matrix() {
+        assert_eq!(
+            parse_from_str("![\n1,2,3\n4,5,6\n7,8,9\n]"),
+            "![1, 2, 3; 4, 5, 6; 7, 8, 9]"
+        );
+        assert_eq!(parse_from_str("![1,2\n3,4]"), "![1, 2; 3, 4]");
+        assert_eq!(parse_from_str("![a + 1, b]"), "![(a + 1), b]");
+        assert_eq!(parse_from_str("![]"), "![]");
+        assert!(parse_error("![\n1,2,3\n4,5\n]"));
+        assert!(parse_error("![1,2\n3,4,5]"));
+        assert!(parse_error("![1,2,\n3,4]"));
+    }
+
+    #[test]
+    fn test_map() {
+        assert_eq!(
+            parse_from_str("{name: \"foo\", age: 1}"),
+            "{name: \"foo\", age: 1}"
+        );
+        assert_eq!(parse_from_str("{\n name: 1,\n ...a\n}"), "{name: 1, ...a}");
+        assert_eq!(parse_from_str("{...a}"), "{...a}");
+        assert_eq!(parse_from_str("{a: 1}.a"), "{a: 1}.a");
+        // 路径不是映射的键
+        assert_eq!(parse_from_str("{a::b}"), "{a::b}");
+        assert!(parse_error("{name: 1, 2}"));
+    }
+
+    #[test]
+    fn test_range_and_sequence() {
+        assert_eq!(parse_from_str("[1..10]"), "[1..10]");
+        assert_eq!(parse_from_str("[a+1 .. b*2]"), "[(a + 1)..(b * 2)]");
+        assert_eq!(parse_from_str("[1, 3, ..11]"), "[1, 3, ..11]");
+        assert_eq!(parse_from_str("[one, ..end]"), "[one, ..end]");
+        assert_eq!(
+            parse_from_str("for let i in [1..3] {i}"),
+            "for let i in [1..3] {i}"
+        );
+        assert!(parse_error("[...a..3]"));
+        assert!(parse_error("[...a, ..3]"));
+        assert!(parse_error("[1..2, 3]"));
+        assert!(parse_error("[1, ..3, 4]"));
+    }
+
+    #[test]
+    fn test_array_pattern() {
+        assert_eq!(parse_from_str("let #[a, b] = x"), "let #[a, b] = x");
+        assert_eq!(
+            parse_from_str("let #[a, ...rest] = x"),
+            "let #[a, ...rest] = x"
+        );
+    }
+
+    #[test]
+    fn test_infix_call() {
+        assert_eq!(parse_from_str("a :add: b"), "add(a, b)");
+        assert_eq!(parse_from_str("a :add: b :mul: c"), "mul(add(a, b), c)");
+        // 中置调用的优先级位于管道和逻辑或之间
+        assert_eq!(parse_from_str("a + b :f: c * d"), "f((a + b), (c * d))");
+        assert_eq!(parse_from_str("a || b :f: c | d"), "(f((a || b), c) | d)");
+        assert_eq!(parse_from_str("a\n:f: b"), "f(a, b)");
+        assert_eq!(parse_from_str("a :f:\nb"), "f(a, b)");
+        assert!(parse_error("a :f:"));
+    }
+
+    #[test]
+    fn test_prefix_call() {
+        assert_eq!(
+            parse_from_str("!fn_name (data, arg1, arg2)"),
+            "fn_name(data, arg1, arg2)"
+        );
+        assert_eq!(parse_from_str("!f(x).y"), "f(x).y");
+        assert_eq!(parse_from_str("!foo::bar (x)"), "foo::bar(x)");
+        assert_eq!(parse_from_str("!f ()"), "f()");
+        assert!(parse_error("!f x"));
+        assert!(parse_error("!(f) (x)"));
+    }
+
+    #[test]
+    fn test_keyword_arguments() {
+        assert_eq!(parse_from_str("name(a=1, b=2)"), "name(a = 1, b = 2)");
+        assert_eq!(parse_from_str("f(x, y, b=x+1)"), "f(x, y, b = (x + 1))");
+        assert_eq!(parse_from_str("!f (x, k=1)"), "f(x, k = 1)");
+        assert_eq!(
+            parse_from_str("max<Int>(a, b, c=3)"),
+            "max<Int>(a, b, c = 3)"
+        );
+        assert!(parse_error("f(b=2, x)"));
+        assert!(parse_error("f(b=1, b=2)"));
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        // 多个错误都被报告，错误的地方替换为错误节点
+        assert_eq!(
+            parse_with_errors("let a = )\nlet b = 2\nc d\ne"),
+            (
+                "<error: unexpected token>\nlet b = 2\nc\n<error: expected new line>\ne".to_owned(),
+                vec![8, 22]
+            )
+        );
+
+        // 块之内的错误不影响后面的表达式
+        assert_eq!(
+            parse_with_errors("function f() {\n  1 +\n  let x = )\n  2\n}\nf()"),
+            (
+                "function f () {<error: unexpected token>; 2}\nf()".to_owned(),
+                vec![31]
+            )
+        );
+
+        // 括号之内的换行符不是同步点
+        assert_eq!(
+            parse_with_errors("let a = f(1,\n,2)\nb"),
+            ("<error: unexpected token>\nb".to_owned(), vec![13])
+        );
+
+        // 顶层多余的右花括号被丢弃
+        assert_eq!(
+            parse_with_errors("}\na"),
+            ("<error: unexpected token>\na".to_owned(), vec![0])
+        );
+
+        // 同步到下一个语句关键字
+        assert_eq!(
+            parse_with_errors("let a = \nfunction f() = 1"),
+            (
+                "<error: unexpected token>\nfunction f () = 1".to_owned(),
+                vec![9]
+            )
+        );
+
+        // 命名空间之内的错误
+        assert_eq!(
+            parse_with_errors("namespace n {\n  use\n  alias A = B\n}"),
+            (
+                "namespace n {<error: expected identifier>; alias A = B}".to_owned(),
+                vec![19]
+            )
+        );
+
+        // 未结束的块
+        assert_eq!(parse_with_errors("function f() {\n  1").1.len(), 1);
+
+        // 语法错误与语义检查的错误一起报告
+        assert_eq!(parse_with_errors("next 1\n1 2").1, vec![0, 9]);
+
+        // parse 返回第一个错误
+        let tokens = tokenize("a b\nc d").unwrap();
+        assert!(matches!(
+            parse(&tokens),
+            Err(Error::ParserError("expected new line", Some(_)))
+        ));
+    }
+
+    #[test]
+    fn test_is_incomplete() {
+        let incomplete = |text: &str| is_incomplete(&tokenize(text).unwrap());
+
+        assert!(!incomplete(""));
+        assert!(!incomplete("1 + 2"));
+        assert!(!incomplete("let f = fn(a) {\n a\n}\n"));
+        assert!(!incomplete("a)"));
+
+        assert!(incomplete("let f = fn(a) {"));
+        assert!(incomplete("foo(1,\n 2"));
+        assert!(incomplete("[1, [2]"));
+        assert!(incomplete("let a ="));
+        assert!(incomplete("1 +\n"));
+        assert!(incomplete("a :add:"));
+        assert!(incomplete("x =>"));
+        assert!(incomplete("if a then"));
+    }
+
+    #[test]
+    fn test_match_expression() {
+        assert_eq!(
+            parse_from_str("match v {\n case 1: a\n case b: {b}\n default: c\n}"),
+            "match v {case 1: a; case b: {b}; default: c}"
+        );
+        assert_eq!(
+            parse_from_str("match v where let a = 1 {\n case x where let y = 2: x + y,\n}"),
+            "match v where let a = 1 {case x where let y = 2: (x + y)}"
+        );
+        assert_eq!(
+            parse_from_str("match v {case -1: a, case 'c': b, case \"s\": c, case _: d}"),
+            "match v {case -1: a; case 'c': b; case \"s\": c; case _: d}"
+        );
+        assert!(parse_error("match v {\n default: 1\n case a: 2\n}"));
+        assert!(parse_error("match v {\n case a b: 2\n}"));
+    }
+
+    #[test]
+    fn test_match_case_only() {
+        assert_eq!(
+            parse_from_str("match v {\n case a only a>0: 1\n case a only {a<0}: 2\n}"),
+            "match v {case a only (a > 0): 1; case a only {(a < 0)}: 2}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_into() {
+        assert_eq!(
+            parse_from_str("match s {\n case into Email email:\n sendMail(email)\n case into Phone phone:\n call(phone)\n}"),
+            "match s {case into Email email: sendMail(email); case into Phone phone: call(phone)}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_regular() {
+        assert_eq!(
+            parse_from_str("match s {\n case regular /^(.+)@(.+)$/ [email, name, domain]:\n writeLineFormat(\"It's Email: {}\", email)\n}"),
+            "match s {case regular /^(.+)@(.+)$/ [email, name, domain]: writeLineFormat(\"It's Email: {}\", email)}"
+        );
+        assert_eq!(
+            parse_from_str("match s {\n case regular re [phone, countryCode, number]: phone\n}"),
+            "match s {case regular re [phone, countryCode, number]: phone}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_template() {
+        assert_eq!(
+            parse_from_str("match s {\n case template `/user/{userName:\\w+}`:\n writeLineFormat(\"Get user {}\", userName)\n}"),
+            "match s {case template `/user/{userName:\\w+}`: writeLineFormat(\"Get user {}\", userName)}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_within() {
+        assert_eq!(
+            parse_from_str("match i {\n case within [1, 2]: a\n case within r: b\n}"),
+            "match i {case within [1, 2]: a; case within r: b}"
+        );
+        assert_eq!(
+            parse_from_str("match i {\n case within [1..2]: a\n}"),
+            "match i {case within [1..2]: a}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_to() {
+        assert_eq!(
+            parse_from_str("match u {\n case User{name} to u1: u1\n}"),
+            "match u {case User{name} to u1: u1}"
+        );
+        assert!(parse_error("match u {\n case a to b to c: 1\n}"));
+    }
+
+    #[test]
+    fn test_match_case_multiple_values() {
+        assert_eq!(
+            parse_from_str("match c {\n case 1,2,3: a\n case 'a', 'b' only x: b\n}"),
+            "match c {case 1, 2, 3: a; case 'a', 'b' only x: b}"
+        );
+    }
+
+    #[test]
+    fn test_match_case_nested() {
+        assert_eq!(
+            parse_from_str(
+                "match u {
+                    case User{
+                        name only name != \"\",
+                        number where let n = 1,
+                        addr: Addr {
+                                city within cities,
+                                ...
+                            }
+                        } to u1: u1
+                }"
+            ),
+            "match u {case User{name only (name != \"\"), number where let n = 1, addr: Addr{city within cities, ...}} to u1: u1}"
+        );
+        assert!(parse_error("match u {\n case User{...rest, name}: 1\n}"));
+    }
+
+    #[test]
+    fn test_match_case_clause_combination() {
+        assert_eq!(
+            parse_from_str(
+                "match v {\n case (a, [b, ...]) to t where let c = a + b only c > 0: t\n}"
+            ),
+            "match v {case (a, [b, ...]) to t only (c > 0) where let c = (a + b): t}"
+        );
+    }
+}
@@ -0,0 +1,1112 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use crate::ast::AnonymousFunction;
+use crate::ast::AssociatedType;
+use crate::ast::BlockExpression;
+use crate::ast::BranchExpression;
+use crate::ast::CallExpression;
+use crate::ast::ConstDeclaration;
+use crate::ast::ConstValue;
+use crate::ast::Expression;
+use crate::ast::FieldPattern;
+use crate::ast::FunctionDeclaration;
+use crate::ast::IfExpression;
+use crate::ast::ImplDeclaration;
+use crate::ast::InBinding;
+use crate::ast::LiteralValue;
+use crate::ast::MapEntry;
+use crate::ast::MatchExpression;
+use crate::ast::NamespaceDeclaration;
+use crate::ast::Parameter;
+use crate::ast::Pattern;
+use crate::ast::PatternClauses;
+use crate::ast::PostfixOperator;
+use crate::ast::Program;
+use crate::ast::Statement;
+use crate::ast::StructExpression;
+use crate::ast::TraitDeclaration;
+use crate::ast::UnionDeclaration;
+use crate::parser::binary_precedence;
+
+// 把 AST 输出为规范格式的 toy 源代码：
+// - 声明、表达式块、branch 和 match 的成员各占一行，使用 4 个空格缩进；
+// - 只在需要的地方添加括号，比如 `(a + b) * c`；
+// - 注释、多余的括号和换行等不在 AST 里的信息不会保留。
+// 对于解析器生成的 AST，输出的源代码重新解析之后得到相同的 AST（位置信息除外）。
+// 错误节点输出为 `<error: message>`，它不是合法的源代码
+pub fn print(program: &Program) -> String {
+    let mut printer = Printer::new();
+    printer.print_statements(&program.statements);
+    printer.output
+}
+
+pub fn print_expression(expression: &Expression) -> String {
+    let mut printer = Printer::new();
+    printer.print_expression(expression, 0);
+    printer.output
+}
+
+const INDENT: &str = "    ";
+
+// 表达式的优先级，开放式的表达式（比如 let、if 和匿名函数）会尽可能地
+// 向右延伸，所以作为运算数时需要添加括号
+const OPEN_PRECEDENCE: u8 = 0;
+const PREFIX_PRECEDENCE: u8 = 13;
+const POSTFIX_PRECEDENCE: u8 = 14;
+
+struct Printer {
+    output: String,
+    indent: usize,
+}
+
+impl Printer {
+    fn new() -> Self {
+        Printer {
+            output: String::new(),
+            indent: 0,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    fn new_line(&mut self) {
+        self.output.push('\n');
+        for _ in 0..self.indent {
+            self.output.push_str(INDENT);
+        }
+    }
+
+    // 输出花括号包围的多行成员，没有成员时输出 `{}`
+    fn print_lines<T>(&mut self, items: &[T], print_item: fn(&mut Self, &T)) {
+        if items.is_empty() {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.indent += 1;
+        for item in items {
+            self.new_line();
+            print_item(self, item);
+        }
+        self.indent -= 1;
+        self.new_line();
+        self.write("}");
+    }
+
+    fn print_separated<T>(&mut self, items: &[T], print_item: fn(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            print_item(self, item);
+        }
+    }
+
+    // 多行的声明语句前后各空一行
+    fn print_statements(&mut self, statements: &[Statement]) {
+        for (index, statement) in statements.iter().enumerate() {
+            if index > 0 {
+                if is_multi_line(&statements[index - 1]) || is_multi_line(statement) {
+                    self.output.push('\n');
+                }
+                self.new_line();
+            }
+            self.print_statement(statement);
+        }
+    }
+
+    fn print_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Expression(e) => self.print_expression(e, 0),
+            Statement::Function(s) => self.print_function(s),
+            Statement::Struct(s) => {
+                self.write(&format!("struct {} ", s.name));
+                self.print_lines(&s.fields, |p, field| p.write(&field.to_string()));
+            }
+            Statement::Union(s) => self.print_union(s),
+            Statement::Enum(s) => {
+                self.write(&format!("enum {} ", s.name));
+                self.print_lines(&s.members, |p, member| p.write(&member.name));
+            }
+            Statement::Const(s) => self.print_const(s),
+            Statement::Trait(s) => self.print_trait(s),
+            Statement::Impl(s) => self.print_impl(s),
+            Statement::Namespace(s) => self.print_namespace(s),
+            Statement::Use(s) => self.write(&format!("use {}", s.tree)),
+            Statement::Alias(s) => self.write(&format!("alias {} = {}", s.name, s.data_type)),
+            Statement::Error(s) => self.write(&s.to_string()),
+        }
+    }
+
+    fn print_function(&mut self, function: &FunctionDeclaration) {
+        if function.is_pattern {
+            self.write("pattern ");
+        }
+        if function.body.is_none() {
+            self.write("empty ");
+        }
+        self.write(&format!("function {}", function.name));
+        if !function.generic_parameters.is_empty() {
+            let names: Vec<String> = function
+                .generic_parameters
+                .iter()
+                .map(|p| p.to_string())
+                .collect();
+            self.write(&format!("<{}>", names.join(", ")));
+        }
+        self.write(" (");
+        self.print_separated(&function.parameters, Self::print_parameter);
+        self.write(")");
+        if let Some(return_type) = &function.return_type {
+            self.write(&format!(" type {}", return_type));
+        }
+        if !function.which.is_empty() {
+            let entries: Vec<String> = function.which.iter().map(|e| e.to_string()).collect();
+            self.write(&format!(" which {{{}}}", entries.join(", ")));
+        }
+        match &function.body {
+            Some(Expression::Block(body)) => {
+                self.write(" ");
+                self.print_block(body);
+            }
+            Some(body) => {
+                self.write(" = ");
+                self.print_expression(body, 0);
+            }
+            None => {}
+        }
+    }
+
+    fn print_parameter(&mut self, parameter: &Parameter) {
+        if let Some(data_type) = &parameter.data_type {
+            self.write(&format!("{} ", data_type));
+        }
+        self.print_pattern(&parameter.pattern);
+        if let Some(default_value) = &parameter.default_value {
+            self.write(" = ");
+            self.print_expression(default_value, 0);
+        }
+    }
+
+    fn print_union(&mut self, union: &UnionDeclaration) {
+        self.write(&format!("union {} ", union.name));
+        self.print_lines(&union.members, |p, member| {
+            if member.fields.is_empty() {
+                p.write(&member.name.name);
+            } else {
+                let fields: Vec<String> = member.fields.iter().map(|f| f.to_string()).collect();
+                p.write(&format!("{} {{{}}}", member.name, fields.join(", ")));
+            }
+        });
+    }
+
+    fn print_const(&mut self, declaration: &ConstDeclaration) {
+        self.write(&format!(
+            "const {} {}",
+            declaration.data_type, declaration.name
+        ));
+        match &declaration.value {
+            ConstValue::Single(value) => {
+                self.write(" = ");
+                self.print_expression(value, 0);
+            }
+            ConstValue::Group(members) => {
+                self.write(" ");
+                self.print_lines(members, |p, member| {
+                    p.write(&format!("{} = ", member.name));
+                    p.print_expression(&member.value, 0);
+                });
+            }
+        }
+    }
+
+    fn print_trait(&mut self, declaration: &TraitDeclaration) {
+        self.write(&format!("trait {}", declaration.name));
+        self.print_associated_types(&declaration.associated_types);
+        self.write(" ");
+        self.print_lines(&declaration.functions, Self::print_function);
+    }
+
+    fn print_impl(&mut self, declaration: &ImplDeclaration) {
+        self.write(&format!("impl {}", declaration.data_type));
+        if let Some(trait_name) = &declaration.trait_name {
+            self.write(&format!(" trait {}", trait_name));
+        }
+        self.print_associated_types(&declaration.associated_types);
+        self.write(" ");
+        self.print_lines(&declaration.functions, Self::print_function);
+    }
+
+    fn print_associated_types(&mut self, associated_types: &[AssociatedType]) {
+        let types: Vec<String> = associated_types.iter().map(|t| t.to_string()).collect();
+        match types.len() {
+            0 => {}
+            1 => self.write(&format!(" type {}", types[0])),
+            _ => self.write(&format!(" type ({})", types.join(", "))),
+        }
+    }
+
+    fn print_namespace(&mut self, declaration: &NamespaceDeclaration) {
+        self.write(&format!("namespace {} ", declaration.name));
+        if declaration.statements.is_empty() {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.indent += 1;
+        self.new_line();
+        self.print_statements(&declaration.statements);
+        self.indent -= 1;
+        self.new_line();
+        self.write("}");
+    }
+
+    // 输出表达式，优先级低于 min_precedence 时添加括号
+    fn print_expression(&mut self, expression: &Expression, min_precedence: u8) {
+        if precedence(expression) < min_precedence {
+            self.write("(");
+            self.print_expression(expression, 0);
+            self.write(")");
+            return;
+        }
+
+        match expression {
+            Expression::Literal(e) => self.write(&e.to_string()),
+            Expression::Identifier(e) => self.write(&e.name),
+            Expression::Path(e) => self.write(&e.to_string()),
+            Expression::Tuple(e) => {
+                self.write("(");
+                self.print_separated(&e.elements, Self::print_element);
+                if e.elements.len() == 1 {
+                    self.write(",");
+                }
+                self.write(")");
+            }
+            Expression::List(e) => {
+                self.write("[");
+                self.print_separated(&e.elements, Self::print_element);
+                self.write("]");
+            }
+            Expression::Array(e) => {
+                self.write("#[");
+                self.print_separated(&e.elements, Self::print_element);
+                self.write("]");
+            }
+            Expression::Matrix(e) => {
+                // 矩阵的行之间使用分号（等同于换行）分隔
+                self.write("![");
+                for (index, row) in e.rows.iter().enumerate() {
+                    if index > 0 {
+                        self.write("; ");
+                    }
+                    self.print_separated(row, Self::print_element);
+                }
+                self.write("]");
+            }
+            Expression::Map(e) => {
+                self.write("{");
+                self.print_separated(&e.entries, |p, entry| match entry {
+                    MapEntry::Pair(pair) => {
+                        p.write(&format!("{}: ", pair.name));
+                        p.print_expression(&pair.value, 0);
+                    }
+                    MapEntry::Spread(spread) => {
+                        p.write("...");
+                        p.print_expression(&spread.value, 0);
+                    }
+                });
+                self.write("}");
+            }
+            Expression::Range(e) => {
+                self.write("[");
+                self.print_expression(&e.start, 0);
+                self.write("..");
+                self.print_expression(&e.end, 0);
+                self.write("]");
+            }
+            Expression::Sequence(e) => {
+                self.write("[");
+                self.print_separated(&e.elements, Self::print_element);
+                self.write(", ..");
+                self.print_expression(&e.end, 0);
+                self.write("]");
+            }
+            Expression::Spread(e) => {
+                self.write("...");
+                self.print_expression(&e.value, 0);
+            }
+            Expression::Block(e) => self.print_block(e),
+            Expression::Let(e) => {
+                self.write("let ");
+                self.print_pattern(&e.pattern);
+                self.write(if e.is_match { " match " } else { " = " });
+                self.print_expression(&e.value, 0);
+            }
+            Expression::If(e) => self.print_if(e),
+            Expression::Branch(e) => self.print_branch(e),
+            Expression::Match(e) => self.print_match(e),
+            Expression::ForLet(e) => {
+                self.write("for let ");
+                self.print_pattern(&e.pattern);
+                self.write(" = ");
+                self.print_head(&e.initial);
+                self.write(" ");
+                self.print_loop_body(&e.body);
+            }
+            Expression::ForIn(e) => {
+                self.write("for ");
+                self.print_in_bindings(&e.bindings);
+                self.write(" ");
+                self.print_loop_body(&e.body);
+            }
+            Expression::Each(e) => {
+                self.write("each ");
+                self.print_in_bindings(&e.bindings);
+                self.write(" ");
+                self.print_loop_body(&e.body);
+            }
+            Expression::Next(e) => {
+                self.write("next ");
+                self.print_expression(&e.value, 0);
+            }
+            Expression::AnonymousFunction(e) => self.print_anonymous_function(e),
+            Expression::Struct(e) => self.print_struct(e),
+            Expression::Prefix(e) => {
+                self.write(&e.operator.to_string());
+                // 避免连续的负号，比如 `-(-a)`
+                if starts_with_minus(&e.operand) {
+                    self.write("(");
+                    self.print_expression(&e.operand, 0);
+                    self.write(")");
+                } else {
+                    self.print_expression(&e.operand, PREFIX_PRECEDENCE);
+                }
+            }
+            Expression::Postfix(e) => {
+                self.print_expression(&e.operand, POSTFIX_PRECEDENCE);
+                // 连续的 `??` 会被识别为 UnwrapOr 运算符
+                if e.operator == PostfixOperator::Unwrap
+                    && matches!(&e.operand, Expression::Postfix(p) if p.operator == PostfixOperator::Unwrap)
+                {
+                    self.write(" ");
+                }
+                self.write(&e.operator.to_string());
+            }
+            Expression::Binary(e) => {
+                let precedence = binary_precedence(e.operator);
+                self.print_expression(&e.left, precedence);
+                self.write(&format!(" {} ", e.operator));
+                self.print_expression(&e.right, precedence + 1);
+            }
+            Expression::Call(e) => self.print_call(e),
+            Expression::Member(e) => {
+                self.print_expression(&e.object, POSTFIX_PRECEDENCE);
+                self.write(&format!(".{}", e.property));
+            }
+            Expression::TupleIndex(e) => {
+                self.print_expression(&e.object, POSTFIX_PRECEDENCE);
+                self.write(&format!(".{}", e.index));
+            }
+            Expression::Index(e) => {
+                self.print_expression(&e.object, POSTFIX_PRECEDENCE);
+                self.write("[");
+                self.print_expression(&e.index, 0);
+                self.write("]");
+            }
+            Expression::Slice(e) => {
+                self.print_expression(&e.object, POSTFIX_PRECEDENCE);
+                self.write("[");
+                if let Some(start) = &e.start {
+                    self.print_expression(start, 0);
+                }
+                self.write(":");
+                if let Some(end) = &e.end {
+                    self.print_expression(end, 0);
+                }
+                self.write("]");
+            }
+            Expression::Error(e) => self.write(&e.to_string()),
+        }
+    }
+
+    fn print_element(&mut self, expression: &Expression) {
+        self.print_expression(expression, 0);
+    }
+
+    fn print_block(&mut self, block: &BlockExpression) {
+        self.print_lines(&block.expressions, Self::print_element);
+    }
+
+    fn print_if(&mut self, expression: &IfExpression) {
+        self.write("if ");
+        self.print_expression(&expression.condition, 0);
+        if let Some(where_clause) = &expression.where_clause {
+            self.write(" where ");
+            self.print_expression(where_clause, 0);
+        }
+        self.write(" then ");
+
+        // 避免 else 被内层没有 else 的 if 表达式获取
+        if expression.alternative.is_some() && is_dangling_if(&expression.consequent) {
+            self.write("(");
+            self.print_expression(&expression.consequent, 0);
+            self.write(")");
+        } else {
+            self.print_expression(&expression.consequent, 0);
+        }
+
+        if let Some(alternative) = &expression.alternative {
+            self.write(" else ");
+            self.print_expression(alternative, 0);
+        }
+    }
+
+    fn print_branch(&mut self, expression: &BranchExpression) {
+        self.write("branch ");
+        if let Some(where_clause) = &expression.where_clause {
+            self.write("where ");
+            self.print_head(where_clause);
+            self.write(" ");
+        }
+
+        self.write("{");
+        self.indent += 1;
+        for case in &expression.cases {
+            self.new_line();
+            self.write("case ");
+            self.print_expression(&case.condition, 0);
+            if let Some(where_clause) = &case.where_clause {
+                self.write(" where ");
+                self.print_expression(where_clause, 0);
+            }
+            self.write(": ");
+            self.print_expression(&case.body, 0);
+        }
+        self.print_default(&expression.default);
+        self.indent -= 1;
+        self.new_line();
+        self.write("}");
+    }
+
+    fn print_match(&mut self, expression: &MatchExpression) {
+        self.write("match ");
+        self.print_head(&expression.subject);
+        if let Some(where_clause) = &expression.where_clause {
+            self.write(" where ");
+            self.print_head(where_clause);
+        }
+
+        self.write(" {");
+        self.indent += 1;
+        for case in &expression.cases {
+            self.new_line();
+            self.write("case ");
+            self.print_separated(&case.patterns, Self::print_pattern);
+            self.print_pattern_clauses(&case.clauses);
+            self.write(": ");
+            self.print_expression(&case.body, 0);
+        }
+        self.print_default(&expression.default);
+        self.indent -= 1;
+        self.new_line();
+        self.write("}");
+    }
+
+    fn print_default(&mut self, default: &Option<Expression>) {
+        if let Some(default) = default {
+            self.new_line();
+            self.write("default: ");
+            self.print_expression(default, 0);
+        }
+    }
+
+    // 输出后面紧跟着左花括号的表达式，比如 match 的主体，
+    // 以类型名称结尾的表达式需要添加括号，以避免被识别为结构体实例化
+    fn print_head(&mut self, expression: &Expression) {
+        if ends_with_type_name(expression) {
+            self.write("(");
+            self.print_expression(expression, 0);
+            self.write(")");
+        } else {
+            self.print_expression(expression, 0);
+        }
+    }
+
+    fn print_in_bindings(&mut self, bindings: &[InBinding]) {
+        for (index, binding) in bindings.iter().enumerate() {
+            if index > 0 {
+                self.write(" mix ");
+            }
+            self.write("let ");
+            self.print_pattern(&binding.pattern);
+            self.write(" in ");
+            self.print_head(&binding.iterable);
+        }
+    }
+
+    // 循环体紧跟在初始值或者迭代对象之后，以负号开始的循环体需要添加括号，
+    // 以避免被识别为减法
+    fn print_loop_body(&mut self, body: &Expression) {
+        if starts_with_minus(body) {
+            self.write("(");
+            self.print_expression(body, 0);
+            self.write(")");
+        } else {
+            self.print_expression(body, 0);
+        }
+    }
+
+    fn print_anonymous_function(&mut self, function: &AnonymousFunction) {
+        self.write("(");
+        self.print_separated(&function.parameters, Self::print_parameter);
+        self.write(")");
+        if let Some(return_type) = &function.return_type {
+            self.write(&format!(" type {}", return_type));
+        }
+        self.write(" => ");
+        self.print_expression(&function.body, 0);
+    }
+
+    fn print_struct(&mut self, expression: &StructExpression) {
+        if let Some(name) = &expression.name {
+            self.write(&format!("{} ", name));
+        }
+
+        self.write("{");
+        self.print_separated(&expression.fields, |p, field| {
+            if let Some(name) = &field.name {
+                p.write(&format!("{}: ", name));
+            }
+            p.print_expression(&field.value, 0);
+        });
+        // 省略名称且只有一个成员时添加逗号，以避免跟表达式块混淆
+        if expression.name.is_none() && expression.fields.len() == 1 {
+            self.write(",");
+        }
+        self.write("}");
+    }
+
+    fn print_call(&mut self, expression: &CallExpression) {
+        self.print_expression(&expression.callee, POSTFIX_PRECEDENCE);
+        if !expression.generic_arguments.is_empty() {
+            let arguments: Vec<String> = expression
+                .generic_arguments
+                .iter()
+                .map(|a| a.to_string())
+                .collect();
+            self.write(&format!("<{}>", arguments.join(", ")));
+        }
+
+        self.write("(");
+        self.print_separated(&expression.arguments, Self::print_element);
+        if !expression.arguments.is_empty() && !expression.keyword_arguments.is_empty() {
+            self.write(", ");
+        }
+        self.print_separated(&expression.keyword_arguments, |p, argument| {
+            p.write(&format!("{} = ", argument.name));
+            p.print_expression(&argument.value, 0);
+        });
+        self.write(")");
+    }
+
+    fn print_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard(_) => self.write("_"),
+            Pattern::Identifier(p) => self.write(&p.name),
+            Pattern::Literal(p) => self.write(&p.to_string()),
+            Pattern::Tuple(p) => {
+                self.write("(");
+                self.print_separated(&p.elements, Self::print_pattern);
+                if p.elements.len() == 1 {
+                    self.write(",");
+                }
+                self.write(")");
+            }
+            Pattern::List(p) => {
+                self.write("[");
+                self.print_separated(&p.elements, Self::print_pattern);
+                self.write("]");
+            }
+            Pattern::Array(p) => {
+                self.write("#[");
+                self.print_separated(&p.elements, Self::print_pattern);
+                self.write("]");
+            }
+            Pattern::Struct(p) => {
+                if let Some(name) = &p.name {
+                    self.write(&format!("{} ", name));
+                }
+                self.write("{");
+                self.print_separated(&p.fields, Self::print_field_pattern);
+                if let Some(rest) = &p.rest {
+                    if !p.fields.is_empty() {
+                        self.write(", ");
+                    }
+                    self.write(&rest.to_string());
+                }
+                self.write("}");
+            }
+            Pattern::Rest(p) => self.write(&p.to_string()),
+            Pattern::Into(p) => self.write(&format!("into {} {}", p.data_type, p.name)),
+            Pattern::Regular(p) => {
+                self.write("regular ");
+                self.print_expression(&p.regexp, POSTFIX_PRECEDENCE);
+                let names: Vec<String> = p.names.iter().map(|n| n.to_string()).collect();
+                self.write(&format!(" [{}]", names.join(", ")));
+            }
+            Pattern::Template(p) => self.write(&format!("template `{}`", p.template)),
+            Pattern::Within(p) => {
+                self.write("within ");
+                self.print_expression(&p.range, 0);
+            }
+        }
+    }
+
+    fn print_field_pattern(&mut self, field: &FieldPattern) {
+        self.write(&field.name.name);
+        if let Some(pattern) = &field.pattern {
+            self.write(if field.is_shorthand { " " } else { ": " });
+            self.print_pattern(pattern);
+        }
+        self.print_pattern_clauses(&field.clauses);
+    }
+
+    fn print_pattern_clauses(&mut self, clauses: &PatternClauses) {
+        if let Some(to) = &clauses.to {
+            self.write(&format!(" to {}", to));
+        }
+        if let Some(only) = &clauses.only {
+            self.write(" only ");
+            self.print_expression(only, 0);
+        }
+        if let Some(where_clause) = &clauses.where_clause {
+            self.write(" where ");
+            self.print_expression(where_clause, 0);
+        }
+    }
+}
+
+fn is_multi_line(statement: &Statement) -> bool {
+    !matches!(
+        statement,
+        Statement::Expression(_)
+            | Statement::Use(_)
+            | Statement::Alias(_)
+            | Statement::Error(_)
+            | Statement::Const(ConstDeclaration {
+                value: ConstValue::Single(_),
+                ..
+            })
+    )
+}
+
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Let(_)
+        | Expression::If(_)
+        | Expression::ForLet(_)
+        | Expression::ForIn(_)
+        | Expression::Each(_)
+        | Expression::Next(_)
+        | Expression::AnonymousFunction(_) => OPEN_PRECEDENCE,
+        Expression::Binary(e) => binary_precedence(e.operator),
+        Expression::Prefix(_) => PREFIX_PRECEDENCE,
+        Expression::Literal(e) if matches!(e.value, LiteralValue::Integer(value) if value < 0) => {
+            PREFIX_PRECEDENCE
+        }
+        _ => POSTFIX_PRECEDENCE,
+    }
+}
+
+// 表达式的输出是否以负号开始
+fn starts_with_minus(expression: &Expression) -> bool {
+    match expression {
+        Expression::Prefix(_) => true,
+        Expression::Literal(e) => matches!(e.value, LiteralValue::Integer(value) if value < 0),
+        Expression::Binary(e) => {
+            precedence(&e.left) >= binary_precedence(e.operator) && starts_with_minus(&e.left)
+        }
+        _ => false,
+    }
+}
+
+// 表达式的输出是否以类型名称（首字母大写的标识符）结尾
+fn ends_with_type_name(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(e) => e.name.starts_with(char::is_uppercase),
+        Expression::Binary(e) => {
+            precedence(&e.right) > binary_precedence(e.operator) && ends_with_type_name(&e.right)
+        }
+        Expression::Prefix(e) => {
+            precedence(&e.operand) >= PREFIX_PRECEDENCE && ends_with_type_name(&e.operand)
+        }
+        Expression::Let(e) => ends_with_type_name(&e.value),
+        Expression::If(e) => match &e.alternative {
+            Some(alternative) => ends_with_type_name(alternative),
+            None => ends_with_type_name(&e.consequent),
+        },
+        Expression::ForLet(e) => ends_with_type_name(&e.body),
+        Expression::ForIn(e) => ends_with_type_name(&e.body),
+        Expression::Each(e) => ends_with_type_name(&e.body),
+        Expression::Next(e) => ends_with_type_name(&e.value),
+        Expression::AnonymousFunction(e) => ends_with_type_name(&e.body),
+        _ => false,
+    }
+}
+
+// 表达式的输出是否以没有 else 的 if 表达式结尾
+fn is_dangling_if(expression: &Expression) -> bool {
+    match expression {
+        Expression::If(e) => match &e.alternative {
+            Some(alternative) => is_dangling_if(alternative),
+            None => true,
+        },
+        Expression::Let(e) => is_dangling_if(&e.value),
+        Expression::ForLet(e) => is_dangling_if(&e.body),
+        Expression::ForIn(e) => is_dangling_if(&e.body),
+        Expression::Each(e) => is_dangling_if(&e.body),
+        Expression::Next(e) => is_dangling_if(&e.value),
+        Expression::AnonymousFunction(e) => is_dangling_if(&e.body),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::AliasDeclaration;
+    use crate::ast::AnonymousFunction;
+    use crate::ast::AssociatedType;
+    use crate::ast::BinaryExpression;
+    use crate::ast::BlockExpression;
+    use crate::ast::BranchCase;
+    use crate::ast::BranchExpression;
+    use crate::ast::CallExpression;
+    use crate::ast::ConstDeclaration;
+    use crate::ast::ConstMember;
+    use crate::ast::DataType;
+    use crate::ast::EachExpression;
+    use crate::ast::EnumDeclaration;
+    use crate::ast::ErrorNode;
+    use crate::ast::FieldDeclaration;
+    use crate::ast::FieldPattern;
+    use crate::ast::ForInExpression;
+    use crate::ast::ForLetExpression;
+    use crate::ast::FunctionDeclaration;
+    use crate::ast::Identifier;
+    use crate::ast::IfExpression;
+    use crate::ast::ImplDeclaration;
+    use crate::ast::InBinding;
+    use crate::ast::IndexExpression;
+    use crate::ast::KeywordArgument;
+    use crate::ast::LetExpression;
+    use crate::ast::ListExpression;
+    use crate::ast::Literal;
+    use crate::ast::MapExpression;
+    use crate::ast::MapPair;
+    use crate::ast::MatchCase;
+    use crate::ast::MatchExpression;
+    use crate::ast::MatrixExpression;
+    use crate::ast::MemberExpression;
+    use crate::ast::NamespaceDeclaration;
+    use crate::ast::NextExpression;
+    use crate::ast::Parameter;
+    use crate::ast::Path;
+    use crate::ast::Pattern;
+    use crate::ast::PostfixExpression;
+    use crate::ast::PrefixExpression;
+    use crate::ast::Program;
+    use crate::ast::RangeExpression;
+    use crate::ast::RestPattern;
+    use crate::ast::SequenceExpression;
+    use crate::ast::SliceExpression;
+    use crate::ast::SpreadExpression;
+    use crate::ast::StructDeclaration;
+    use crate::ast::StructExpression;
+    use crate::ast::StructPattern;
+    use crate::ast::TraitDeclaration;
+    use crate::ast::TupleExpression;
+    use crate::ast::TupleIndexExpression;
+    use crate::ast::UnionDeclaration;
+    use crate::ast::UnionMember;
+    use crate::ast::UseDeclaration;
+    use crate::ast::UseTree;
+    use crate::ast::WhichEntry;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+    use crate::token::Location;
+    use crate::visitor::walk_alias_declaration_mut;
+    use crate::visitor::walk_anonymous_function_mut;
+    use crate::visitor::walk_associated_type_mut;
+    use crate::visitor::walk_binary_expression_mut;
+    use crate::visitor::walk_block_expression_mut;
+    use crate::visitor::walk_branch_case_mut;
+    use crate::visitor::walk_branch_expression_mut;
+    use crate::visitor::walk_call_expression_mut;
+    use crate::visitor::walk_const_declaration_mut;
+    use crate::visitor::walk_const_member_mut;
+    use crate::visitor::walk_data_type_mut;
+    use crate::visitor::walk_each_expression_mut;
+    use crate::visitor::walk_enum_declaration_mut;
+    use crate::visitor::walk_field_declaration_mut;
+    use crate::visitor::walk_field_pattern_mut;
+    use crate::visitor::walk_for_in_expression_mut;
+    use crate::visitor::walk_for_let_expression_mut;
+    use crate::visitor::walk_function_declaration_mut;
+    use crate::visitor::walk_if_expression_mut;
+    use crate::visitor::walk_impl_declaration_mut;
+    use crate::visitor::walk_in_binding_mut;
+    use crate::visitor::walk_index_expression_mut;
+    use crate::visitor::walk_keyword_argument_mut;
+    use crate::visitor::walk_let_expression_mut;
+    use crate::visitor::walk_list_expression_mut;
+    use crate::visitor::walk_map_expression_mut;
+    use crate::visitor::walk_map_pair_mut;
+    use crate::visitor::walk_match_case_mut;
+    use crate::visitor::walk_match_expression_mut;
+    use crate::visitor::walk_matrix_expression_mut;
+    use crate::visitor::walk_member_expression_mut;
+    use crate::visitor::walk_namespace_declaration_mut;
+    use crate::visitor::walk_next_expression_mut;
+    use crate::visitor::walk_parameter_mut;
+    use crate::visitor::walk_path_mut;
+    use crate::visitor::walk_pattern_mut;
+    use crate::visitor::walk_postfix_expression_mut;
+    use crate::visitor::walk_prefix_expression_mut;
+    use crate::visitor::walk_range_expression_mut;
+    use crate::visitor::walk_rest_pattern_mut;
+    use crate::visitor::walk_sequence_expression_mut;
+    use crate::visitor::walk_slice_expression_mut;
+    use crate::visitor::walk_spread_expression_mut;
+    use crate::visitor::walk_struct_declaration_mut;
+    use crate::visitor::walk_struct_expression_mut;
+    use crate::visitor::walk_struct_pattern_mut;
+    use crate::visitor::walk_trait_declaration_mut;
+    use crate::visitor::walk_tuple_expression_mut;
+    use crate::visitor::walk_tuple_index_expression_mut;
+    use crate::visitor::walk_union_declaration_mut;
+    use crate::visitor::walk_union_member_mut;
+    use crate::visitor::walk_use_declaration_mut;
+    use crate::visitor::walk_use_tree_mut;
+    use crate::visitor::walk_which_entry_mut;
+    use crate::visitor::VisitorMut;
+
+    use super::print;
+
+    fn parse_from_str(text: &str) -> Program {
+        let tokens = tokenize(text).unwrap();
+        parse(&tokens).unwrap_or_else(|error| panic!("{:?}\n{}", error, text))
+    }
+
+    fn print_from_str(text: &str) -> String {
+        print(&parse_from_str(text))
+    }
+
+    const ERASED: Location = Location {
+        file_id: 0,
+        start: 0,
+        end: 0,
+    };
+
+    // 把 AST 所有节点的位置信息清零，以便直接比较两棵语法树
+    struct LocationEraser;
+
+    macro_rules! erase_locations {
+        ($($visit:ident($node_type:ty) => $walk:ident;)*) => {
+            $(
+                fn $visit(&mut self, node: &mut $node_type) {
+                    node.location = ERASED;
+                    $walk(self, node);
+                }
+            )*
+        };
+    }
+
+    impl VisitorMut for LocationEraser {
+        erase_locations! {
+            visit_function_declaration(FunctionDeclaration) => walk_function_declaration_mut;
+            visit_parameter(Parameter) => walk_parameter_mut;
+            visit_which_entry(WhichEntry) => walk_which_entry_mut;
+            visit_trait_declaration(TraitDeclaration) => walk_trait_declaration_mut;
+            visit_impl_declaration(ImplDeclaration) => walk_impl_declaration_mut;
+            visit_associated_type(AssociatedType) => walk_associated_type_mut;
+            visit_namespace_declaration(NamespaceDeclaration) => walk_namespace_declaration_mut;
+            visit_use_declaration(UseDeclaration) => walk_use_declaration_mut;
+            visit_use_tree(UseTree) => walk_use_tree_mut;
+            visit_alias_declaration(AliasDeclaration) => walk_alias_declaration_mut;
+            visit_struct_declaration(StructDeclaration) => walk_struct_declaration_mut;
+            visit_field_declaration(FieldDeclaration) => walk_field_declaration_mut;
+            visit_union_declaration(UnionDeclaration) => walk_union_declaration_mut;
+            visit_union_member(UnionMember) => walk_union_member_mut;
+            visit_enum_declaration(EnumDeclaration) => walk_enum_declaration_mut;
+            visit_const_declaration(ConstDeclaration) => walk_const_declaration_mut;
+            visit_const_member(ConstMember) => walk_const_member_mut;
+            visit_path(Path) => walk_path_mut;
+            visit_tuple_expression(TupleExpression) => walk_tuple_expression_mut;
+            visit_list_expression(ListExpression) => walk_list_expression_mut;
+            visit_array_expression(ListExpression) => walk_list_expression_mut;
+            visit_matrix_expression(MatrixExpression) => walk_matrix_expression_mut;
+            visit_map_expression(MapExpression) => walk_map_expression_mut;
+            visit_map_pair(MapPair) => walk_map_pair_mut;
+            visit_range_expression(RangeExpression) => walk_range_expression_mut;
+            visit_sequence_expression(SequenceExpression) => walk_sequence_expression_mut;
+            visit_spread_expression(SpreadExpression) => walk_spread_expression_mut;
+            visit_block_expression(BlockExpression) => walk_block_expression_mut;
+            visit_let_expression(LetExpression) => walk_let_expression_mut;
+            visit_if_expression(IfExpression) => walk_if_expression_mut;
+            visit_branch_expression(BranchExpression) => walk_branch_expression_mut;
+            visit_branch_case(BranchCase) => walk_branch_case_mut;
+            visit_match_expression(MatchExpression) => walk_match_expression_mut;
+            visit_match_case(MatchCase) => walk_match_case_mut;
+            visit_for_let_expression(ForLetExpression) => walk_for_let_expression_mut;
+            visit_for_in_expression(ForInExpression) => walk_for_in_expression_mut;
+            visit_each_expression(EachExpression) => walk_each_expression_mut;
+            visit_in_binding(InBinding) => walk_in_binding_mut;
+            visit_next_expression(NextExpression) => walk_next_expression_mut;
+            visit_anonymous_function(AnonymousFunction) => walk_anonymous_function_mut;
+            visit_struct_expression(StructExpression) => walk_struct_expression_mut;
+            visit_prefix_expression(PrefixExpression) => walk_prefix_expression_mut;
+            visit_postfix_expression(PostfixExpression) => walk_postfix_expression_mut;
+            visit_binary_expression(BinaryExpression) => walk_binary_expression_mut;
+            visit_call_expression(CallExpression) => walk_call_expression_mut;
+            visit_keyword_argument(KeywordArgument) => walk_keyword_argument_mut;
+            visit_member_expression(MemberExpression) => walk_member_expression_mut;
+            visit_tuple_index_expression(TupleIndexExpression) => walk_tuple_index_expression_mut;
+            visit_index_expression(IndexExpression) => walk_index_expression_mut;
+            visit_slice_expression(SliceExpression) => walk_slice_expression_mut;
+            visit_struct_pattern(StructPattern) => walk_struct_pattern_mut;
+            visit_field_pattern(FieldPattern) => walk_field_pattern_mut;
+            visit_rest_pattern(RestPattern) => walk_rest_pattern_mut;
+        }
+
+        fn visit_error_node(&mut self, node: &mut ErrorNode) {
+            node.location = ERASED;
+        }
+
+        fn visit_identifier(&mut self, identifier: &mut Identifier) {
+            identifier.location = ERASED;
+        }
+
+        fn visit_literal(&mut self, literal: &mut Literal) {
+            literal.location = ERASED;
+        }
+
+        fn visit_data_type(&mut self, data_type: &mut DataType) {
+            match data_type {
+                DataType::Named(t) => t.location = ERASED,
+                DataType::Sign(t) => t.location = ERASED,
+            }
+            walk_data_type_mut(self, data_type);
+        }
+
+        fn visit_pattern(&mut self, pattern: &mut Pattern) {
+            match pattern {
+                Pattern::Wildcard(location) => *location = ERASED,
+                Pattern::Tuple(p) => p.location = ERASED,
+                Pattern::List(p) | Pattern::Array(p) => p.location = ERASED,
+                Pattern::Into(p) => p.location = ERASED,
+                Pattern::Regular(p) => p.location = ERASED,
+                Pattern::Template(p) => p.location = ERASED,
+                Pattern::Within(p) => p.location = ERASED,
+                Pattern::Identifier(_)
+                | Pattern::Literal(_)
+                | Pattern::Struct(_)
+                | Pattern::Rest(_) => {}
+            }
+            walk_pattern_mut(self, pattern);
+        }
+    }
+
+    fn without_locations(program: &Program) -> Program {
+        let mut program = program.clone();
+        LocationEraser.visit_program(&mut program);
+        program
+    }
+
+    fn assert_round_trip(text: &str) {
+        let program = parse_from_str(text);
+        let printed = print(&program);
+        let reparsed = parse_from_str(&printed);
+        assert_eq!(
+            without_locations(&reparsed),
+            without_locations(&program),
+            "{}",
+            printed
+        );
+        assert_eq!(print(&reparsed), printed);
+    }
+
+    #[test]
+    fn test_print_expression() {
+        assert_eq!(print_from_str("(1+2)*3-(4-5)"), "(1 + 2) * 3 - (4 - 5)");
+        assert_eq!(print_from_str("1+(2*3)"), "1 + 2 * 3");
+        assert_eq!(print_from_str("-(-a)"), "-(-a)");
+        assert_eq!(print_from_str("(-a)?.b"), "(-a)?.b");
+        assert_eq!(print_from_str("a :max: b"), "max(a, b)");
+        assert_eq!(
+            print_from_str("(if a then b else c) + 1"),
+            "(if a then b else c) + 1"
+        );
+        assert_eq!(
+            print_from_str("if a then (if b then c) else d"),
+            "if a then (if b then c) else d"
+        );
+        assert_eq!(
+            print_from_str("match (Foo) {case 1: 2}"),
+            "match (Foo) {\n    case 1: 2\n}"
+        );
+        assert_eq!(print_from_str("for let i = 0 (-i)"), "for let i = 0 (-i)");
+    }
+
+    #[test]
+    fn test_print_statements() {
+        assert_eq!(
+            print_from_str(
+                "let a = 1\nfunction f(Int x) type Int {let y = x\ny}\nstruct S {Int a, String b}\nf(a)"
+            ),
+            "let a = 1\n\n\
+            function f (Int x) type Int {\n    let y = x\n    y\n}\n\n\
+            struct S {\n    Int a\n    String b\n}\n\n\
+            f(a)"
+        );
+        assert_eq!(
+            print_from_str("namespace n {use a::{b, c::*}\nconst Int C {A = 1, B = 2}}"),
+            "namespace n {\n    use a::{b, c::*}\n\n    const Int C {\n        A = 1\n        B = 2\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip("1 + 2 * 3 - (4 - 5) ?? a & b | c >> d");
+        assert_round_trip("a < b > c\nf<Int>(x)\nList<List<Int>>(y)");
+        assert_round_trip("a.b(c)[0]? ?\nx[1:]\nt.0.1\nm[:n - 1]");
+        assert_round_trip("(1,)\n()\n[1, ...a]\n#[1, 2]\n![1, 2\n3, 4]\n{a: 1, ...b}");
+        assert_round_trip("[1..n + 1]\n[1, 3, ..n]\nUser {id: 1}\nUser {1, \"foo\"}\n{a,}");
+        assert_round_trip("let f = (Int a, b = 1) type Int => a + b\nf(1, b = 2)\n!max (1, 2)");
+        assert_round_trip("if a where b then if c then d else e\nif a then (if b then c) else d");
+        assert_round_trip(
+            "branch where x {\ncase a where b: c\ndefault: {\nd\ne}\n}\n\
+            match x where (Y) {\ncase regular /a+/ [m] to k only k > 1: 2\ncase -1, 2: 3\ndefault: 4\n}",
+        );
+        assert_round_trip(
+            "let User {id, name: n, age within [1..3] only age > 1, ...} = u\n\
+            let Option::Some {value} match o\n\
+            for let i = 0 {next i + 1}\nfor let x in xs mix let y in Ys {x}\neach let x in xs (-x)",
+        );
+        assert_round_trip(
+            "pattern function f((a, b), [c, ...d]) = a\n\
+            function max<T> (T a, T b) type T which {T: limit Ordered, Display} {a}\n\
+            trait Shape type (Item, Other = String) {empty function area (Self s) type Int}\n\
+            impl Square trait Shape type Item = Int {function area (Self s) = s.width}\n\
+            union Json {Null, Number {Int value}}\nenum Color {Red, Green}\nalias A = sign (Int x) type Int",
+        );
+    }
+}
@@ -0,0 +1,268 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{fs, time::Instant};
+
+use crate::ast::Program;
+use crate::diagnostic;
+use crate::dump;
+use crate::error::Error;
+use crate::interpreter::Interpreter;
+use crate::lexer;
+use crate::parser;
+use crate::source_map::SourceMap;
+use crate::token::Token;
+use crate::value::Value;
+
+const HELP: &str = "\
+:tokens <code>  show the tokens of the code
+:ast <code>     show the AST of the code as S-expressions
+:type <expr>    evaluate the expression and show the type of its value
+:env            show the global bindings
+:load <path>    load and evaluate a script file
+:reset          clear all bindings
+:time <expr>    evaluate the expression and show the elapsed time
+:help           show this message";
+
+// 交互模式的会话，所有输入共用同一个解释器，所以变量在多次输入之间保留，
+// 每次输入作为一个单独的源文件加入 SourceMap，以便错误信息指向正确的输入
+pub struct Repl {
+    interpreter: Interpreter,
+    source_map: SourceMap,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            interpreter: Interpreter::new(),
+            source_map: SourceMap::new(),
+        }
+    }
+
+    // 执行一次（可能是多行的）输入，返回需要显示的文本，值为 `()` 时返回空字符串，
+    // 出错时返回渲染之后的错误信息，出错之前已经定义的变量仍然保留。
+    // 以冒号开始的输入为命令，比如 `:ast 1 + 2`
+    pub fn eval(&mut self, text: &str) -> Result<String, String> {
+        match split_command(text) {
+            Some((command, argument)) => self.run_command(command, argument),
+            None => {
+                let value = self.eval_source(&self.next_name(), text)?;
+                Ok(if value.is_unit() {
+                    String::new()
+                } else {
+                    value.to_string()
+                })
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: &str, argument: &str) -> Result<String, String> {
+        match command {
+            "tokens" => {
+                let (_, tokens) = self.tokenize(&self.next_name(), argument)?;
+                let lines: Vec<String> = tokens
+                    .iter()
+                    .map(|token| {
+                        format!(
+                            "{}..{} {:?}",
+                            token.location.start, token.location.end, token.token_type
+                        )
+                    })
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            "ast" => {
+                let program = self.parse(&self.next_name(), argument)?;
+                Ok(dump::to_s_expression(&program))
+            }
+            "type" => {
+                let value = self.eval_source(&self.next_name(), argument)?;
+                Ok(value.type_name().to_owned())
+            }
+            "env" => {
+                let lines: Vec<String> = self
+                    .interpreter
+                    .globals()
+                    .iter()
+                    .map(|(name, value)| format!("{} = {}", name, value))
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            "load" => {
+                let path = argument.trim();
+                let text = fs::read_to_string(path)
+                    .map_err(|e| format!("failed to read file {}: {}", path, e))?;
+                let value = self.eval_source(path, &text)?;
+                Ok(if value.is_unit() {
+                    String::new()
+                } else {
+                    value.to_string()
+                })
+            }
+            "reset" => {
+                *self = Repl::new();
+                Ok(String::new())
+            }
+            "time" => {
+                let start = Instant::now();
+                let value = self.eval_source(&self.next_name(), argument)?;
+                Ok(format!("{}\ntime: {:?}", value, start.elapsed()))
+            }
+            "help" => Ok(HELP.to_owned()),
+            _ => Err(format!(
+                "unknown command \":{}\", type :help for the list of commands",
+                command
+            )),
+        }
+    }
+
+    fn next_name(&self) -> String {
+        format!("<repl:{}>", self.source_map.len() + 1)
+    }
+
+    fn tokenize(&mut self, name: &str, text: &str) -> Result<(usize, Vec<Token>), String> {
+        let file_id = self.source_map.add_file(name, text);
+        match lexer::tokenize_file(text, file_id) {
+            Ok(tokens) => Ok((file_id, tokens)),
+            Err(e) => Err(self.render(&[e], file_id)),
+        }
+    }
+
+    fn parse(&mut self, name: &str, text: &str) -> Result<Program, String> {
+        let (file_id, tokens) = self.tokenize(name, text)?;
+        let (program, errors) = parser::parse_with_recovery(&tokens);
+        if errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(self.render(&errors, file_id))
+        }
+    }
+
+    fn eval_source(&mut self, name: &str, text: &str) -> Result<Value, String> {
+        let program = self.parse(name, text)?;
+        self.interpreter
+            .eval_program(&program)
+            .map_err(|e| self.render(&[e], self.source_map.len() - 1))
+    }
+
+    fn render(&self, errors: &[Error], file_id: usize) -> String {
+        let messages: Vec<String> = errors
+            .iter()
+            .map(|error| diagnostic::render(error, &self.source_map, file_id))
+            .collect();
+        messages.join("\n\n")
+    }
+}
+
+// 判断输入是否需要继续读取下一行，除了语法上的未完成（参见 parser::is_incomplete）之外，
+// 未闭合的块注释也需要继续读取，其他词法错误则交给 eval 报告。
+// 命令只检查其参数，而且只有参数为代码的命令才可能需要继续读取
+pub fn is_incomplete(text: &str) -> bool {
+    let code = match split_command(text) {
+        Some(("tokens" | "ast" | "type" | "time", argument)) => argument,
+        Some(_) => return false,
+        None => text,
+    };
+
+    match lexer::tokenize(code) {
+        Ok(tokens) => parser::is_incomplete(&tokens),
+        Err(Error::LexerError(message, _)) => message == "unterminated block comment",
+        Err(_) => false,
+    }
+}
+
+// 把 `:name argument` 分割为命令名称和参数
+fn split_command(text: &str) -> Option<(&str, &str)> {
+    let command = text.trim_start().strip_prefix(':')?;
+    match command.find(char::is_whitespace) {
+        Some(index) => Some((&command[..index], command[index..].trim_start())),
+        None => Some((command.trim_end(), "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_incomplete, Repl};
+
+    #[test]
+    fn test_eval() {
+        let mut repl = Repl::new();
+        assert_eq!(repl.eval("1 + 2"), Ok("3".to_owned()));
+        assert_eq!(repl.eval("let a = 10"), Ok("".to_owned()));
+        assert_eq!(repl.eval("let inc = fn(x) {\n x + 1\n}"), Ok("".to_owned()));
+        assert_eq!(repl.eval("inc(a)"), Ok("11".to_owned()));
+
+        // 出错之后会话仍然可用
+        assert_eq!(
+            repl.eval("a / 0"),
+            Err("runtime error: division by zero\n \
+                --> <repl:5>:1:1\n  \
+                |\n\
+                1 | a / 0\n  \
+                | ^^^^^"
+                .to_owned())
+        );
+        assert!(repl.eval("let b = )").is_err());
+        assert_eq!(repl.eval("inc(inc(a))"), Ok("12".to_owned()));
+    }
+
+    #[test]
+    fn test_command() {
+        let mut repl = Repl::new();
+        assert_eq!(
+            repl.eval(":tokens let a = 'x'"),
+            Ok("0..3 Let\n4..5 Identifier(\"a\")\n6..7 Assign\n8..11 Char('x')".to_owned())
+        );
+        assert_eq!(
+            repl.eval(":ast 1 + a"),
+            Ok("(Program ((Binary \"+\" (Integer 1) (Identifier \"a\"))))".to_owned())
+        );
+        assert!(repl.eval(":ast 1 +").is_err());
+
+        assert_eq!(repl.eval(":type [1]"), Ok("List".to_owned()));
+        assert_eq!(repl.eval(":type fn(a) {a}"), Ok("Function".to_owned()));
+
+        repl.eval("let b = 2\nlet a = (1, \"x\")").unwrap();
+        assert_eq!(repl.eval(":env"), Ok("a = (1, \"x\")\nb = 2".to_owned()));
+
+        let result = repl.eval(":time b * 3").unwrap();
+        assert!(result.starts_with("6\ntime: "));
+
+        assert_eq!(
+            repl.eval(":load examples/04-fib.toy\n"),
+            Ok("34".to_owned())
+        );
+        assert_eq!(repl.eval(":type fib"), Ok("Function".to_owned()));
+        assert!(repl.eval(":load no_such_file.toy").is_err());
+
+        assert_eq!(repl.eval(":reset"), Ok("".to_owned()));
+        assert_eq!(repl.eval(":env"), Ok("".to_owned()));
+        assert!(repl.eval("b").is_err());
+
+        assert!(repl.eval(":help").unwrap().contains(":tokens"));
+        assert!(repl.eval(":foo").is_err());
+    }
+
+    #[test]
+    fn test_is_incomplete() {
+        assert!(!is_incomplete("1 + 2"));
+        assert!(!is_incomplete("\"abc"));
+        assert!(is_incomplete("let f = fn(a) {"));
+        assert!(is_incomplete("let a ="));
+        assert!(is_incomplete("1 *"));
+        assert!(is_incomplete("/* comment"));
+        assert!(is_incomplete(":ast fn(a) {"));
+        assert!(!is_incomplete(":env"));
+        assert!(!is_incomplete(":load a/b.toy"));
+    }
+}
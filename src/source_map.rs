@@ -0,0 +1,111 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// 已加载的源文件，Location 的 file_id 即源文件在 SourceMap 里的编号，
+// start 和 end 是字符（而不是字节）的偏移量
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+    // 每一行第一个字符的偏移量
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 返回新文件的 file_id
+    pub fn add_file(&mut self, name: &str, text: &str) -> usize {
+        self.files.push(SourceFile::new(name, text));
+        self.files.len() - 1
+    }
+
+    pub fn get(&self, file_id: usize) -> Option<&SourceFile> {
+        self.files.get(file_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl SourceFile {
+    fn new(name: &str, text: &str) -> Self {
+        let mut line_starts = vec![0];
+        for (offset, c) in text.chars().enumerate() {
+            if c == '\n' {
+                line_starts.push(offset + 1);
+            }
+        }
+
+        SourceFile {
+            name: name.to_owned(),
+            text: text.to_owned(),
+            line_starts,
+        }
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    // 返回偏移量所在的行号和列号，均从 1 开始
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let index = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        (index + 1, offset - self.line_starts[index] + 1)
+    }
+
+    // 返回指定行（从 1 开始）的文本，不包括换行符
+    pub fn line(&self, line_number: usize) -> &str {
+        self.text
+            .split('\n')
+            .nth(line_number - 1)
+            .unwrap_or("")
+            .trim_end_matches('\r')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceMap;
+
+    #[test]
+    fn test_source_map() {
+        let mut source_map = SourceMap::new();
+        assert_eq!(source_map.add_file("a.toy", "let a = 1\r\n中文 + b\n"), 0);
+        assert_eq!(source_map.add_file("b.toy", ""), 1);
+
+        let file = source_map.get(0).unwrap();
+        assert_eq!(file.name, "a.toy");
+        assert_eq!(file.line_column(0), (1, 1));
+        assert_eq!(file.line_column(4), (1, 5));
+        assert_eq!(file.line_column(11), (2, 1));
+        assert_eq!(file.line_column(14), (2, 4));
+        assert_eq!(file.line_column(18), (3, 1));
+        assert_eq!(file.line(1), "let a = 1");
+        assert_eq!(file.line(2), "中文 + b");
+        assert_eq!(file.line(3), "");
+
+        assert_eq!(source_map.get(1).unwrap().line_column(0), (1, 1));
+        assert!(source_map.get(2).is_none());
+    }
+}
@@ -36,7 +36,6 @@ pub enum TokenType {
     //
     // 符号名称参考
     // https://en.wikipedia.org/wiki/List_of_typographical_symbols_and_punctuation_marks
-
     LeftBrace,  // {
     RightBrace, // }
 
@@ -80,11 +79,12 @@ pub enum TokenType {
     RightParen, // )
 
     // 其他符号
-    Hash,     // #
-    Range,    // ..
-    Ellipsis, // ...
-    Colon,    // :
-    Comma,    // ,
+    Hash,          // #
+    Range,         // ..
+    Ellipsis,      // ...
+    Colon,         // :
+    PathSeparator, // ::
+    Comma,         // ,
 
     // 关键字
     Let,
@@ -139,7 +139,7 @@ impl fmt::Display for TokenType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             // TokenType::EOF => write!(f, "EOF"),
-            TokenType::NewLine => write!(f, "\n"),
+            TokenType::NewLine => writeln!(f),
 
             TokenType::Identifier(value) => write!(f, "{}", value),
 
@@ -198,6 +198,7 @@ impl fmt::Display for TokenType {
             TokenType::Range => write!(f, ".."),     // ..
             TokenType::Ellipsis => write!(f, "..."), // ...
             TokenType::Colon => write!(f, ":"),      // :
+            TokenType::PathSeparator => write!(f, "::"), // ::
             TokenType::Comma => write!(f, ","),      // ,
 
             // 关键字
@@ -0,0 +1,374 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use std::{cell::RefCell, cmp::Ordering, collections::BTreeMap, fmt, rc::Rc};
+
+use crate::ast::escape;
+use crate::ast::Expression;
+use crate::ast::Parameter;
+use crate::environment::Environment;
+
+// 运行时的值，聚合类型使用 Rc 共享，所以复制值的开销很小，
+// 值是不可变的，修改聚合类型的值（比如 push）总是产生新的值
+#[derive(Debug, Clone)]
+pub enum Value {
+    Integer(i64),
+    Boolean(bool),
+    Char(char),
+    String(Rc<String>),
+    Tuple(Rc<Vec<Value>>),
+    List(Rc<Vec<Value>>),
+    Array(Rc<Vec<Value>>),
+    Matrix(Rc<Matrix>),
+    Map(Rc<BTreeMap<String, Value>>),
+    Struct(Rc<StructValue>),
+    Union(Rc<UnionValue>),
+    Enum(Rc<EnumValue>),
+    Range(i64, i64),
+    Function(Rc<Function>),
+    Builtin(&'static str),
+}
+
+// 按行存储的矩阵
+#[derive(Debug, PartialEq)]
+pub struct Matrix {
+    pub rows: usize,
+    pub columns: usize,
+    pub elements: Vec<Value>,
+}
+
+// 成员的顺序跟结构体声明的顺序一致
+#[derive(Debug, PartialEq)]
+pub struct StructValue {
+    pub name: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnionValue {
+    pub name: String,
+    pub member: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+// index 为成员在枚举声明里的序号，用于比较大小
+#[derive(Debug, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub member: String,
+    pub index: usize,
+}
+
+// 闭包，environment 为函数定义时所在的环境
+pub struct Function {
+    pub name: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub body: Expression,
+    pub environment: Rc<RefCell<Environment>>,
+}
+
+impl Value {
+    // 空元组 `()` 作为没有返回值的表达式（比如 let 语句、没有 else 的 if）的值
+    pub fn unit() -> Value {
+        Value::Tuple(Rc::new(vec![]))
+    }
+
+    pub fn is_unit(&self) -> bool {
+        matches!(self, Value::Tuple(elements) if elements.is_empty())
+    }
+
+    pub fn string(value: &str) -> Value {
+        Value::String(Rc::new(value.to_owned()))
+    }
+
+    // 内置的联合体 `Option {Some {value}, None}` 和 `Result {Ok {value}, Error {error}}`，
+    // 内置函数 parse_int 使用 Result 返回可能失败的结果，first、last 和 rest 使用 Option 表示空列表
+    pub fn some(value: Value) -> Value {
+        new_union("Option", "Some", vec![("value", value)])
+    }
+
+    pub fn none() -> Value {
+        new_union("Option", "None", vec![])
+    }
+
+    pub fn ok(value: Value) -> Value {
+        new_union("Result", "Ok", vec![("value", value)])
+    }
+
+    pub fn error(message: &str) -> Value {
+        new_union("Result", "Error", vec![("error", Value::string(message))])
+    }
+
+    // Some 和 Ok 返回 Some(Ok(value))，None 和 Error 返回 Some(Err(self))，
+    // 其他值返回 None
+    pub fn unwrap(&self) -> Option<Result<Value, Value>> {
+        match self {
+            Value::Union(value) => match (value.name.as_str(), value.member.as_str()) {
+                ("Option", "Some") | ("Result", "Ok") => Some(Ok(value.fields.first()?.1.clone())),
+                ("Option", "None") | ("Result", "Error") => Some(Err(self.clone())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // 结构体、联合体和枚举返回声明的名称
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Integer(_) => "Int".to_owned(),
+            Value::Boolean(_) => "Boolean".to_owned(),
+            Value::Char(_) => "Char".to_owned(),
+            Value::String(_) => "String".to_owned(),
+            Value::Tuple(_) => "Tuple".to_owned(),
+            Value::List(_) => "List".to_owned(),
+            Value::Array(_) => "Array".to_owned(),
+            Value::Matrix(_) => "Matrix".to_owned(),
+            Value::Map(_) => "Map".to_owned(),
+            Value::Struct(value) => value.name.clone(),
+            Value::Union(value) => value.name.clone(),
+            Value::Enum(value) => value.name.clone(),
+            Value::Range(..) => "Range".to_owned(),
+            Value::Function(_) | Value::Builtin(_) => "Function".to_owned(),
+        }
+    }
+}
+
+// 环境里面通常包含函数自身，所以 Debug 不输出环境，避免无限递归
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .finish_non_exhaustive()
+    }
+}
+
+// 结构相等，函数只跟自身相等
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => left == right,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Char(left), Value::Char(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Tuple(left), Value::Tuple(right)) => left == right,
+            (Value::List(left), Value::List(right)) => left == right,
+            (Value::Array(left), Value::Array(right)) => left == right,
+            (Value::Matrix(left), Value::Matrix(right)) => left == right,
+            (Value::Map(left), Value::Map(right)) => left == right,
+            (Value::Struct(left), Value::Struct(right)) => left == right,
+            (Value::Union(left), Value::Union(right)) => left == right,
+            (Value::Enum(left), Value::Enum(right)) => left == right,
+            (Value::Range(left_start, left_end), Value::Range(right_start, right_end)) => {
+                left_start == right_start && left_end == right_end
+            }
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::Builtin(left), Value::Builtin(right)) => left == right,
+            _ => false,
+        }
+    }
+}
+
+// 只有整数、布尔、字符、字符串、同一个枚举的成员，以及由这些值组成的
+// 元组、列表和数组（按字典序）可以比较大小，其他值返回 None
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => left.partial_cmp(right),
+            (Value::Boolean(left), Value::Boolean(right)) => left.partial_cmp(right),
+            (Value::Char(left), Value::Char(right)) => left.partial_cmp(right),
+            (Value::String(left), Value::String(right)) => left.partial_cmp(right),
+            (Value::Tuple(left), Value::Tuple(right))
+            | (Value::List(left), Value::List(right))
+            | (Value::Array(left), Value::Array(right)) => left.partial_cmp(right),
+            (Value::Enum(left), Value::Enum(right)) if left.name == right.name => {
+                left.index.partial_cmp(&right.index)
+            }
+            _ => None,
+        }
+    }
+}
+
+// 输出 toy 字面量形式的文本，函数没有字面量，输出为 `<function name>`
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "'{}'", escape(&value.to_string(), '\'')),
+            Value::String(value) => write!(f, "\"{}\"", escape(value, '"')),
+            Value::Tuple(elements) => match elements.as_slice() {
+                [element] => write!(f, "({},)", element),
+                _ => write!(f, "({})", join(elements)),
+            },
+            Value::List(elements) => write!(f, "[{}]", join(elements)),
+            Value::Array(elements) => write!(f, "#[{}]", join(elements)),
+            Value::Matrix(matrix) => {
+                let rows: Vec<String> = matrix
+                    .elements
+                    .chunks(matrix.columns.max(1))
+                    .map(join)
+                    .collect();
+                write!(f, "![{}]", rows.join("; "))
+            }
+            Value::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Value::Struct(value) => write!(f, "{} {}", value.name, join_fields(&value.fields)),
+            Value::Union(value) if value.fields.is_empty() => {
+                write!(f, "{}::{}", value.name, value.member)
+            }
+            Value::Union(value) => write!(
+                f,
+                "{}::{} {}",
+                value.name,
+                value.member,
+                join_fields(&value.fields)
+            ),
+            Value::Enum(value) => write!(f, "{}::{}", value.name, value.member),
+            Value::Range(start, end) => write!(f, "[{}..{}]", start, end),
+            Value::Function(function) => match &function.name {
+                Some(name) => write!(f, "<function {}>", name),
+                None => write!(f, "<function>"),
+            },
+            Value::Builtin(name) => write!(f, "<function {}>", name),
+        }
+    }
+}
+
+fn new_union(name: &str, member: &str, fields: Vec<(&str, Value)>) -> Value {
+    Value::Union(Rc::new(UnionValue {
+        name: name.to_owned(),
+        member: member.to_owned(),
+        fields: fields
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+    }))
+}
+
+fn join(values: &[Value]) -> String {
+    let texts: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    texts.join(", ")
+}
+
+fn join_fields(fields: &[(String, Value)]) -> String {
+    let texts: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+    format!("{{{}}}", texts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cmp::Ordering, collections::BTreeMap, rc::Rc};
+
+    use super::{EnumValue, Matrix, StructValue, UnionValue, Value};
+
+    fn list(values: Vec<Value>) -> Value {
+        Value::List(Rc::new(values))
+    }
+
+    fn color(member: &str, index: usize) -> Value {
+        Value::Enum(Rc::new(EnumValue {
+            name: "Color".to_owned(),
+            member: member.to_owned(),
+            index,
+        }))
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Value::Integer(-1).to_string(), "-1");
+        assert_eq!(Value::Char('\'').to_string(), "'\\''");
+        assert_eq!(Value::string("a\"b\n").to_string(), "\"a\\\"b\\n\"");
+        assert_eq!(Value::unit().to_string(), "()");
+        assert_eq!(
+            Value::Tuple(Rc::new(vec![Value::Integer(1)])).to_string(),
+            "(1,)"
+        );
+        assert_eq!(
+            list(vec![Value::Integer(1), Value::Boolean(true)]).to_string(),
+            "[1, true]"
+        );
+        assert_eq!(
+            Value::Array(Rc::new(vec![Value::Integer(1)])).to_string(),
+            "#[1]"
+        );
+        assert_eq!(
+            Value::Matrix(Rc::new(Matrix {
+                rows: 2,
+                columns: 2,
+                elements: (1..=4).map(Value::Integer).collect(),
+            }))
+            .to_string(),
+            "![1, 2; 3, 4]"
+        );
+
+        let mut entries = BTreeMap::new();
+        entries.insert("b".to_owned(), Value::Integer(2));
+        entries.insert("a".to_owned(), Value::Integer(1));
+        assert_eq!(Value::Map(Rc::new(entries)).to_string(), "{a: 1, b: 2}");
+
+        assert_eq!(
+            Value::Struct(Rc::new(StructValue {
+                name: "User".to_owned(),
+                fields: vec![
+                    ("id".to_owned(), Value::Integer(1)),
+                    ("name".to_owned(), Value::string("foo"))
+                ],
+            }))
+            .to_string(),
+            "User {id: 1, name: \"foo\"}"
+        );
+        assert_eq!(
+            Value::Union(Rc::new(UnionValue {
+                name: "Shape".to_owned(),
+                member: "Circle".to_owned(),
+                fields: vec![("radius".to_owned(), Value::Integer(1))],
+            }))
+            .to_string(),
+            "Shape::Circle {radius: 1}"
+        );
+        assert_eq!(
+            Value::Union(Rc::new(UnionValue {
+                name: "Shape".to_owned(),
+                member: "Empty".to_owned(),
+                fields: vec![],
+            }))
+            .to_string(),
+            "Shape::Empty"
+        );
+        assert_eq!(color("Red", 0).to_string(), "Color::Red");
+        assert_eq!(Value::Range(1, 10).to_string(), "[1..10]");
+        assert_eq!(Value::Builtin("len").to_string(), "<function len>");
+    }
+
+    #[test]
+    fn test_equality_and_ordering() {
+        let a = list(vec![Value::Integer(1), Value::string("a")]);
+        let b = list(vec![Value::Integer(1), Value::string("a")]);
+        assert_eq!(a, b);
+        assert_ne!(a, Value::Array(Rc::new(vec![Value::Integer(1)])));
+        assert_ne!(Value::Integer(1), Value::Char('1'));
+
+        assert!(Value::Integer(1) < Value::Integer(2));
+        assert!(Value::string("abc") < Value::string("abd"));
+        assert!(list(vec![Value::Integer(1)]) < list(vec![Value::Integer(1), Value::Integer(0)]));
+        assert!(color("Red", 0) < color("Blue", 2));
+        assert_eq!(Value::Integer(1).partial_cmp(&Value::string("1")), None);
+        assert_eq!(
+            Value::Range(1, 2).partial_cmp(&Value::Range(1, 2)),
+            None::<Ordering>
+        );
+    }
+}
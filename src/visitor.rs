@@ -0,0 +1,1909 @@
+/**
+ * Copyright (c) 2022 Hemashushu <hippospark@gmail.com>, All rights reserved.
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+use crate::ast::AliasDeclaration;
+use crate::ast::AnonymousFunction;
+use crate::ast::AssociatedType;
+use crate::ast::BinaryExpression;
+use crate::ast::BlockExpression;
+use crate::ast::BranchCase;
+use crate::ast::BranchExpression;
+use crate::ast::CallExpression;
+use crate::ast::ConstDeclaration;
+use crate::ast::ConstMember;
+use crate::ast::ConstValue;
+use crate::ast::DataType;
+use crate::ast::EachExpression;
+use crate::ast::EnumDeclaration;
+use crate::ast::ErrorNode;
+use crate::ast::Expression;
+use crate::ast::FieldDeclaration;
+use crate::ast::FieldPattern;
+use crate::ast::FieldValue;
+use crate::ast::ForInExpression;
+use crate::ast::ForLetExpression;
+use crate::ast::FunctionDeclaration;
+use crate::ast::Identifier;
+use crate::ast::IfExpression;
+use crate::ast::ImplDeclaration;
+use crate::ast::InBinding;
+use crate::ast::IndexExpression;
+use crate::ast::KeywordArgument;
+use crate::ast::LetExpression;
+use crate::ast::ListExpression;
+use crate::ast::Literal;
+use crate::ast::MapEntry;
+use crate::ast::MapExpression;
+use crate::ast::MapPair;
+use crate::ast::MatchCase;
+use crate::ast::MatchExpression;
+use crate::ast::MatrixExpression;
+use crate::ast::MemberExpression;
+use crate::ast::NamespaceDeclaration;
+use crate::ast::NextExpression;
+use crate::ast::Parameter;
+use crate::ast::Path;
+use crate::ast::Pattern;
+use crate::ast::PatternClauses;
+use crate::ast::PostfixExpression;
+use crate::ast::PrefixExpression;
+use crate::ast::Program;
+use crate::ast::RangeExpression;
+use crate::ast::RestPattern;
+use crate::ast::SequenceExpression;
+use crate::ast::SliceExpression;
+use crate::ast::SpreadExpression;
+use crate::ast::Statement;
+use crate::ast::StructDeclaration;
+use crate::ast::StructExpression;
+use crate::ast::StructPattern;
+use crate::ast::TraitDeclaration;
+use crate::ast::TupleExpression;
+use crate::ast::TupleIndexExpression;
+use crate::ast::TypeConstraint;
+use crate::ast::UnionDeclaration;
+use crate::ast::UnionMember;
+use crate::ast::UseDeclaration;
+use crate::ast::UseKind;
+use crate::ast::UseTree;
+use crate::ast::WhichEntry;
+
+// 只读遍历 AST，各个 visit 方法的默认实现调用对应的 walk 函数访问子节点，
+// 实现者只需覆盖关心的节点，并在需要继续访问子节点时调用对应的 walk 函数
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program)
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_function_declaration(&mut self, function: &FunctionDeclaration) {
+        walk_function_declaration(self, function)
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter) {
+        walk_parameter(self, parameter)
+    }
+
+    fn visit_which_entry(&mut self, entry: &WhichEntry) {
+        walk_which_entry(self, entry)
+    }
+
+    fn visit_trait_declaration(&mut self, declaration: &TraitDeclaration) {
+        walk_trait_declaration(self, declaration)
+    }
+
+    fn visit_impl_declaration(&mut self, declaration: &ImplDeclaration) {
+        walk_impl_declaration(self, declaration)
+    }
+
+    fn visit_associated_type(&mut self, associated_type: &AssociatedType) {
+        walk_associated_type(self, associated_type)
+    }
+
+    fn visit_namespace_declaration(&mut self, declaration: &NamespaceDeclaration) {
+        walk_namespace_declaration(self, declaration)
+    }
+
+    fn visit_use_declaration(&mut self, declaration: &UseDeclaration) {
+        walk_use_declaration(self, declaration)
+    }
+
+    fn visit_use_tree(&mut self, tree: &UseTree) {
+        walk_use_tree(self, tree)
+    }
+
+    fn visit_alias_declaration(&mut self, declaration: &AliasDeclaration) {
+        walk_alias_declaration(self, declaration)
+    }
+
+    fn visit_struct_declaration(&mut self, declaration: &StructDeclaration) {
+        walk_struct_declaration(self, declaration)
+    }
+
+    fn visit_field_declaration(&mut self, field: &FieldDeclaration) {
+        walk_field_declaration(self, field)
+    }
+
+    fn visit_union_declaration(&mut self, declaration: &UnionDeclaration) {
+        walk_union_declaration(self, declaration)
+    }
+
+    fn visit_union_member(&mut self, member: &UnionMember) {
+        walk_union_member(self, member)
+    }
+
+    fn visit_enum_declaration(&mut self, declaration: &EnumDeclaration) {
+        walk_enum_declaration(self, declaration)
+    }
+
+    fn visit_const_declaration(&mut self, declaration: &ConstDeclaration) {
+        walk_const_declaration(self, declaration)
+    }
+
+    fn visit_const_member(&mut self, member: &ConstMember) {
+        walk_const_member(self, member)
+    }
+
+    fn visit_error_node(&mut self, _node: &ErrorNode) {}
+
+    fn visit_identifier(&mut self, _identifier: &Identifier) {}
+
+    fn visit_literal(&mut self, _literal: &Literal) {}
+
+    fn visit_data_type(&mut self, data_type: &DataType) {
+        walk_data_type(self, data_type)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+
+    fn visit_path(&mut self, path: &Path) {
+        walk_path(self, path)
+    }
+
+    fn visit_tuple_expression(&mut self, expression: &TupleExpression) {
+        walk_tuple_expression(self, expression)
+    }
+
+    fn visit_list_expression(&mut self, expression: &ListExpression) {
+        walk_list_expression(self, expression)
+    }
+
+    fn visit_array_expression(&mut self, expression: &ListExpression) {
+        walk_list_expression(self, expression)
+    }
+
+    fn visit_matrix_expression(&mut self, expression: &MatrixExpression) {
+        walk_matrix_expression(self, expression)
+    }
+
+    fn visit_map_expression(&mut self, expression: &MapExpression) {
+        walk_map_expression(self, expression)
+    }
+
+    fn visit_map_pair(&mut self, pair: &MapPair) {
+        walk_map_pair(self, pair)
+    }
+
+    fn visit_range_expression(&mut self, expression: &RangeExpression) {
+        walk_range_expression(self, expression)
+    }
+
+    fn visit_sequence_expression(&mut self, expression: &SequenceExpression) {
+        walk_sequence_expression(self, expression)
+    }
+
+    fn visit_spread_expression(&mut self, expression: &SpreadExpression) {
+        walk_spread_expression(self, expression)
+    }
+
+    fn visit_block_expression(&mut self, expression: &BlockExpression) {
+        walk_block_expression(self, expression)
+    }
+
+    fn visit_let_expression(&mut self, expression: &LetExpression) {
+        walk_let_expression(self, expression)
+    }
+
+    fn visit_if_expression(&mut self, expression: &IfExpression) {
+        walk_if_expression(self, expression)
+    }
+
+    fn visit_branch_expression(&mut self, expression: &BranchExpression) {
+        walk_branch_expression(self, expression)
+    }
+
+    fn visit_branch_case(&mut self, case: &BranchCase) {
+        walk_branch_case(self, case)
+    }
+
+    fn visit_match_expression(&mut self, expression: &MatchExpression) {
+        walk_match_expression(self, expression)
+    }
+
+    fn visit_match_case(&mut self, case: &MatchCase) {
+        walk_match_case(self, case)
+    }
+
+    fn visit_pattern_clauses(&mut self, clauses: &PatternClauses) {
+        walk_pattern_clauses(self, clauses)
+    }
+
+    fn visit_for_let_expression(&mut self, expression: &ForLetExpression) {
+        walk_for_let_expression(self, expression)
+    }
+
+    fn visit_for_in_expression(&mut self, expression: &ForInExpression) {
+        walk_for_in_expression(self, expression)
+    }
+
+    fn visit_each_expression(&mut self, expression: &EachExpression) {
+        walk_each_expression(self, expression)
+    }
+
+    fn visit_in_binding(&mut self, binding: &InBinding) {
+        walk_in_binding(self, binding)
+    }
+
+    fn visit_next_expression(&mut self, expression: &NextExpression) {
+        walk_next_expression(self, expression)
+    }
+
+    fn visit_anonymous_function(&mut self, function: &AnonymousFunction) {
+        walk_anonymous_function(self, function)
+    }
+
+    fn visit_struct_expression(&mut self, expression: &StructExpression) {
+        walk_struct_expression(self, expression)
+    }
+
+    fn visit_field_value(&mut self, field: &FieldValue) {
+        walk_field_value(self, field)
+    }
+
+    fn visit_prefix_expression(&mut self, expression: &PrefixExpression) {
+        walk_prefix_expression(self, expression)
+    }
+
+    fn visit_postfix_expression(&mut self, expression: &PostfixExpression) {
+        walk_postfix_expression(self, expression)
+    }
+
+    fn visit_binary_expression(&mut self, expression: &BinaryExpression) {
+        walk_binary_expression(self, expression)
+    }
+
+    fn visit_call_expression(&mut self, expression: &CallExpression) {
+        walk_call_expression(self, expression)
+    }
+
+    fn visit_keyword_argument(&mut self, argument: &KeywordArgument) {
+        walk_keyword_argument(self, argument)
+    }
+
+    fn visit_member_expression(&mut self, expression: &MemberExpression) {
+        walk_member_expression(self, expression)
+    }
+
+    fn visit_tuple_index_expression(&mut self, expression: &TupleIndexExpression) {
+        walk_tuple_index_expression(self, expression)
+    }
+
+    fn visit_index_expression(&mut self, expression: &IndexExpression) {
+        walk_index_expression(self, expression)
+    }
+
+    fn visit_slice_expression(&mut self, expression: &SliceExpression) {
+        walk_slice_expression(self, expression)
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern)
+    }
+
+    fn visit_struct_pattern(&mut self, pattern: &StructPattern) {
+        walk_struct_pattern(self, pattern)
+    }
+
+    fn visit_field_pattern(&mut self, pattern: &FieldPattern) {
+        walk_field_pattern(self, pattern)
+    }
+
+    fn visit_rest_pattern(&mut self, pattern: &RestPattern) {
+        walk_rest_pattern(self, pattern)
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for statement in &program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Expression(s) => visitor.visit_expression(s),
+        Statement::Function(s) => visitor.visit_function_declaration(s),
+        Statement::Struct(s) => visitor.visit_struct_declaration(s),
+        Statement::Union(s) => visitor.visit_union_declaration(s),
+        Statement::Enum(s) => visitor.visit_enum_declaration(s),
+        Statement::Const(s) => visitor.visit_const_declaration(s),
+        Statement::Trait(s) => visitor.visit_trait_declaration(s),
+        Statement::Impl(s) => visitor.visit_impl_declaration(s),
+        Statement::Namespace(s) => visitor.visit_namespace_declaration(s),
+        Statement::Use(s) => visitor.visit_use_declaration(s),
+        Statement::Alias(s) => visitor.visit_alias_declaration(s),
+        Statement::Error(s) => visitor.visit_error_node(s),
+    }
+}
+
+pub fn walk_function_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    function: &FunctionDeclaration,
+) {
+    visitor.visit_identifier(&function.name);
+    for parameter in &function.generic_parameters {
+        visitor.visit_identifier(parameter);
+    }
+    for parameter in &function.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    if let Some(return_type) = &function.return_type {
+        visitor.visit_data_type(return_type);
+    }
+    for entry in &function.which {
+        visitor.visit_which_entry(entry);
+    }
+    if let Some(body) = &function.body {
+        visitor.visit_expression(body);
+    }
+}
+
+pub fn walk_parameter<V: Visitor + ?Sized>(visitor: &mut V, parameter: &Parameter) {
+    if let Some(data_type) = &parameter.data_type {
+        visitor.visit_data_type(data_type);
+    }
+    visitor.visit_pattern(&parameter.pattern);
+    if let Some(default_value) = &parameter.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_which_entry<V: Visitor + ?Sized>(visitor: &mut V, entry: &WhichEntry) {
+    visitor.visit_identifier(&entry.name);
+    match &entry.constraint {
+        TypeConstraint::DataType(data_type) => visitor.visit_data_type(data_type),
+        TypeConstraint::Limit(traits) => {
+            for data_type in traits {
+                visitor.visit_data_type(data_type);
+            }
+        }
+    }
+}
+
+pub fn walk_trait_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &TraitDeclaration,
+) {
+    visitor.visit_identifier(&declaration.name);
+    for associated_type in &declaration.associated_types {
+        visitor.visit_associated_type(associated_type);
+    }
+    for function in &declaration.functions {
+        visitor.visit_function_declaration(function);
+    }
+}
+
+pub fn walk_impl_declaration<V: Visitor + ?Sized>(visitor: &mut V, declaration: &ImplDeclaration) {
+    visitor.visit_data_type(&declaration.data_type);
+    if let Some(trait_name) = &declaration.trait_name {
+        visitor.visit_data_type(trait_name);
+    }
+    for associated_type in &declaration.associated_types {
+        visitor.visit_associated_type(associated_type);
+    }
+    for function in &declaration.functions {
+        visitor.visit_function_declaration(function);
+    }
+}
+
+pub fn walk_associated_type<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    associated_type: &AssociatedType,
+) {
+    visitor.visit_identifier(&associated_type.name);
+    if let Some(data_type) = &associated_type.data_type {
+        visitor.visit_data_type(data_type);
+    }
+}
+
+pub fn walk_namespace_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &NamespaceDeclaration,
+) {
+    visitor.visit_identifier(&declaration.name);
+    for statement in &declaration.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_use_declaration<V: Visitor + ?Sized>(visitor: &mut V, declaration: &UseDeclaration) {
+    visitor.visit_use_tree(&declaration.tree);
+}
+
+pub fn walk_use_tree<V: Visitor + ?Sized>(visitor: &mut V, tree: &UseTree) {
+    for segment in &tree.path {
+        visitor.visit_identifier(segment);
+    }
+    if let UseKind::Group(items) = &tree.kind {
+        for item in items {
+            visitor.visit_use_tree(item);
+        }
+    }
+}
+
+pub fn walk_alias_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &AliasDeclaration,
+) {
+    visitor.visit_identifier(&declaration.name);
+    visitor.visit_data_type(&declaration.data_type);
+}
+
+pub fn walk_struct_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &StructDeclaration,
+) {
+    visitor.visit_identifier(&declaration.name);
+    for field in &declaration.fields {
+        visitor.visit_field_declaration(field);
+    }
+}
+
+pub fn walk_field_declaration<V: Visitor + ?Sized>(visitor: &mut V, field: &FieldDeclaration) {
+    visitor.visit_data_type(&field.data_type);
+    visitor.visit_identifier(&field.name);
+}
+
+pub fn walk_union_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &UnionDeclaration,
+) {
+    visitor.visit_identifier(&declaration.name);
+    for member in &declaration.members {
+        visitor.visit_union_member(member);
+    }
+}
+
+pub fn walk_union_member<V: Visitor + ?Sized>(visitor: &mut V, member: &UnionMember) {
+    visitor.visit_identifier(&member.name);
+    for field in &member.fields {
+        visitor.visit_field_declaration(field);
+    }
+}
+
+pub fn walk_enum_declaration<V: Visitor + ?Sized>(visitor: &mut V, declaration: &EnumDeclaration) {
+    visitor.visit_identifier(&declaration.name);
+    for member in &declaration.members {
+        visitor.visit_identifier(member);
+    }
+}
+
+pub fn walk_const_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &ConstDeclaration,
+) {
+    visitor.visit_data_type(&declaration.data_type);
+    visitor.visit_identifier(&declaration.name);
+    match &declaration.value {
+        ConstValue::Single(value) => visitor.visit_expression(value),
+        ConstValue::Group(members) => {
+            for member in members {
+                visitor.visit_const_member(member);
+            }
+        }
+    }
+}
+
+pub fn walk_const_member<V: Visitor + ?Sized>(visitor: &mut V, member: &ConstMember) {
+    visitor.visit_identifier(&member.name);
+    visitor.visit_expression(&member.value);
+}
+
+pub fn walk_data_type<V: Visitor + ?Sized>(visitor: &mut V, data_type: &DataType) {
+    match data_type {
+        DataType::Named(t) => {
+            visitor.visit_identifier(&t.name);
+            for argument in &t.generic_arguments {
+                visitor.visit_data_type(argument);
+            }
+        }
+        DataType::Sign(t) => {
+            for parameter in &t.parameters {
+                visitor.visit_data_type(&parameter.data_type);
+                if let Some(name) = &parameter.name {
+                    visitor.visit_identifier(name);
+                }
+            }
+            if let Some(return_type) = &t.return_type {
+                visitor.visit_data_type(return_type);
+            }
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Literal(e) => visitor.visit_literal(e),
+        Expression::Identifier(e) => visitor.visit_identifier(e),
+        Expression::Path(e) => visitor.visit_path(e),
+        Expression::Tuple(e) => visitor.visit_tuple_expression(e),
+        Expression::List(e) => visitor.visit_list_expression(e),
+        Expression::Array(e) => visitor.visit_array_expression(e),
+        Expression::Matrix(e) => visitor.visit_matrix_expression(e),
+        Expression::Map(e) => visitor.visit_map_expression(e),
+        Expression::Range(e) => visitor.visit_range_expression(e),
+        Expression::Sequence(e) => visitor.visit_sequence_expression(e),
+        Expression::Spread(e) => visitor.visit_spread_expression(e),
+        Expression::Block(e) => visitor.visit_block_expression(e),
+        Expression::Let(e) => visitor.visit_let_expression(e),
+        Expression::If(e) => visitor.visit_if_expression(e),
+        Expression::Branch(e) => visitor.visit_branch_expression(e),
+        Expression::Match(e) => visitor.visit_match_expression(e),
+        Expression::ForLet(e) => visitor.visit_for_let_expression(e),
+        Expression::ForIn(e) => visitor.visit_for_in_expression(e),
+        Expression::Each(e) => visitor.visit_each_expression(e),
+        Expression::Next(e) => visitor.visit_next_expression(e),
+        Expression::AnonymousFunction(e) => visitor.visit_anonymous_function(e),
+        Expression::Struct(e) => visitor.visit_struct_expression(e),
+        Expression::Prefix(e) => visitor.visit_prefix_expression(e),
+        Expression::Postfix(e) => visitor.visit_postfix_expression(e),
+        Expression::Binary(e) => visitor.visit_binary_expression(e),
+        Expression::Call(e) => visitor.visit_call_expression(e),
+        Expression::Member(e) => visitor.visit_member_expression(e),
+        Expression::TupleIndex(e) => visitor.visit_tuple_index_expression(e),
+        Expression::Index(e) => visitor.visit_index_expression(e),
+        Expression::Slice(e) => visitor.visit_slice_expression(e),
+        Expression::Error(e) => visitor.visit_error_node(e),
+    }
+}
+
+pub fn walk_path<V: Visitor + ?Sized>(visitor: &mut V, path: &Path) {
+    for segment in &path.segments {
+        visitor.visit_identifier(segment);
+    }
+}
+
+pub fn walk_tuple_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &TupleExpression) {
+    for element in &expression.elements {
+        visitor.visit_expression(element);
+    }
+}
+
+// 列表和数组共用
+pub fn walk_list_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &ListExpression) {
+    for element in &expression.elements {
+        visitor.visit_expression(element);
+    }
+}
+
+pub fn walk_matrix_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &MatrixExpression) {
+    for row in &expression.rows {
+        for element in row {
+            visitor.visit_expression(element);
+        }
+    }
+}
+
+pub fn walk_map_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &MapExpression) {
+    for entry in &expression.entries {
+        match entry {
+            MapEntry::Pair(pair) => visitor.visit_map_pair(pair),
+            MapEntry::Spread(spread) => visitor.visit_spread_expression(spread),
+        }
+    }
+}
+
+pub fn walk_map_pair<V: Visitor + ?Sized>(visitor: &mut V, pair: &MapPair) {
+    visitor.visit_identifier(&pair.name);
+    visitor.visit_expression(&pair.value);
+}
+
+pub fn walk_range_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &RangeExpression) {
+    visitor.visit_expression(&expression.start);
+    visitor.visit_expression(&expression.end);
+}
+
+pub fn walk_sequence_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &SequenceExpression,
+) {
+    for element in &expression.elements {
+        visitor.visit_expression(element);
+    }
+    visitor.visit_expression(&expression.end);
+}
+
+pub fn walk_spread_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &SpreadExpression) {
+    visitor.visit_expression(&expression.value);
+}
+
+pub fn walk_block_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &BlockExpression) {
+    for expression in &expression.expressions {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_let_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &LetExpression) {
+    visitor.visit_pattern(&expression.pattern);
+    visitor.visit_expression(&expression.value);
+}
+
+pub fn walk_if_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &IfExpression) {
+    visitor.visit_expression(&expression.condition);
+    if let Some(where_clause) = &expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    visitor.visit_expression(&expression.consequent);
+    if let Some(alternative) = &expression.alternative {
+        visitor.visit_expression(alternative);
+    }
+}
+
+pub fn walk_branch_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &BranchExpression) {
+    if let Some(where_clause) = &expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    for case in &expression.cases {
+        visitor.visit_branch_case(case);
+    }
+    if let Some(default) = &expression.default {
+        visitor.visit_expression(default);
+    }
+}
+
+pub fn walk_branch_case<V: Visitor + ?Sized>(visitor: &mut V, case: &BranchCase) {
+    visitor.visit_expression(&case.condition);
+    if let Some(where_clause) = &case.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    visitor.visit_expression(&case.body);
+}
+
+pub fn walk_match_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &MatchExpression) {
+    visitor.visit_expression(&expression.subject);
+    if let Some(where_clause) = &expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    for case in &expression.cases {
+        visitor.visit_match_case(case);
+    }
+    if let Some(default) = &expression.default {
+        visitor.visit_expression(default);
+    }
+}
+
+pub fn walk_match_case<V: Visitor + ?Sized>(visitor: &mut V, case: &MatchCase) {
+    for pattern in &case.patterns {
+        visitor.visit_pattern(pattern);
+    }
+    visitor.visit_pattern_clauses(&case.clauses);
+    visitor.visit_expression(&case.body);
+}
+
+pub fn walk_pattern_clauses<V: Visitor + ?Sized>(visitor: &mut V, clauses: &PatternClauses) {
+    if let Some(to) = &clauses.to {
+        visitor.visit_identifier(to);
+    }
+    if let Some(only) = &clauses.only {
+        visitor.visit_expression(only);
+    }
+    if let Some(where_clause) = &clauses.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+}
+
+pub fn walk_for_let_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &ForLetExpression,
+) {
+    visitor.visit_pattern(&expression.pattern);
+    visitor.visit_expression(&expression.initial);
+    visitor.visit_expression(&expression.body);
+}
+
+pub fn walk_for_in_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &ForInExpression) {
+    for binding in &expression.bindings {
+        visitor.visit_in_binding(binding);
+    }
+    visitor.visit_expression(&expression.body);
+}
+
+pub fn walk_each_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &EachExpression) {
+    for binding in &expression.bindings {
+        visitor.visit_in_binding(binding);
+    }
+    visitor.visit_expression(&expression.body);
+}
+
+pub fn walk_in_binding<V: Visitor + ?Sized>(visitor: &mut V, binding: &InBinding) {
+    visitor.visit_pattern(&binding.pattern);
+    visitor.visit_expression(&binding.iterable);
+}
+
+pub fn walk_next_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &NextExpression) {
+    visitor.visit_expression(&expression.value);
+}
+
+pub fn walk_anonymous_function<V: Visitor + ?Sized>(visitor: &mut V, function: &AnonymousFunction) {
+    for parameter in &function.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    if let Some(return_type) = &function.return_type {
+        visitor.visit_data_type(return_type);
+    }
+    visitor.visit_expression(&function.body);
+}
+
+pub fn walk_struct_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &StructExpression) {
+    if let Some(name) = &expression.name {
+        visitor.visit_identifier(name);
+    }
+    for field in &expression.fields {
+        visitor.visit_field_value(field);
+    }
+}
+
+pub fn walk_field_value<V: Visitor + ?Sized>(visitor: &mut V, field: &FieldValue) {
+    if let Some(name) = &field.name {
+        visitor.visit_identifier(name);
+    }
+    visitor.visit_expression(&field.value);
+}
+
+pub fn walk_prefix_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &PrefixExpression) {
+    visitor.visit_expression(&expression.operand);
+}
+
+pub fn walk_postfix_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &PostfixExpression,
+) {
+    visitor.visit_expression(&expression.operand);
+}
+
+pub fn walk_binary_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &BinaryExpression) {
+    visitor.visit_expression(&expression.left);
+    visitor.visit_expression(&expression.right);
+}
+
+pub fn walk_call_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &CallExpression) {
+    visitor.visit_expression(&expression.callee);
+    for argument in &expression.generic_arguments {
+        visitor.visit_data_type(argument);
+    }
+    for argument in &expression.arguments {
+        visitor.visit_expression(argument);
+    }
+    for argument in &expression.keyword_arguments {
+        visitor.visit_keyword_argument(argument);
+    }
+}
+
+pub fn walk_keyword_argument<V: Visitor + ?Sized>(visitor: &mut V, argument: &KeywordArgument) {
+    visitor.visit_identifier(&argument.name);
+    visitor.visit_expression(&argument.value);
+}
+
+pub fn walk_member_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &MemberExpression) {
+    visitor.visit_expression(&expression.object);
+    visitor.visit_identifier(&expression.property);
+}
+
+pub fn walk_tuple_index_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &TupleIndexExpression,
+) {
+    visitor.visit_expression(&expression.object);
+}
+
+pub fn walk_index_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &IndexExpression) {
+    visitor.visit_expression(&expression.object);
+    visitor.visit_expression(&expression.index);
+}
+
+pub fn walk_slice_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &SliceExpression) {
+    visitor.visit_expression(&expression.object);
+    if let Some(start) = &expression.start {
+        visitor.visit_expression(start);
+    }
+    if let Some(end) = &expression.end {
+        visitor.visit_expression(end);
+    }
+}
+
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Template(_) => {}
+        Pattern::Identifier(p) => visitor.visit_identifier(p),
+        Pattern::Literal(p) => visitor.visit_literal(p),
+        Pattern::Tuple(p) => {
+            for element in &p.elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::List(p) | Pattern::Array(p) => {
+            for element in &p.elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::Struct(p) => visitor.visit_struct_pattern(p),
+        Pattern::Rest(p) => visitor.visit_rest_pattern(p),
+        Pattern::Into(p) => {
+            visitor.visit_data_type(&p.data_type);
+            visitor.visit_identifier(&p.name);
+        }
+        Pattern::Regular(p) => {
+            visitor.visit_expression(&p.regexp);
+            for name in &p.names {
+                visitor.visit_identifier(name);
+            }
+        }
+        Pattern::Within(p) => visitor.visit_expression(&p.range),
+    }
+}
+
+pub fn walk_struct_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &StructPattern) {
+    if let Some(name) = &pattern.name {
+        visitor.visit_path(name);
+    }
+    for field in &pattern.fields {
+        visitor.visit_field_pattern(field);
+    }
+    if let Some(rest) = &pattern.rest {
+        visitor.visit_rest_pattern(rest);
+    }
+}
+
+pub fn walk_field_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &FieldPattern) {
+    visitor.visit_identifier(&pattern.name);
+    if let Some(sub_pattern) = &pattern.pattern {
+        visitor.visit_pattern(sub_pattern);
+    }
+    visitor.visit_pattern_clauses(&pattern.clauses);
+}
+
+pub fn walk_rest_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &RestPattern) {
+    if let Some(name) = &pattern.name {
+        visitor.visit_identifier(name);
+    }
+}
+
+// 可变遍历 AST，方法名与 Visitor 相同，walk 函数名以 _mut 结尾，
+// 实现者可以在 visit 方法里原地修改或者整个替换节点（比如常量折叠、重命名等变换）
+pub trait VisitorMut {
+    fn visit_program(&mut self, program: &mut Program) {
+        walk_program_mut(self, program)
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_function_declaration(&mut self, function: &mut FunctionDeclaration) {
+        walk_function_declaration_mut(self, function)
+    }
+
+    fn visit_parameter(&mut self, parameter: &mut Parameter) {
+        walk_parameter_mut(self, parameter)
+    }
+
+    fn visit_which_entry(&mut self, entry: &mut WhichEntry) {
+        walk_which_entry_mut(self, entry)
+    }
+
+    fn visit_trait_declaration(&mut self, declaration: &mut TraitDeclaration) {
+        walk_trait_declaration_mut(self, declaration)
+    }
+
+    fn visit_impl_declaration(&mut self, declaration: &mut ImplDeclaration) {
+        walk_impl_declaration_mut(self, declaration)
+    }
+
+    fn visit_associated_type(&mut self, associated_type: &mut AssociatedType) {
+        walk_associated_type_mut(self, associated_type)
+    }
+
+    fn visit_namespace_declaration(&mut self, declaration: &mut NamespaceDeclaration) {
+        walk_namespace_declaration_mut(self, declaration)
+    }
+
+    fn visit_use_declaration(&mut self, declaration: &mut UseDeclaration) {
+        walk_use_declaration_mut(self, declaration)
+    }
+
+    fn visit_use_tree(&mut self, tree: &mut UseTree) {
+        walk_use_tree_mut(self, tree)
+    }
+
+    fn visit_alias_declaration(&mut self, declaration: &mut AliasDeclaration) {
+        walk_alias_declaration_mut(self, declaration)
+    }
+
+    fn visit_struct_declaration(&mut self, declaration: &mut StructDeclaration) {
+        walk_struct_declaration_mut(self, declaration)
+    }
+
+    fn visit_field_declaration(&mut self, field: &mut FieldDeclaration) {
+        walk_field_declaration_mut(self, field)
+    }
+
+    fn visit_union_declaration(&mut self, declaration: &mut UnionDeclaration) {
+        walk_union_declaration_mut(self, declaration)
+    }
+
+    fn visit_union_member(&mut self, member: &mut UnionMember) {
+        walk_union_member_mut(self, member)
+    }
+
+    fn visit_enum_declaration(&mut self, declaration: &mut EnumDeclaration) {
+        walk_enum_declaration_mut(self, declaration)
+    }
+
+    fn visit_const_declaration(&mut self, declaration: &mut ConstDeclaration) {
+        walk_const_declaration_mut(self, declaration)
+    }
+
+    fn visit_const_member(&mut self, member: &mut ConstMember) {
+        walk_const_member_mut(self, member)
+    }
+
+    fn visit_error_node(&mut self, _node: &mut ErrorNode) {}
+
+    fn visit_identifier(&mut self, _identifier: &mut Identifier) {}
+
+    fn visit_literal(&mut self, _literal: &mut Literal) {}
+
+    fn visit_data_type(&mut self, data_type: &mut DataType) {
+        walk_data_type_mut(self, data_type)
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+
+    fn visit_path(&mut self, path: &mut Path) {
+        walk_path_mut(self, path)
+    }
+
+    fn visit_tuple_expression(&mut self, expression: &mut TupleExpression) {
+        walk_tuple_expression_mut(self, expression)
+    }
+
+    fn visit_list_expression(&mut self, expression: &mut ListExpression) {
+        walk_list_expression_mut(self, expression)
+    }
+
+    fn visit_array_expression(&mut self, expression: &mut ListExpression) {
+        walk_list_expression_mut(self, expression)
+    }
+
+    fn visit_matrix_expression(&mut self, expression: &mut MatrixExpression) {
+        walk_matrix_expression_mut(self, expression)
+    }
+
+    fn visit_map_expression(&mut self, expression: &mut MapExpression) {
+        walk_map_expression_mut(self, expression)
+    }
+
+    fn visit_map_pair(&mut self, pair: &mut MapPair) {
+        walk_map_pair_mut(self, pair)
+    }
+
+    fn visit_range_expression(&mut self, expression: &mut RangeExpression) {
+        walk_range_expression_mut(self, expression)
+    }
+
+    fn visit_sequence_expression(&mut self, expression: &mut SequenceExpression) {
+        walk_sequence_expression_mut(self, expression)
+    }
+
+    fn visit_spread_expression(&mut self, expression: &mut SpreadExpression) {
+        walk_spread_expression_mut(self, expression)
+    }
+
+    fn visit_block_expression(&mut self, expression: &mut BlockExpression) {
+        walk_block_expression_mut(self, expression)
+    }
+
+    fn visit_let_expression(&mut self, expression: &mut LetExpression) {
+        walk_let_expression_mut(self, expression)
+    }
+
+    fn visit_if_expression(&mut self, expression: &mut IfExpression) {
+        walk_if_expression_mut(self, expression)
+    }
+
+    fn visit_branch_expression(&mut self, expression: &mut BranchExpression) {
+        walk_branch_expression_mut(self, expression)
+    }
+
+    fn visit_branch_case(&mut self, case: &mut BranchCase) {
+        walk_branch_case_mut(self, case)
+    }
+
+    fn visit_match_expression(&mut self, expression: &mut MatchExpression) {
+        walk_match_expression_mut(self, expression)
+    }
+
+    fn visit_match_case(&mut self, case: &mut MatchCase) {
+        walk_match_case_mut(self, case)
+    }
+
+    fn visit_pattern_clauses(&mut self, clauses: &mut PatternClauses) {
+        walk_pattern_clauses_mut(self, clauses)
+    }
+
+    fn visit_for_let_expression(&mut self, expression: &mut ForLetExpression) {
+        walk_for_let_expression_mut(self, expression)
+    }
+
+    fn visit_for_in_expression(&mut self, expression: &mut ForInExpression) {
+        walk_for_in_expression_mut(self, expression)
+    }
+
+    fn visit_each_expression(&mut self, expression: &mut EachExpression) {
+        walk_each_expression_mut(self, expression)
+    }
+
+    fn visit_in_binding(&mut self, binding: &mut InBinding) {
+        walk_in_binding_mut(self, binding)
+    }
+
+    fn visit_next_expression(&mut self, expression: &mut NextExpression) {
+        walk_next_expression_mut(self, expression)
+    }
+
+    fn visit_anonymous_function(&mut self, function: &mut AnonymousFunction) {
+        walk_anonymous_function_mut(self, function)
+    }
+
+    fn visit_struct_expression(&mut self, expression: &mut StructExpression) {
+        walk_struct_expression_mut(self, expression)
+    }
+
+    fn visit_field_value(&mut self, field: &mut FieldValue) {
+        walk_field_value_mut(self, field)
+    }
+
+    fn visit_prefix_expression(&mut self, expression: &mut PrefixExpression) {
+        walk_prefix_expression_mut(self, expression)
+    }
+
+    fn visit_postfix_expression(&mut self, expression: &mut PostfixExpression) {
+        walk_postfix_expression_mut(self, expression)
+    }
+
+    fn visit_binary_expression(&mut self, expression: &mut BinaryExpression) {
+        walk_binary_expression_mut(self, expression)
+    }
+
+    fn visit_call_expression(&mut self, expression: &mut CallExpression) {
+        walk_call_expression_mut(self, expression)
+    }
+
+    fn visit_keyword_argument(&mut self, argument: &mut KeywordArgument) {
+        walk_keyword_argument_mut(self, argument)
+    }
+
+    fn visit_member_expression(&mut self, expression: &mut MemberExpression) {
+        walk_member_expression_mut(self, expression)
+    }
+
+    fn visit_tuple_index_expression(&mut self, expression: &mut TupleIndexExpression) {
+        walk_tuple_index_expression_mut(self, expression)
+    }
+
+    fn visit_index_expression(&mut self, expression: &mut IndexExpression) {
+        walk_index_expression_mut(self, expression)
+    }
+
+    fn visit_slice_expression(&mut self, expression: &mut SliceExpression) {
+        walk_slice_expression_mut(self, expression)
+    }
+
+    fn visit_pattern(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern)
+    }
+
+    fn visit_struct_pattern(&mut self, pattern: &mut StructPattern) {
+        walk_struct_pattern_mut(self, pattern)
+    }
+
+    fn visit_field_pattern(&mut self, pattern: &mut FieldPattern) {
+        walk_field_pattern_mut(self, pattern)
+    }
+
+    fn visit_rest_pattern(&mut self, pattern: &mut RestPattern) {
+        walk_rest_pattern_mut(self, pattern)
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for statement in &mut program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Expression(s) => visitor.visit_expression(s),
+        Statement::Function(s) => visitor.visit_function_declaration(s),
+        Statement::Struct(s) => visitor.visit_struct_declaration(s),
+        Statement::Union(s) => visitor.visit_union_declaration(s),
+        Statement::Enum(s) => visitor.visit_enum_declaration(s),
+        Statement::Const(s) => visitor.visit_const_declaration(s),
+        Statement::Trait(s) => visitor.visit_trait_declaration(s),
+        Statement::Impl(s) => visitor.visit_impl_declaration(s),
+        Statement::Namespace(s) => visitor.visit_namespace_declaration(s),
+        Statement::Use(s) => visitor.visit_use_declaration(s),
+        Statement::Alias(s) => visitor.visit_alias_declaration(s),
+        Statement::Error(s) => visitor.visit_error_node(s),
+    }
+}
+
+pub fn walk_function_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    function: &mut FunctionDeclaration,
+) {
+    visitor.visit_identifier(&mut function.name);
+    for parameter in &mut function.generic_parameters {
+        visitor.visit_identifier(parameter);
+    }
+    for parameter in &mut function.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    if let Some(return_type) = &mut function.return_type {
+        visitor.visit_data_type(return_type);
+    }
+    for entry in &mut function.which {
+        visitor.visit_which_entry(entry);
+    }
+    if let Some(body) = &mut function.body {
+        visitor.visit_expression(body);
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, parameter: &mut Parameter) {
+    if let Some(data_type) = &mut parameter.data_type {
+        visitor.visit_data_type(data_type);
+    }
+    visitor.visit_pattern(&mut parameter.pattern);
+    if let Some(default_value) = &mut parameter.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_which_entry_mut<V: VisitorMut + ?Sized>(visitor: &mut V, entry: &mut WhichEntry) {
+    visitor.visit_identifier(&mut entry.name);
+    match &mut entry.constraint {
+        TypeConstraint::DataType(data_type) => visitor.visit_data_type(data_type),
+        TypeConstraint::Limit(traits) => {
+            for data_type in traits {
+                visitor.visit_data_type(data_type);
+            }
+        }
+    }
+}
+
+pub fn walk_trait_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut TraitDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    for associated_type in &mut declaration.associated_types {
+        visitor.visit_associated_type(associated_type);
+    }
+    for function in &mut declaration.functions {
+        visitor.visit_function_declaration(function);
+    }
+}
+
+pub fn walk_impl_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut ImplDeclaration,
+) {
+    visitor.visit_data_type(&mut declaration.data_type);
+    if let Some(trait_name) = &mut declaration.trait_name {
+        visitor.visit_data_type(trait_name);
+    }
+    for associated_type in &mut declaration.associated_types {
+        visitor.visit_associated_type(associated_type);
+    }
+    for function in &mut declaration.functions {
+        visitor.visit_function_declaration(function);
+    }
+}
+
+pub fn walk_associated_type_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    associated_type: &mut AssociatedType,
+) {
+    visitor.visit_identifier(&mut associated_type.name);
+    if let Some(data_type) = &mut associated_type.data_type {
+        visitor.visit_data_type(data_type);
+    }
+}
+
+pub fn walk_namespace_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut NamespaceDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    for statement in &mut declaration.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_use_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut UseDeclaration,
+) {
+    visitor.visit_use_tree(&mut declaration.tree);
+}
+
+pub fn walk_use_tree_mut<V: VisitorMut + ?Sized>(visitor: &mut V, tree: &mut UseTree) {
+    for segment in &mut tree.path {
+        visitor.visit_identifier(segment);
+    }
+    if let UseKind::Group(items) = &mut tree.kind {
+        for item in items {
+            visitor.visit_use_tree(item);
+        }
+    }
+}
+
+pub fn walk_alias_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut AliasDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    visitor.visit_data_type(&mut declaration.data_type);
+}
+
+pub fn walk_struct_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut StructDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    for field in &mut declaration.fields {
+        visitor.visit_field_declaration(field);
+    }
+}
+
+pub fn walk_field_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    field: &mut FieldDeclaration,
+) {
+    visitor.visit_data_type(&mut field.data_type);
+    visitor.visit_identifier(&mut field.name);
+}
+
+pub fn walk_union_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut UnionDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    for member in &mut declaration.members {
+        visitor.visit_union_member(member);
+    }
+}
+
+pub fn walk_union_member_mut<V: VisitorMut + ?Sized>(visitor: &mut V, member: &mut UnionMember) {
+    visitor.visit_identifier(&mut member.name);
+    for field in &mut member.fields {
+        visitor.visit_field_declaration(field);
+    }
+}
+
+pub fn walk_enum_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut EnumDeclaration,
+) {
+    visitor.visit_identifier(&mut declaration.name);
+    for member in &mut declaration.members {
+        visitor.visit_identifier(member);
+    }
+}
+
+pub fn walk_const_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut ConstDeclaration,
+) {
+    visitor.visit_data_type(&mut declaration.data_type);
+    visitor.visit_identifier(&mut declaration.name);
+    match &mut declaration.value {
+        ConstValue::Single(value) => visitor.visit_expression(value),
+        ConstValue::Group(members) => {
+            for member in members {
+                visitor.visit_const_member(member);
+            }
+        }
+    }
+}
+
+pub fn walk_const_member_mut<V: VisitorMut + ?Sized>(visitor: &mut V, member: &mut ConstMember) {
+    visitor.visit_identifier(&mut member.name);
+    visitor.visit_expression(&mut member.value);
+}
+
+pub fn walk_data_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, data_type: &mut DataType) {
+    match data_type {
+        DataType::Named(t) => {
+            visitor.visit_identifier(&mut t.name);
+            for argument in &mut t.generic_arguments {
+                visitor.visit_data_type(argument);
+            }
+        }
+        DataType::Sign(t) => {
+            for parameter in &mut t.parameters {
+                visitor.visit_data_type(&mut parameter.data_type);
+                if let Some(name) = &mut parameter.name {
+                    visitor.visit_identifier(name);
+                }
+            }
+            if let Some(return_type) = &mut t.return_type {
+                visitor.visit_data_type(return_type);
+            }
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Literal(e) => visitor.visit_literal(e),
+        Expression::Identifier(e) => visitor.visit_identifier(e),
+        Expression::Path(e) => visitor.visit_path(e),
+        Expression::Tuple(e) => visitor.visit_tuple_expression(e),
+        Expression::List(e) => visitor.visit_list_expression(e),
+        Expression::Array(e) => visitor.visit_array_expression(e),
+        Expression::Matrix(e) => visitor.visit_matrix_expression(e),
+        Expression::Map(e) => visitor.visit_map_expression(e),
+        Expression::Range(e) => visitor.visit_range_expression(e),
+        Expression::Sequence(e) => visitor.visit_sequence_expression(e),
+        Expression::Spread(e) => visitor.visit_spread_expression(e),
+        Expression::Block(e) => visitor.visit_block_expression(e),
+        Expression::Let(e) => visitor.visit_let_expression(e),
+        Expression::If(e) => visitor.visit_if_expression(e),
+        Expression::Branch(e) => visitor.visit_branch_expression(e),
+        Expression::Match(e) => visitor.visit_match_expression(e),
+        Expression::ForLet(e) => visitor.visit_for_let_expression(e),
+        Expression::ForIn(e) => visitor.visit_for_in_expression(e),
+        Expression::Each(e) => visitor.visit_each_expression(e),
+        Expression::Next(e) => visitor.visit_next_expression(e),
+        Expression::AnonymousFunction(e) => visitor.visit_anonymous_function(e),
+        Expression::Struct(e) => visitor.visit_struct_expression(e),
+        Expression::Prefix(e) => visitor.visit_prefix_expression(e),
+        Expression::Postfix(e) => visitor.visit_postfix_expression(e),
+        Expression::Binary(e) => visitor.visit_binary_expression(e),
+        Expression::Call(e) => visitor.visit_call_expression(e),
+        Expression::Member(e) => visitor.visit_member_expression(e),
+        Expression::TupleIndex(e) => visitor.visit_tuple_index_expression(e),
+        Expression::Index(e) => visitor.visit_index_expression(e),
+        Expression::Slice(e) => visitor.visit_slice_expression(e),
+        Expression::Error(e) => visitor.visit_error_node(e),
+    }
+}
+
+pub fn walk_path_mut<V: VisitorMut + ?Sized>(visitor: &mut V, path: &mut Path) {
+    for segment in &mut path.segments {
+        visitor.visit_identifier(segment);
+    }
+}
+
+pub fn walk_tuple_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut TupleExpression,
+) {
+    for element in &mut expression.elements {
+        visitor.visit_expression(element);
+    }
+}
+
+pub fn walk_list_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ListExpression,
+) {
+    for element in &mut expression.elements {
+        visitor.visit_expression(element);
+    }
+}
+
+pub fn walk_matrix_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut MatrixExpression,
+) {
+    for row in &mut expression.rows {
+        for element in row {
+            visitor.visit_expression(element);
+        }
+    }
+}
+
+pub fn walk_map_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut MapExpression,
+) {
+    for entry in &mut expression.entries {
+        match entry {
+            MapEntry::Pair(pair) => visitor.visit_map_pair(pair),
+            MapEntry::Spread(spread) => visitor.visit_spread_expression(spread),
+        }
+    }
+}
+
+pub fn walk_map_pair_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pair: &mut MapPair) {
+    visitor.visit_identifier(&mut pair.name);
+    visitor.visit_expression(&mut pair.value);
+}
+
+pub fn walk_range_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut RangeExpression,
+) {
+    visitor.visit_expression(&mut expression.start);
+    visitor.visit_expression(&mut expression.end);
+}
+
+pub fn walk_sequence_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut SequenceExpression,
+) {
+    for element in &mut expression.elements {
+        visitor.visit_expression(element);
+    }
+    visitor.visit_expression(&mut expression.end);
+}
+
+pub fn walk_spread_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut SpreadExpression,
+) {
+    visitor.visit_expression(&mut expression.value);
+}
+
+pub fn walk_block_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut BlockExpression,
+) {
+    for expression in &mut expression.expressions {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_let_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut LetExpression,
+) {
+    visitor.visit_pattern(&mut expression.pattern);
+    visitor.visit_expression(&mut expression.value);
+}
+
+pub fn walk_if_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut IfExpression,
+) {
+    visitor.visit_expression(&mut expression.condition);
+    if let Some(where_clause) = &mut expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    visitor.visit_expression(&mut expression.consequent);
+    if let Some(alternative) = &mut expression.alternative {
+        visitor.visit_expression(alternative);
+    }
+}
+
+pub fn walk_branch_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut BranchExpression,
+) {
+    if let Some(where_clause) = &mut expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    for case in &mut expression.cases {
+        visitor.visit_branch_case(case);
+    }
+    if let Some(default) = &mut expression.default {
+        visitor.visit_expression(default);
+    }
+}
+
+pub fn walk_branch_case_mut<V: VisitorMut + ?Sized>(visitor: &mut V, case: &mut BranchCase) {
+    visitor.visit_expression(&mut case.condition);
+    if let Some(where_clause) = &mut case.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    visitor.visit_expression(&mut case.body);
+}
+
+pub fn walk_match_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut MatchExpression,
+) {
+    visitor.visit_expression(&mut expression.subject);
+    if let Some(where_clause) = &mut expression.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+    for case in &mut expression.cases {
+        visitor.visit_match_case(case);
+    }
+    if let Some(default) = &mut expression.default {
+        visitor.visit_expression(default);
+    }
+}
+
+pub fn walk_match_case_mut<V: VisitorMut + ?Sized>(visitor: &mut V, case: &mut MatchCase) {
+    for pattern in &mut case.patterns {
+        visitor.visit_pattern(pattern);
+    }
+    visitor.visit_pattern_clauses(&mut case.clauses);
+    visitor.visit_expression(&mut case.body);
+}
+
+pub fn walk_pattern_clauses_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    clauses: &mut PatternClauses,
+) {
+    if let Some(to) = &mut clauses.to {
+        visitor.visit_identifier(to);
+    }
+    if let Some(only) = &mut clauses.only {
+        visitor.visit_expression(only);
+    }
+    if let Some(where_clause) = &mut clauses.where_clause {
+        visitor.visit_expression(where_clause);
+    }
+}
+
+pub fn walk_for_let_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ForLetExpression,
+) {
+    visitor.visit_pattern(&mut expression.pattern);
+    visitor.visit_expression(&mut expression.initial);
+    visitor.visit_expression(&mut expression.body);
+}
+
+pub fn walk_for_in_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ForInExpression,
+) {
+    for binding in &mut expression.bindings {
+        visitor.visit_in_binding(binding);
+    }
+    visitor.visit_expression(&mut expression.body);
+}
+
+pub fn walk_each_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut EachExpression,
+) {
+    for binding in &mut expression.bindings {
+        visitor.visit_in_binding(binding);
+    }
+    visitor.visit_expression(&mut expression.body);
+}
+
+pub fn walk_in_binding_mut<V: VisitorMut + ?Sized>(visitor: &mut V, binding: &mut InBinding) {
+    visitor.visit_pattern(&mut binding.pattern);
+    visitor.visit_expression(&mut binding.iterable);
+}
+
+pub fn walk_next_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut NextExpression,
+) {
+    visitor.visit_expression(&mut expression.value);
+}
+
+pub fn walk_anonymous_function_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    function: &mut AnonymousFunction,
+) {
+    for parameter in &mut function.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    if let Some(return_type) = &mut function.return_type {
+        visitor.visit_data_type(return_type);
+    }
+    visitor.visit_expression(&mut function.body);
+}
+
+pub fn walk_struct_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut StructExpression,
+) {
+    if let Some(name) = &mut expression.name {
+        visitor.visit_identifier(name);
+    }
+    for field in &mut expression.fields {
+        visitor.visit_field_value(field);
+    }
+}
+
+pub fn walk_field_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, field: &mut FieldValue) {
+    if let Some(name) = &mut field.name {
+        visitor.visit_identifier(name);
+    }
+    visitor.visit_expression(&mut field.value);
+}
+
+pub fn walk_prefix_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut PrefixExpression,
+) {
+    visitor.visit_expression(&mut expression.operand);
+}
+
+pub fn walk_postfix_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut PostfixExpression,
+) {
+    visitor.visit_expression(&mut expression.operand);
+}
+
+pub fn walk_binary_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut BinaryExpression,
+) {
+    visitor.visit_expression(&mut expression.left);
+    visitor.visit_expression(&mut expression.right);
+}
+
+pub fn walk_call_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut CallExpression,
+) {
+    visitor.visit_expression(&mut expression.callee);
+    for argument in &mut expression.generic_arguments {
+        visitor.visit_data_type(argument);
+    }
+    for argument in &mut expression.arguments {
+        visitor.visit_expression(argument);
+    }
+    for argument in &mut expression.keyword_arguments {
+        visitor.visit_keyword_argument(argument);
+    }
+}
+
+pub fn walk_keyword_argument_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    argument: &mut KeywordArgument,
+) {
+    visitor.visit_identifier(&mut argument.name);
+    visitor.visit_expression(&mut argument.value);
+}
+
+pub fn walk_member_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut MemberExpression,
+) {
+    visitor.visit_expression(&mut expression.object);
+    visitor.visit_identifier(&mut expression.property);
+}
+
+pub fn walk_tuple_index_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut TupleIndexExpression,
+) {
+    visitor.visit_expression(&mut expression.object);
+}
+
+pub fn walk_index_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut IndexExpression,
+) {
+    visitor.visit_expression(&mut expression.object);
+    visitor.visit_expression(&mut expression.index);
+}
+
+pub fn walk_slice_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut SliceExpression,
+) {
+    visitor.visit_expression(&mut expression.object);
+    if let Some(start) = &mut expression.start {
+        visitor.visit_expression(start);
+    }
+    if let Some(end) = &mut expression.end {
+        visitor.visit_expression(end);
+    }
+}
+
+pub fn walk_pattern_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Template(_) => {}
+        Pattern::Identifier(p) => visitor.visit_identifier(p),
+        Pattern::Literal(p) => visitor.visit_literal(p),
+        Pattern::Tuple(p) => {
+            for element in &mut p.elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::List(p) | Pattern::Array(p) => {
+            for element in &mut p.elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::Struct(p) => visitor.visit_struct_pattern(p),
+        Pattern::Rest(p) => visitor.visit_rest_pattern(p),
+        Pattern::Into(p) => {
+            visitor.visit_data_type(&mut p.data_type);
+            visitor.visit_identifier(&mut p.name);
+        }
+        Pattern::Regular(p) => {
+            visitor.visit_expression(&mut p.regexp);
+            for name in &mut p.names {
+                visitor.visit_identifier(name);
+            }
+        }
+        Pattern::Within(p) => visitor.visit_expression(&mut p.range),
+    }
+}
+
+pub fn walk_struct_pattern_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    pattern: &mut StructPattern,
+) {
+    if let Some(name) = &mut pattern.name {
+        visitor.visit_path(name);
+    }
+    for field in &mut pattern.fields {
+        visitor.visit_field_pattern(field);
+    }
+    if let Some(rest) = &mut pattern.rest {
+        visitor.visit_rest_pattern(rest);
+    }
+}
+
+pub fn walk_field_pattern_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pattern: &mut FieldPattern) {
+    visitor.visit_identifier(&mut pattern.name);
+    if let Some(sub_pattern) = &mut pattern.pattern {
+        visitor.visit_pattern(sub_pattern);
+    }
+    visitor.visit_pattern_clauses(&mut pattern.clauses);
+}
+
+pub fn walk_rest_pattern_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pattern: &mut RestPattern) {
+    if let Some(name) = &mut pattern.name {
+        visitor.visit_identifier(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::BinaryOperator;
+    use crate::ast::Expression;
+    use crate::ast::Identifier;
+    use crate::ast::Literal;
+    use crate::ast::LiteralValue;
+    use crate::ast::Program;
+    use crate::lexer;
+    use crate::parser;
+
+    use super::walk_expression_mut;
+    use super::Visitor;
+    use super::VisitorMut;
+
+    fn parse_from_str(text: &str) -> Program {
+        let tokens = lexer::tokenize(text).unwrap();
+        parser::parse(&tokens).unwrap()
+    }
+
+    struct IdentifierCollector {
+        names: Vec<String>,
+    }
+
+    impl Visitor for IdentifierCollector {
+        fn visit_identifier(&mut self, identifier: &Identifier) {
+            self.names.push(identifier.name.clone());
+        }
+    }
+
+    fn collect_identifiers(text: &str) -> Vec<String> {
+        let mut collector = IdentifierCollector { names: vec![] };
+        collector.visit_program(&parse_from_str(text));
+        collector.names
+    }
+
+    #[test]
+    fn test_visitor() {
+        assert_eq!(collect_identifiers("a + b * c"), vec!["a", "b", "c"]);
+        assert_eq!(
+            collect_identifiers("let (x, y) = f(z)"),
+            vec!["x", "y", "f", "z"]
+        );
+        assert_eq!(
+            collect_identifiers("function add(Int a, Int b) type Int = a + b"),
+            vec!["add", "Int", "a", "Int", "b", "Int", "a", "b"]
+        );
+        assert_eq!(
+            collect_identifiers(
+                "match p {\n    case {x, y: [first, ...]} only x > 0: first\n    default: 0\n}"
+            ),
+            vec!["p", "x", "y", "first", "x", "first"]
+        );
+    }
+
+    struct Renamer;
+
+    impl VisitorMut for Renamer {
+        fn visit_identifier(&mut self, identifier: &mut Identifier) {
+            identifier.name = identifier.name.to_uppercase();
+        }
+    }
+
+    // 把两个整数字面量的加法和乘法折叠为一个字面量
+    struct ConstantFolder;
+
+    impl VisitorMut for ConstantFolder {
+        fn visit_expression(&mut self, expression: &mut Expression) {
+            walk_expression_mut(self, expression);
+
+            if let Expression::Binary(binary) = expression {
+                if let (
+                    Expression::Literal(Literal {
+                        value: LiteralValue::Integer(left),
+                        ..
+                    }),
+                    Expression::Literal(Literal {
+                        value: LiteralValue::Integer(right),
+                        ..
+                    }),
+                ) = (&binary.left, &binary.right)
+                {
+                    let value = match binary.operator {
+                        BinaryOperator::Add => left + right,
+                        BinaryOperator::Multiply => left * right,
+                        _ => return,
+                    };
+
+                    *expression = Expression::Literal(Literal {
+                        value: LiteralValue::Integer(value),
+                        location: binary.location.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn transform<T: VisitorMut>(visitor: &mut T, text: &str) -> String {
+        let mut program = parse_from_str(text);
+        visitor.visit_program(&mut program);
+        program.to_string()
+    }
+
+    #[test]
+    fn test_visitor_mut() {
+        assert_eq!(transform(&mut Renamer, "foo(bar, 1)"), "FOO(BAR, 1)");
+        assert_eq!(
+            transform(&mut Renamer, "let {a, b: c} = d.e"),
+            "let {A, B: C} = D.E"
+        );
+
+        assert_eq!(transform(&mut ConstantFolder, "1 + 2 * 3"), "7");
+        assert_eq!(transform(&mut ConstantFolder, "x + 2 * 3"), "(x + 6)");
+        assert_eq!(
+            transform(&mut ConstantFolder, "f(1 + 1, [2 * 2])"),
+            "f(2, [4])"
+        );
+    }
+}